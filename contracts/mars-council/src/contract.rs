@@ -1,27 +1,44 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
-    QuerierWrapper, QueryRequest, Response, StdResult, Uint128, WasmMsg, WasmQuery,
+    attr, from_binary, to_binary, to_vec, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Empty,
+    Env, MessageInfo, Order, QuerierWrapper, QueryRequest, Response, StdResult, Uint128, WasmMsg,
+    WasmQuery,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw_storage_plus::{Bound, U64Key};
+use serde::Serialize;
 
 use mars_core::council::error::ContractError;
 use mars_core::error::MarsError;
 use mars_core::helpers::{option_string_to_addr, zero_address};
 use mars_core::math::decimal::Decimal;
+use mars_core::storage::paginate_prefix;
 
 use mars_core::address_provider;
 use mars_core::address_provider::MarsContract;
+use mars_core::red_bank;
 use mars_core::vesting;
 use mars_core::xmars_token;
 
+use crate::attributes::{
+    ACTION, ACTION_ACCEPT_DELEGATION, ACTION_AMEND_PROPOSAL, ACTION_CAST_VOTE,
+    ACTION_DELEGATE_VOTING_POWER, ACTION_END_PROPOSAL, ACTION_EXECUTE_PROPOSAL,
+    ACTION_REVOKE_DELEGATION, ACTION_SUBMIT_PROPOSAL, ACTION_UPDATE_CONFIG, AGAINST_VOTES,
+    DELEGATE, DELEGATOR, DEPOSIT, DEPOSIT_AMOUNT, FOR_VOTES, PROPOSAL_END_HEIGHT, PROPOSAL_ID,
+    PROPOSAL_RESULT, PROPOSAL_START_HEIGHT, SUBMITTER, VOTE, VOTER, VOTING_POWER,
+};
 use crate::msg::{CreateOrUpdateConfig, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg};
-use crate::state::{CONFIG, GLOBAL_STATE, PROPOSALS, PROPOSAL_VOTES};
+use crate::state::{
+    CONFIG, DELEGATIONS, DELEGATORS, GLOBAL_STATE, LAST_PROPOSAL_SUBMISSION_HEIGHTS,
+    PENDING_DELEGATIONS, PROPOSALS, PROPOSAL_DEPOSIT_STATUSES, PROPOSAL_VOTES,
+};
 use crate::{
-    Config, GlobalState, Proposal, ProposalMessage, ProposalStatus, ProposalVote,
-    ProposalVoteOption, ProposalVoteResponse, ProposalVotesResponse, ProposalsListResponse,
+    Config, GlobalState, Proposal, ProposalDepositResponse, ProposalDepositStatus,
+    ProposalDepositsResponse, ProposalKind, ProposalMessage, ProposalMessageParam,
+    ProposalMessageSummary, ProposalMessagesSummaryResponse, ProposalResultResponse,
+    ProposalStatus, ProposalVote, ProposalVoteOption, ProposalVoteResponse, ProposalVotesResponse,
+    ProposalsListResponse, TotalVotingPowerResponse,
 };
 
 // Proposal validation attributes
@@ -32,6 +49,9 @@ const MAX_DESC_LENGTH: usize = 1024;
 const MIN_LINK_LENGTH: usize = 12;
 const MAX_LINK_LENGTH: usize = 128;
 
+// Max delegators whose voting power a delegate aggregates when voting
+const MAX_DELEGATORS_PER_DELEGATE: usize = 20;
+
 // INSTANTIATE
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -46,16 +66,24 @@ pub fn instantiate(
     let CreateOrUpdateConfig {
         address_provider_address,
         proposal_voting_period,
+        proposal_min_voting_period,
+        proposal_max_voting_period,
         proposal_effective_delay,
         proposal_expiration_period,
         proposal_required_deposit,
         proposal_required_quorum,
         proposal_required_threshold,
+        proposal_submission_cooldown,
+        proposal_voting_delay,
+        proposal_max_voter_power_share,
+        proposal_kind_requirements,
     } = msg.config;
 
     // Check required fields are available
     let available = address_provider_address.is_some()
         && proposal_voting_period.is_some()
+        && proposal_min_voting_period.is_some()
+        && proposal_max_voting_period.is_some()
         && proposal_effective_delay.is_some()
         && proposal_expiration_period.is_some()
         && proposal_required_deposit.is_some()
@@ -74,11 +102,20 @@ pub fn instantiate(
             zero_address(),
         )?,
         proposal_voting_period: proposal_voting_period.unwrap(),
+        proposal_min_voting_period: proposal_min_voting_period.unwrap(),
+        proposal_max_voting_period: proposal_max_voting_period.unwrap(),
         proposal_effective_delay: proposal_effective_delay.unwrap(),
         proposal_expiration_period: proposal_expiration_period.unwrap(),
         proposal_required_deposit: proposal_required_deposit.unwrap(),
         proposal_required_quorum: proposal_required_quorum.unwrap(),
         proposal_required_threshold: proposal_required_threshold.unwrap(),
+        // Proposal submissions are not rate limited unless specified
+        proposal_submission_cooldown: proposal_submission_cooldown.unwrap_or(0),
+        // Voting starts on submission unless specified
+        proposal_voting_delay: proposal_voting_delay.unwrap_or(0),
+        // Voting power of a single voter is not capped unless specified
+        proposal_max_voter_power_share: proposal_max_voter_power_share.unwrap_or_else(Decimal::one),
+        proposal_kind_requirements: proposal_kind_requirements.unwrap_or_default(),
     };
 
     // Validate config
@@ -117,7 +154,34 @@ pub fn execute(
             execute_execute_proposal(deps, env, info, proposal_id)
         }
 
+        ExecuteMsg::AmendProposal {
+            proposal_id,
+            title,
+            description,
+            link,
+            messages,
+        } => execute_amend_proposal(
+            deps,
+            env,
+            info,
+            proposal_id,
+            title,
+            description,
+            link,
+            messages,
+        ),
+
         ExecuteMsg::UpdateConfig { config } => execute_update_config(deps, env, info, config),
+
+        ExecuteMsg::DelegateVotingPower { delegate } => {
+            execute_delegate_voting_power(deps, env, info, delegate)
+        }
+
+        ExecuteMsg::AcceptDelegation { delegator } => {
+            execute_accept_delegation(deps, env, info, delegator)
+        }
+
+        ExecuteMsg::RevokeDelegation {} => execute_revoke_delegation(deps, env, info),
     }
 }
 
@@ -133,7 +197,9 @@ pub fn execute_receive_cw20(
             title,
             description,
             link,
+            kind,
             messages,
+            voting_period_override,
         } => execute_submit_proposal(
             deps,
             env,
@@ -143,7 +209,9 @@ pub fn execute_receive_cw20(
             title,
             description,
             link,
+            kind,
             messages,
+            voting_period_override,
         ),
     }
 }
@@ -157,35 +225,32 @@ pub fn execute_submit_proposal(
     title: String,
     description: String,
     option_link: Option<String>,
+    kind: ProposalKind,
     option_messages: Option<Vec<ProposalMessage>>,
+    option_voting_period_override: Option<u64>,
 ) -> Result<Response, ContractError> {
-    // Validate title
-    if title.len() < MIN_TITLE_LENGTH {
-        return Err(ContractError::invalid_proposal("title too short"));
-    }
-    if title.len() > MAX_TITLE_LENGTH {
-        return Err(ContractError::invalid_proposal("title too long"));
-    }
+    validate_proposal_content(&title, &description, &option_link, &kind, &option_messages)?;
 
-    // Validate description
-    if description.len() < MIN_DESC_LENGTH {
-        return Err(ContractError::invalid_proposal("description too short"));
-    }
-    if description.len() > MAX_DESC_LENGTH {
-        return Err(ContractError::invalid_proposal("description too long"));
-    }
+    let config = CONFIG.load(deps.storage)?;
 
-    // Validate Link
-    if let Some(link) = &option_link {
-        if link.len() < MIN_LINK_LENGTH {
-            return Err(ContractError::invalid_proposal("Link too short"));
-        }
-        if link.len() > MAX_LINK_LENGTH {
-            return Err(ContractError::invalid_proposal("Link too long"));
+    // Validate voting period override
+    let voting_period = match option_voting_period_override {
+        Some(voting_period_override) => {
+            if voting_period_override < config.proposal_min_voting_period {
+                return Err(ContractError::invalid_proposal(
+                    "voting period override too short",
+                ));
+            }
+            if voting_period_override > config.proposal_max_voting_period {
+                return Err(ContractError::invalid_proposal(
+                    "voting period override too long",
+                ));
+            }
+            voting_period_override
         }
-    }
+        None => config.proposal_voting_period,
+    };
 
-    let config = CONFIG.load(deps.storage)?;
     let mars_token_address = address_provider::helpers::query_address(
         &deps.querier,
         config.address_provider_address,
@@ -201,19 +266,39 @@ pub fn execute_submit_proposal(
         )));
     }
 
+    let submitter_address = deps.api.addr_validate(&submitter_address_unchecked)?;
+
+    // Rate limit submissions per address
+    if config.proposal_submission_cooldown > 0 {
+        if let Some(last_submission_height) =
+            LAST_PROPOSAL_SUBMISSION_HEIGHTS.may_load(deps.storage, &submitter_address)?
+        {
+            let next_submission_height =
+                last_submission_height.saturating_add(config.proposal_submission_cooldown);
+            if env.block.height < next_submission_height {
+                return Err(ContractError::ProposalSubmissionCooldown {
+                    next_submission_height,
+                });
+            }
+        }
+    }
+    LAST_PROPOSAL_SUBMISSION_HEIGHTS.save(deps.storage, &submitter_address, &env.block.height)?;
+
     // Update proposal totals
     let mut global_state = GLOBAL_STATE.load(deps.storage)?;
     global_state.proposal_count += 1;
     GLOBAL_STATE.save(deps.storage, &global_state)?;
 
+    let start_height = env.block.height + config.proposal_voting_delay;
     let new_proposal = Proposal {
         proposal_id: global_state.proposal_count,
-        submitter_address: deps.api.addr_validate(&submitter_address_unchecked)?,
+        submitter_address,
         status: ProposalStatus::Active,
+        kind,
         for_votes: Uint128::zero(),
         against_votes: Uint128::zero(),
-        start_height: env.block.height,
-        end_height: env.block.height + config.proposal_voting_period,
+        start_height,
+        end_height: start_height + voting_period,
         title,
         description,
         link: option_link,
@@ -227,10 +312,118 @@ pub fn execute_submit_proposal(
     )?;
 
     let response = Response::new().add_attributes(vec![
-        attr("action", "submit_proposal"),
-        attr("submitter", submitter_address_unchecked),
-        attr("proposal_id", &global_state.proposal_count.to_string()),
-        attr("proposal_end_height", &new_proposal.end_height.to_string()),
+        attr(ACTION, ACTION_SUBMIT_PROPOSAL),
+        attr(SUBMITTER, submitter_address_unchecked),
+        attr(PROPOSAL_ID, global_state.proposal_count.to_string()),
+        attr(PROPOSAL_START_HEIGHT, new_proposal.start_height.to_string()),
+        attr(PROPOSAL_END_HEIGHT, new_proposal.end_height.to_string()),
+        attr(DEPOSIT_AMOUNT, new_proposal.deposit_amount.to_string()),
+    ]);
+
+    Ok(response)
+}
+
+/// Validates the title, description, link and messages of a proposal of the given kind
+fn validate_proposal_content(
+    title: &str,
+    description: &str,
+    option_link: &Option<String>,
+    kind: &ProposalKind,
+    option_messages: &Option<Vec<ProposalMessage>>,
+) -> Result<(), ContractError> {
+    // Validate title
+    if title.len() < MIN_TITLE_LENGTH {
+        return Err(ContractError::invalid_proposal("title too short"));
+    }
+    if title.len() > MAX_TITLE_LENGTH {
+        return Err(ContractError::invalid_proposal("title too long"));
+    }
+
+    // Validate description
+    if description.len() < MIN_DESC_LENGTH {
+        return Err(ContractError::invalid_proposal("description too short"));
+    }
+    if description.len() > MAX_DESC_LENGTH {
+        return Err(ContractError::invalid_proposal("description too long"));
+    }
+
+    // Validate Link
+    if let Some(link) = option_link {
+        if link.len() < MIN_LINK_LENGTH {
+            return Err(ContractError::invalid_proposal("Link too short"));
+        }
+        if link.len() > MAX_LINK_LENGTH {
+            return Err(ContractError::invalid_proposal("Link too long"));
+        }
+    }
+
+    // Validate messages
+    let has_messages = matches!(option_messages, Some(messages) if !messages.is_empty());
+    if *kind == ProposalKind::TextOnly && has_messages {
+        return Err(ContractError::invalid_proposal(
+            "text only proposal cannot have messages",
+        ));
+    }
+    if *kind != ProposalKind::TextOnly && !has_messages {
+        return Err(ContractError::invalid_proposal(
+            "proposal must have at least one message unless it is text only",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Amend an active proposal before its voting period starts. Only the submitter can amend it
+/// and the amended proposal is validated the same way as on submission
+#[allow(clippy::too_many_arguments)]
+pub fn execute_amend_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    option_title: Option<String>,
+    option_description: Option<String>,
+    option_link: Option<String>,
+    option_messages: Option<Vec<ProposalMessage>>,
+) -> Result<Response, ContractError> {
+    let proposal_path = PROPOSALS.key(U64Key::new(proposal_id));
+    let mut proposal = proposal_path.load(deps.storage)?;
+
+    if info.sender != proposal.submitter_address {
+        return Err(MarsError::Unauthorized {}.into());
+    }
+
+    if proposal.status != ProposalStatus::Active || env.block.height >= proposal.start_height {
+        return Err(ContractError::ProposalAmendmentNotAllowed {});
+    }
+
+    if let Some(title) = option_title {
+        proposal.title = title;
+    }
+    if let Some(description) = option_description {
+        proposal.description = description;
+    }
+    if let Some(link) = option_link {
+        proposal.link = Some(link);
+    }
+    if let Some(messages) = option_messages {
+        proposal.messages = Some(messages);
+    }
+
+    validate_proposal_content(
+        &proposal.title,
+        &proposal.description,
+        &proposal.link,
+        &proposal.kind,
+        &proposal.messages,
+    )?;
+
+    proposal_path.save(deps.storage, &proposal)?;
+
+    let response = Response::new().add_attributes(vec![
+        attr(ACTION, ACTION_AMEND_PROPOSAL),
+        attr(PROPOSAL_ID, proposal_id.to_string()),
+        attr(SUBMITTER, &info.sender),
     ]);
 
     Ok(response)
@@ -249,6 +442,9 @@ pub fn execute_cast_vote(
         return Err(ContractError::ProposalNotActive {});
     }
 
+    if env.block.height < proposal.start_height {
+        return Err(ContractError::VoteVotingPeriodNotStarted {});
+    }
     if env.block.height > proposal.end_height {
         return Err(ContractError::VoteVotingPeriodEnded {});
     }
@@ -271,29 +467,68 @@ pub fn execute_cast_vote(
 
     let balance_at_block = proposal.start_height - 1;
 
-    // The voting power of a user for a proposal is defined as the sum of two parts:
-    //
-    // - Free voting power: the amount of xMARS token in the user's wallet, at the block before the
-    //   proposal was created
-    // - Locked voting power: the amount of MARS locked in the vesting contract owned by the user,
-    //   at the block before the proposal was created
-    //
-    // The reason we can use the amount of MARS (instead of xMARS) for locked voting power is that,
-    // since vesting allocations can only be created when 1 MARS == 1 xMARS, these MARS tokens would
-    // have produced the same amount of xMARS if they were staked.
-    let voting_power_free = xmars_get_balance_at(
-        &deps.querier,
-        xmars_token_address,
-        info.sender.clone(),
-        balance_at_block,
-    )?;
-    let voting_power_locked = vesting_get_voting_power_at(
+    // Voting power each voter (the sender and each of its delegators) can contribute
+    let max_voter_power = if config.proposal_max_voter_power_share < Decimal::one() {
+        let total_voting_power = get_total_voting_power_at(
+            &deps.querier,
+            xmars_token_address.clone(),
+            vesting_address.clone(),
+            balance_at_block,
+        )?;
+        Some(total_voting_power.total * config.proposal_max_voter_power_share)
+    } else {
+        None
+    };
+    let cap_voter_power = |voting_power: Uint128| match max_voter_power {
+        Some(max_voter_power) => voting_power.min(max_voter_power),
+        None => voting_power,
+    };
+
+    let own_voting_power = cap_voter_power(get_voting_power_at(
         &deps.querier,
-        vesting_address,
+        xmars_token_address.clone(),
+        vesting_address.clone(),
         info.sender.clone(),
         balance_at_block,
-    )?;
-    let voting_power = voting_power_free + voting_power_locked;
+    )?);
+
+    // Delegated voting power is recorded as a vote from each delegator so they cannot vote
+    // again on the proposal (even after revoking). Delegators that already voted themselves
+    // are skipped
+    let delegators: Vec<Addr> = DELEGATORS
+        .prefix(&info.sender)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|key| Addr::unchecked(String::from_utf8_lossy(&key)))
+        .collect();
+    let mut delegated_voting_power = Uint128::zero();
+    for delegator in delegators {
+        let delegator_vote_path = PROPOSAL_VOTES.key((U64Key::new(proposal_id), &delegator));
+        if delegator_vote_path.may_load(deps.storage)?.is_some() {
+            continue;
+        }
+
+        let delegator_voting_power = cap_voter_power(get_voting_power_at(
+            &deps.querier,
+            xmars_token_address.clone(),
+            vesting_address.clone(),
+            delegator,
+            balance_at_block,
+        )?);
+        if delegator_voting_power.is_zero() {
+            continue;
+        }
+
+        delegator_vote_path.save(
+            deps.storage,
+            &ProposalVote {
+                option: vote_option.clone(),
+                power: delegator_voting_power,
+            },
+        )?;
+        delegated_voting_power += delegator_voting_power;
+    }
+
+    let voting_power = own_voting_power + delegated_voting_power;
 
     if voting_power.is_zero() {
         return Err(ContractError::VoteNoVotingPower {
@@ -310,18 +545,111 @@ pub fn execute_cast_vote(
         deps.storage,
         &ProposalVote {
             option: vote_option.clone(),
-            power: voting_power,
+            power: own_voting_power,
         },
     )?;
 
     proposal_path.save(deps.storage, &proposal)?;
 
     let response = Response::new().add_attributes(vec![
-        attr("action", "cast_vote"),
-        attr("proposal_id", proposal_id.to_string()),
-        attr("voter", &info.sender),
-        attr("vote", vote_option.to_string()),
-        attr("voting_power", voting_power.to_string()),
+        attr(ACTION, ACTION_CAST_VOTE),
+        attr(PROPOSAL_ID, proposal_id.to_string()),
+        attr(VOTER, &info.sender),
+        attr(VOTE, vote_option.to_string()),
+        attr(VOTING_POWER, voting_power.to_string()),
+    ]);
+
+    Ok(response)
+}
+
+pub fn execute_delegate_voting_power(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    delegate: String,
+) -> Result<Response, ContractError> {
+    let delegate_address = deps.api.addr_validate(&delegate)?;
+    if delegate_address == info.sender {
+        return Err(ContractError::DelegateToSelf {});
+    }
+
+    // Replace previous delegation if any. The new one only counts once the delegate accepts
+    // it, so delegates control how many delegators they aggregate when voting
+    if let Some(previous_delegate) = DELEGATIONS.may_load(deps.storage, &info.sender)? {
+        DELEGATIONS.remove(deps.storage, &info.sender);
+        DELEGATORS.remove(deps.storage, (&previous_delegate, &info.sender));
+    }
+
+    PENDING_DELEGATIONS.save(deps.storage, &info.sender, &delegate_address)?;
+
+    let response = Response::new().add_attributes(vec![
+        attr(ACTION, ACTION_DELEGATE_VOTING_POWER),
+        attr(DELEGATOR, &info.sender),
+        attr(DELEGATE, &delegate_address),
+    ]);
+
+    Ok(response)
+}
+
+pub fn execute_accept_delegation(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    delegator: String,
+) -> Result<Response, ContractError> {
+    let delegator_address = deps.api.addr_validate(&delegator)?;
+    match PENDING_DELEGATIONS.may_load(deps.storage, &delegator_address)? {
+        Some(delegate_address) if delegate_address == info.sender => {}
+        _ => return Err(ContractError::DelegationNotFound {}),
+    }
+
+    let delegators_count = DELEGATORS
+        .prefix(&info.sender)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(MAX_DELEGATORS_PER_DELEGATE)
+        .count();
+    if delegators_count >= MAX_DELEGATORS_PER_DELEGATE {
+        return Err(ContractError::DelegateMaxDelegatorsReached {
+            max_delegators: MAX_DELEGATORS_PER_DELEGATE,
+        });
+    }
+
+    PENDING_DELEGATIONS.remove(deps.storage, &delegator_address);
+    DELEGATIONS.save(deps.storage, &delegator_address, &info.sender)?;
+    DELEGATORS.save(deps.storage, (&info.sender, &delegator_address), &Empty {})?;
+
+    let response = Response::new().add_attributes(vec![
+        attr(ACTION, ACTION_ACCEPT_DELEGATION),
+        attr(DELEGATOR, &delegator_address),
+        attr(DELEGATE, &info.sender),
+    ]);
+
+    Ok(response)
+}
+
+pub fn execute_revoke_delegation(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let delegate_address =
+        if let Some(delegate_address) = DELEGATIONS.may_load(deps.storage, &info.sender)? {
+            DELEGATIONS.remove(deps.storage, &info.sender);
+            DELEGATORS.remove(deps.storage, (&delegate_address, &info.sender));
+            delegate_address
+        } else if let Some(delegate_address) =
+            PENDING_DELEGATIONS.may_load(deps.storage, &info.sender)?
+        {
+            PENDING_DELEGATIONS.remove(deps.storage, &info.sender);
+            delegate_address
+        } else {
+            return Err(ContractError::DelegationNotFound {});
+        };
+
+    let response = Response::new().add_attributes(vec![
+        attr(ACTION, ACTION_REVOKE_DELEGATION),
+        attr(DELEGATOR, &info.sender),
+        attr(DELEGATE, &delegate_address),
     ]);
 
     Ok(response)
@@ -353,7 +681,7 @@ pub fn execute_end_proposal(
     ];
     let mut addresses_query = address_provider::helpers::query_addresses(
         &deps.querier,
-        config.address_provider_address,
+        config.address_provider_address.clone(),
         mars_contracts,
     )?;
     let xmars_token_address = addresses_query.pop().unwrap();
@@ -361,84 +689,59 @@ pub fn execute_end_proposal(
     let staking_address = addresses_query.pop().unwrap();
     let mars_token_address = addresses_query.pop().unwrap();
 
-    // The total voting power of a proposal is defined as the sum of two parts:
-    //
-    // - Free voting power: the total supply of xMARS token at the block before the proposal was
-    //   created
-    // - Locked voting power: the total amount of MARS token locked in the vesting contract, at the
-    //   block before the proposal was created
-    //
-    // The reason we can use the amount of MARS (instead of xMARS) for locked voting power is that,
-    // since vesting allocations can only be created when 1 MARS == 1 xMARS, these MARS tokens would
-    // have produced the same amount of xMARS if they were staked.
-    let total_voting_power_free = xmars_get_total_supply_at(
+    let proposal_result = compute_proposal_result(
         &deps.querier,
+        &config,
+        &proposal,
         xmars_token_address,
-        proposal.start_height - 1,
-    )?;
-    let total_voting_power_locked = vesting_get_total_voting_power_at(
-        &deps.querier,
         vesting_address,
-        proposal.start_height - 1,
     )?;
-    let total_voting_power = total_voting_power_free + total_voting_power_locked;
-
-    // Compute proposal quorum and threshold
-    let for_votes = proposal.for_votes;
-    let against_votes = proposal.against_votes;
-    let total_votes = for_votes + against_votes;
-
-    let mut proposal_quorum: Decimal = Decimal::zero();
-    let mut proposal_threshold: Decimal = Decimal::zero();
-    if total_voting_power > Uint128::zero() {
-        proposal_quorum = Decimal::from_ratio(total_votes, total_voting_power);
-    }
-    if total_votes > Uint128::zero() {
-        proposal_threshold = Decimal::from_ratio(for_votes, total_votes);
-    }
 
     // Determine proposal result
-    let (new_proposal_status, log_proposal_result, messages) = if proposal_quorum
-        >= config.proposal_required_quorum
-        && proposal_threshold > config.proposal_required_threshold
-    {
-        // if quorum and threshold are met then proposal passes
-        // refund deposit amount to submitter
-        let msg = CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: mars_token_address.into(),
-            funds: vec![],
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: proposal.submitter_address.to_string(),
-                amount: proposal.deposit_amount,
-            })?,
-        });
+    let (new_proposal_status, log_proposal_result) =
+        if proposal_result.quorum_met && proposal_result.threshold_met {
+            // if quorum and threshold are met then proposal passes
+            (ProposalStatus::Passed, "passed")
+        } else {
+            // Else proposal is rejected
+            (ProposalStatus::Rejected, "rejected")
+        };
 
-        (ProposalStatus::Passed, "passed", vec![msg])
+    // Deposit is refunded to submitter if quorum is met (even if the proposal is rejected),
+    // otherwise it is slashed and sent to the staking contract
+    let (deposit_recipient, deposit_status) = if proposal_result.quorum_met {
+        (
+            proposal.submitter_address.clone(),
+            ProposalDepositStatus::Refunded,
+        )
     } else {
-        // Else proposal is rejected
-        let msg = CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: mars_token_address.into(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: staking_address.into(),
-                amount: proposal.deposit_amount,
-            })?,
-            funds: vec![],
-        });
-
-        (ProposalStatus::Rejected, "rejected", vec![msg])
+        (staking_address, ProposalDepositStatus::Slashed)
     };
+    let msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: mars_token_address.into(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: deposit_recipient.to_string(),
+            amount: proposal.deposit_amount,
+        })?,
+    });
 
     // Update proposal status
     proposal.status = new_proposal_status;
     proposal_path.save(deps.storage, &proposal)?;
+    PROPOSAL_DEPOSIT_STATUSES.save(deps.storage, U64Key::new(proposal_id), &deposit_status)?;
 
     let response = Response::new()
         .add_attributes(vec![
-            attr("action", "end_proposal"),
-            attr("proposal_id", proposal_id.to_string()),
-            attr("proposal_result", log_proposal_result),
+            attr(ACTION, ACTION_END_PROPOSAL),
+            attr(PROPOSAL_ID, proposal_id.to_string()),
+            attr(PROPOSAL_RESULT, log_proposal_result),
+            attr(DEPOSIT, deposit_status.to_string()),
+            attr(DEPOSIT_AMOUNT, proposal.deposit_amount.to_string()),
+            attr(FOR_VOTES, proposal.for_votes.to_string()),
+            attr(AGAINST_VOTES, proposal.against_votes.to_string()),
         ])
-        .add_messages(messages);
+        .add_message(msg);
 
     Ok(response)
 }
@@ -481,8 +784,8 @@ pub fn execute_execute_proposal(
 
     let response = Response::new()
         .add_attributes(vec![
-            attr("action", "execute_proposal"),
-            attr("proposal_id", proposal_id.to_string()),
+            attr(ACTION, ACTION_EXECUTE_PROPOSAL),
+            attr(PROPOSAL_ID, proposal_id.to_string()),
         ])
         .add_messages(messages);
 
@@ -510,11 +813,17 @@ pub fn execute_update_config(
         address_provider_address,
 
         proposal_voting_period,
+        proposal_min_voting_period,
+        proposal_max_voting_period,
         proposal_effective_delay,
         proposal_expiration_period,
         proposal_required_deposit,
         proposal_required_quorum,
         proposal_required_threshold,
+        proposal_submission_cooldown,
+        proposal_voting_delay,
+        proposal_max_voter_power_share,
+        proposal_kind_requirements,
     } = new_config;
 
     // Update config
@@ -525,6 +834,10 @@ pub fn execute_update_config(
     )?;
 
     config.proposal_voting_period = proposal_voting_period.unwrap_or(config.proposal_voting_period);
+    config.proposal_min_voting_period =
+        proposal_min_voting_period.unwrap_or(config.proposal_min_voting_period);
+    config.proposal_max_voting_period =
+        proposal_max_voting_period.unwrap_or(config.proposal_max_voting_period);
     config.proposal_effective_delay =
         proposal_effective_delay.unwrap_or(config.proposal_effective_delay);
     config.proposal_expiration_period =
@@ -535,28 +848,37 @@ pub fn execute_update_config(
         proposal_required_quorum.unwrap_or(config.proposal_required_quorum);
     config.proposal_required_threshold =
         proposal_required_threshold.unwrap_or(config.proposal_required_threshold);
+    config.proposal_submission_cooldown =
+        proposal_submission_cooldown.unwrap_or(config.proposal_submission_cooldown);
+    config.proposal_voting_delay = proposal_voting_delay.unwrap_or(config.proposal_voting_delay);
+    config.proposal_max_voter_power_share =
+        proposal_max_voter_power_share.unwrap_or(config.proposal_max_voter_power_share);
+    config.proposal_kind_requirements =
+        proposal_kind_requirements.unwrap_or(config.proposal_kind_requirements);
 
     // Validate config
     config.validate()?;
 
     CONFIG.save(deps.storage, &config)?;
 
-    let res = Response::new().add_attribute("action", "update_config");
+    let res = Response::new().add_attribute(ACTION, ACTION_UPDATE_CONFIG);
     Ok(res)
 }
 
 // QUERIES
 
-// Pagination defaults
-const PAGINATION_DEFAULT_LIMIT: u32 = 10;
-const PAGINATION_MAX_LIMIT: u32 = 30;
-
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::Proposals { start, limit } => to_binary(&query_proposals(deps, start, limit)?),
         QueryMsg::Proposal { proposal_id } => to_binary(&query_proposal(deps, proposal_id)?),
+        QueryMsg::ProposalResult { proposal_id } => {
+            to_binary(&query_proposal_result(deps, proposal_id)?)
+        }
+        QueryMsg::ProposalDeposits { start, limit } => {
+            to_binary(&query_proposal_deposits(deps, start, limit)?)
+        }
         QueryMsg::ProposalVotes {
             proposal_id,
             start_after,
@@ -567,6 +889,12 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             start_after,
             limit,
         )?),
+        QueryMsg::TotalVotingPower { height } => {
+            to_binary(&query_total_voting_power(deps, env, height)?)
+        }
+        QueryMsg::ProposalMessagesSummary { proposal_id } => {
+            to_binary(&query_proposal_messages_summary(deps, env, proposal_id)?)
+        }
     }
 }
 
@@ -583,22 +911,20 @@ fn query_proposals(
     let global_state = GLOBAL_STATE.load(deps.storage)?;
 
     let option_start = start_from.map(|start| Bound::inclusive(U64Key::new(start)));
-    let limit = option_limit
-        .unwrap_or(PAGINATION_DEFAULT_LIMIT)
-        .min(PAGINATION_MAX_LIMIT) as usize;
-
-    let proposals_list: StdResult<Vec<_>> = PROPOSALS
-        .range(deps.storage, option_start, None, Order::Ascending)
-        .take(limit)
-        .map(|item| {
-            let (_k, v) = item?;
-            Ok(v)
-        })
-        .collect();
+
+    let proposals_list = paginate_prefix(
+        &PROPOSALS.prefix(()),
+        deps.storage,
+        option_start,
+        option_limit,
+    )?
+    .into_iter()
+    .map(|(_k, v)| v)
+    .collect();
 
     Ok(ProposalsListResponse {
         proposal_count: global_state.proposal_count,
-        proposal_list: proposals_list?,
+        proposal_list: proposals_list,
     })
 }
 
@@ -607,47 +933,601 @@ fn query_proposal(deps: Deps, proposal_id: u64) -> StdResult<Proposal> {
     Ok(proposal)
 }
 
-fn query_proposal_votes(
+fn query_proposal_result(deps: Deps, proposal_id: u64) -> StdResult<ProposalResultResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let proposal = PROPOSALS.load(deps.storage, U64Key::new(proposal_id))?;
+
+    let mut addresses_query = address_provider::helpers::query_addresses(
+        &deps.querier,
+        config.address_provider_address.clone(),
+        vec![MarsContract::Vesting, MarsContract::XMarsToken],
+    )?;
+    let xmars_token_address = addresses_query.pop().unwrap();
+    let vesting_address = addresses_query.pop().unwrap();
+
+    compute_proposal_result(
+        &deps.querier,
+        &config,
+        &proposal,
+        xmars_token_address,
+        vesting_address,
+    )
+}
+
+fn query_proposal_deposits(
     deps: Deps,
-    proposal_id: u64,
-    start_after: Option<String>,
+    start_from: Option<u64>,
     option_limit: Option<u32>,
-) -> StdResult<ProposalVotesResponse> {
-    let limit = option_limit
-        .unwrap_or(PAGINATION_DEFAULT_LIMIT)
-        .min(PAGINATION_MAX_LIMIT) as usize;
-    let option_start = start_after.map(Bound::exclusive);
+) -> StdResult<ProposalDepositsResponse> {
+    let option_start = start_from.map(|start| Bound::inclusive(U64Key::new(start)));
 
-    let votes: StdResult<Vec<ProposalVoteResponse>> = PROPOSAL_VOTES
-        .prefix(U64Key::new(proposal_id))
-        .range(deps.storage, option_start, None, Order::Ascending)
-        .take(limit)
-        .map(|vote| {
-            let (k, v) = vote?;
-            let voter_address = String::from_utf8(k)?;
-
-            Ok(ProposalVoteResponse {
-                voter_address,
-                option: v.option,
-                power: v.power,
-            })
+    let deposits: StdResult<Vec<_>> = paginate_prefix(
+        &PROPOSALS.prefix(()),
+        deps.storage,
+        option_start,
+        option_limit,
+    )?
+    .into_iter()
+    .map(|(_k, proposal)| {
+        // Deposit status is recorded when the proposal ends
+        let status = match proposal.status {
+            ProposalStatus::Active => ProposalDepositStatus::Locked,
+            _ => PROPOSAL_DEPOSIT_STATUSES.load(deps.storage, U64Key::new(proposal.proposal_id))?,
+        };
+        Ok(ProposalDepositResponse {
+            proposal_id: proposal.proposal_id,
+            status,
+            amount: proposal.deposit_amount,
         })
-        .collect();
+    })
+    .collect();
 
-    Ok(ProposalVotesResponse {
-        proposal_id,
-        votes: votes?,
+    Ok(ProposalDepositsResponse {
+        deposits: deposits?,
     })
 }
 
-// HELPERS
+fn query_proposal_messages_summary(
+    deps: Deps,
+    env: Env,
+    proposal_id: u64,
+) -> StdResult<ProposalMessagesSummaryResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let proposal = PROPOSALS.load(deps.storage, U64Key::new(proposal_id))?;
 
-fn xmars_get_total_supply_at(
-    querier: &QuerierWrapper,
-    xmars_address: Addr,
-    block: u64,
-) -> StdResult<Uint128> {
-    let query: xmars_token::TotalSupplyResponse =
+    let red_bank_address = address_provider::helpers::query_address(
+        &deps.querier,
+        config.address_provider_address,
+        MarsContract::RedBank,
+    )?;
+
+    let mut messages = proposal.messages.unwrap_or_default();
+    messages.sort_by_key(|message| message.execution_order);
+
+    let messages: StdResult<Vec<_>> = messages
+        .into_iter()
+        .map(|message| {
+            let decoded =
+                decode_proposal_message(&message.msg, &red_bank_address, &env.contract.address)?;
+
+            Ok(match decoded {
+                Some((description, params)) => ProposalMessageSummary {
+                    execution_order: message.execution_order,
+                    description: Some(description),
+                    params,
+                    raw: None,
+                },
+                None => ProposalMessageSummary {
+                    execution_order: message.execution_order,
+                    description: None,
+                    params: vec![],
+                    raw: Some(to_binary(&message.msg)?),
+                },
+            })
+        })
+        .collect();
+
+    Ok(ProposalMessagesSummaryResponse {
+        proposal_id,
+        messages: messages?,
+    })
+}
+
+fn query_total_voting_power(
+    deps: Deps,
+    env: Env,
+    height: Option<u64>,
+) -> StdResult<TotalVotingPowerResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut addresses_query = address_provider::helpers::query_addresses(
+        &deps.querier,
+        config.address_provider_address,
+        vec![MarsContract::Vesting, MarsContract::XMarsToken],
+    )?;
+    let xmars_token_address = addresses_query.pop().unwrap();
+    let vesting_address = addresses_query.pop().unwrap();
+
+    get_total_voting_power_at(
+        &deps.querier,
+        xmars_token_address,
+        vesting_address,
+        height.unwrap_or(env.block.height),
+    )
+}
+
+fn query_proposal_votes(
+    deps: Deps,
+    proposal_id: u64,
+    start_after: Option<String>,
+    option_limit: Option<u32>,
+) -> StdResult<ProposalVotesResponse> {
+    let option_start = start_after.map(Bound::exclusive);
+
+    let votes: StdResult<Vec<ProposalVoteResponse>> = paginate_prefix(
+        &PROPOSAL_VOTES.prefix(U64Key::new(proposal_id)),
+        deps.storage,
+        option_start,
+        option_limit,
+    )?
+    .into_iter()
+    .map(|(k, v)| {
+        let voter_address = String::from_utf8(k)?;
+
+        Ok(ProposalVoteResponse {
+            voter_address,
+            option: v.option,
+            power: v.power,
+        })
+    })
+    .collect();
+
+    Ok(ProposalVotesResponse {
+        proposal_id,
+        votes: votes?,
+    })
+}
+
+// HELPERS
+
+/// Decodes a proposal message into a description and the parameters it sets.
+/// Returns None if the message type is not known
+fn decode_proposal_message(
+    msg: &CosmosMsg,
+    red_bank_address: &Addr,
+    council_address: &Addr,
+) -> StdResult<Option<(String, Vec<ProposalMessageParam>)>> {
+    let mut params = vec![];
+
+    let description = match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) if contract_addr == red_bank_address.as_str() => {
+            match from_binary::<red_bank::msg::ExecuteMsg>(msg) {
+                Ok(red_bank::msg::ExecuteMsg::UpdateConfig { config }) => {
+                    push_red_bank_config_params(&mut params, &config)?;
+                    "Update Red Bank config".to_string()
+                }
+                Ok(red_bank::msg::ExecuteMsg::InitAsset {
+                    asset,
+                    asset_params,
+                    ..
+                }) => {
+                    push_red_bank_asset_params(&mut params, &asset_params)?;
+                    format!(
+                        "Initialize Red Bank market for {}",
+                        asset.get_attributes().0
+                    )
+                }
+                Ok(red_bank::msg::ExecuteMsg::UpdateAsset {
+                    asset,
+                    asset_params,
+                }) => {
+                    push_red_bank_asset_params(&mut params, &asset_params)?;
+                    format!("Update Red Bank market for {}", asset.get_attributes().0)
+                }
+                Ok(red_bank::msg::ExecuteMsg::UpdateUncollateralizedLoanLimit {
+                    user_address,
+                    asset,
+                    new_limit,
+                }) => format!(
+                    "Set uncollateralized loan limit of {} for {} to {}",
+                    user_address,
+                    asset.get_attributes().0,
+                    new_limit
+                ),
+                Ok(red_bank::msg::ExecuteMsg::DeprecateMarket { asset }) => {
+                    format!("Deprecate Red Bank market for {}", asset.get_attributes().0)
+                }
+                _ => return Ok(None),
+            }
+        }
+
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) if contract_addr == council_address.as_str() => match from_binary::<ExecuteMsg>(msg) {
+            Ok(ExecuteMsg::UpdateConfig { config }) => {
+                push_param(
+                    &mut params,
+                    "address_provider_address",
+                    &config.address_provider_address,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_voting_period",
+                    &config.proposal_voting_period,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_min_voting_period",
+                    &config.proposal_min_voting_period,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_max_voting_period",
+                    &config.proposal_max_voting_period,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_effective_delay",
+                    &config.proposal_effective_delay,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_expiration_period",
+                    &config.proposal_expiration_period,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_required_deposit",
+                    &config.proposal_required_deposit,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_required_quorum",
+                    &config.proposal_required_quorum,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_required_threshold",
+                    &config.proposal_required_threshold,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_submission_cooldown",
+                    &config.proposal_submission_cooldown,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_voting_delay",
+                    &config.proposal_voting_delay,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_max_voter_power_share",
+                    &config.proposal_max_voter_power_share,
+                )?;
+                push_param(
+                    &mut params,
+                    "proposal_kind_requirements",
+                    &config.proposal_kind_requirements,
+                )?;
+                "Update Council config".to_string()
+            }
+            _ => return Ok(None),
+        },
+
+        CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr,
+            new_code_id,
+            ..
+        }) => format!(
+            "Migrate contract {} to code id {}",
+            contract_addr, new_code_id
+        ),
+
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+            let amount: Vec<String> = amount.iter().map(|coin| coin.to_string()).collect();
+            format!("Send {} to {}", amount.join(", "), to_address)
+        }
+
+        _ => return Ok(None),
+    };
+
+    Ok(Some((description, params)))
+}
+
+fn push_red_bank_config_params(
+    params: &mut Vec<ProposalMessageParam>,
+    config: &red_bank::msg::CreateOrUpdateConfig,
+) -> StdResult<()> {
+    push_param(params, "owner", &config.owner)?;
+    push_param(
+        params,
+        "address_provider_address",
+        &config.address_provider_address,
+    )?;
+    push_param(params, "ma_token_code_id", &config.ma_token_code_id)?;
+    push_param(params, "close_factor", &config.close_factor)?;
+    push_param(
+        params,
+        "dust_threshold_in_uusd",
+        &config.dust_threshold_in_uusd,
+    )?;
+    push_param(
+        params,
+        "min_collateral_value_in_uusd",
+        &config.min_collateral_value_in_uusd,
+    )?;
+    push_param(
+        params,
+        "borrow_health_factor_buffer",
+        &config.borrow_health_factor_buffer,
+    )?;
+    push_param(params, "max_markets_per_user", &config.max_markets_per_user)?;
+    push_param(params, "max_price_deviation", &config.max_price_deviation)?;
+    push_param(
+        params,
+        "price_deviation_window",
+        &config.price_deviation_window,
+    )?;
+    push_param(
+        params,
+        "astroport_factory_address",
+        &config.astroport_factory_address,
+    )?;
+    push_param(
+        params,
+        "update_cooldown_seconds",
+        &config.update_cooldown_seconds,
+    )?;
+    push_param(params, "guardian_address", &config.guardian_address)?;
+    push_param(
+        params,
+        "auto_repay_keeper_fee",
+        &config.auto_repay_keeper_fee,
+    )
+}
+
+fn push_red_bank_asset_params(
+    params: &mut Vec<ProposalMessageParam>,
+    asset_params: &red_bank::msg::InitOrUpdateAssetParams,
+) -> StdResult<()> {
+    // Destructuring a struct’s fields into separate variables in order to force
+    // compile error if we add more params
+    let red_bank::msg::InitOrUpdateAssetParams {
+        initial_borrow_rate,
+        reserve_factor,
+        deposit_fee,
+        withdraw_fee,
+        max_loan_to_value,
+        liquidation_threshold,
+        liquidation_bonus,
+        interest_rate_model_params,
+        active,
+        deposit_enabled,
+        borrow_enabled,
+        borrow_cooldown_seconds,
+        max_borrow_rate_change,
+        borrow_rate_discount,
+        borrow_rate_discount_min_xmars,
+        stable_borrow_enabled,
+        stable_borrow_rate_premium,
+        stable_rate_rebalance_utilization_threshold,
+        stable_rate_rebalance_max_divergence,
+        deprecation_grace_period_seconds,
+        deprecation_penalty_borrow_rate,
+        min_liquidity_rate,
+        emode_category_id,
+        max_liquidation_amount_per_tx,
+        interest_rate_strategy_id,
+        debt_ceiling_in_uusd,
+        liquidation_protocol_fee,
+        rounding_reserve,
+        asset_decimals,
+        price_decimals,
+    } = asset_params;
+
+    push_param(params, "initial_borrow_rate", initial_borrow_rate)?;
+    push_param(params, "reserve_factor", reserve_factor)?;
+    push_param(params, "deposit_fee", deposit_fee)?;
+    push_param(params, "withdraw_fee", withdraw_fee)?;
+    push_param(params, "max_loan_to_value", max_loan_to_value)?;
+    push_param(params, "liquidation_threshold", liquidation_threshold)?;
+    push_param(params, "liquidation_bonus", liquidation_bonus)?;
+    push_param(
+        params,
+        "interest_rate_model_params",
+        interest_rate_model_params,
+    )?;
+    push_param(params, "active", active)?;
+    push_param(params, "deposit_enabled", deposit_enabled)?;
+    push_param(params, "borrow_enabled", borrow_enabled)?;
+    push_param(params, "borrow_cooldown_seconds", borrow_cooldown_seconds)?;
+    push_param(params, "max_borrow_rate_change", max_borrow_rate_change)?;
+    push_param(params, "borrow_rate_discount", borrow_rate_discount)?;
+    push_param(
+        params,
+        "borrow_rate_discount_min_xmars",
+        borrow_rate_discount_min_xmars,
+    )?;
+    push_param(params, "stable_borrow_enabled", stable_borrow_enabled)?;
+    push_param(
+        params,
+        "stable_borrow_rate_premium",
+        stable_borrow_rate_premium,
+    )?;
+    push_param(
+        params,
+        "stable_rate_rebalance_utilization_threshold",
+        stable_rate_rebalance_utilization_threshold,
+    )?;
+    push_param(
+        params,
+        "stable_rate_rebalance_max_divergence",
+        stable_rate_rebalance_max_divergence,
+    )?;
+    push_param(
+        params,
+        "deprecation_grace_period_seconds",
+        deprecation_grace_period_seconds,
+    )?;
+    push_param(
+        params,
+        "deprecation_penalty_borrow_rate",
+        deprecation_penalty_borrow_rate,
+    )?;
+    push_param(params, "min_liquidity_rate", min_liquidity_rate)?;
+    push_param(params, "emode_category_id", emode_category_id)?;
+    push_param(
+        params,
+        "max_liquidation_amount_per_tx",
+        max_liquidation_amount_per_tx,
+    )?;
+    push_param(
+        params,
+        "interest_rate_strategy_id",
+        interest_rate_strategy_id,
+    )?;
+    push_param(params, "debt_ceiling_in_uusd", debt_ceiling_in_uusd)?;
+    push_param(params, "liquidation_protocol_fee", liquidation_protocol_fee)?;
+    push_param(params, "rounding_reserve", rounding_reserve)?;
+    push_param(params, "asset_decimals", asset_decimals)?;
+    push_param(params, "price_decimals", price_decimals)
+}
+
+/// Adds the param to the list if it is set. Values are displayed as json without the quotes
+/// around strings (decimals, amounts and addresses are serialized as strings)
+fn push_param<T: Serialize>(
+    params: &mut Vec<ProposalMessageParam>,
+    name: &str,
+    value: &Option<T>,
+) -> StdResult<()> {
+    if let Some(value) = value {
+        let json = String::from_utf8(to_vec(value)?)?;
+        params.push(ProposalMessageParam {
+            name: name.to_string(),
+            value: json.trim_matches('"').to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// The voting power of a user for a proposal is defined as the sum of two parts:
+///
+/// - Free voting power: the amount of xMARS token in the user's wallet, at the block before the
+///   proposal was created
+/// - Locked voting power: the amount of MARS locked in the vesting contract owned by the user,
+///   at the block before the proposal was created
+///
+/// The reason we can use the amount of MARS (instead of xMARS) for locked voting power is that,
+/// since vesting allocations can only be created when 1 MARS == 1 xMARS, these MARS tokens would
+/// have produced the same amount of xMARS if they were staked.
+fn get_voting_power_at(
+    querier: &QuerierWrapper,
+    xmars_token_address: Addr,
+    vesting_address: Addr,
+    user_address: Addr,
+    block: u64,
+) -> StdResult<Uint128> {
+    let voting_power_free =
+        xmars_get_balance_at(querier, xmars_token_address, user_address.clone(), block)?;
+    let voting_power_locked =
+        vesting_get_voting_power_at(querier, vesting_address, user_address, block)?;
+    Ok(voting_power_free + voting_power_locked)
+}
+
+/// Computes the proposal quorum and threshold with the current votes and whether they meet
+/// the ones required for the proposal to pass
+fn compute_proposal_result(
+    querier: &QuerierWrapper,
+    config: &Config,
+    proposal: &Proposal,
+    xmars_token_address: Addr,
+    vesting_address: Addr,
+) -> StdResult<ProposalResultResponse> {
+    // The total voting power of a proposal is the one at the block before the proposal was
+    // created
+    let total_voting_power = get_total_voting_power_at(
+        querier,
+        xmars_token_address,
+        vesting_address,
+        proposal.start_height - 1,
+    )?
+    .total;
+
+    // Compute proposal quorum and threshold
+    let for_votes = proposal.for_votes;
+    let against_votes = proposal.against_votes;
+    let total_votes = for_votes + against_votes;
+
+    let mut quorum: Decimal = Decimal::zero();
+    let mut threshold: Decimal = Decimal::zero();
+    if total_voting_power > Uint128::zero() {
+        quorum = Decimal::from_ratio(total_votes, total_voting_power);
+    }
+    if total_votes > Uint128::zero() {
+        threshold = Decimal::from_ratio(for_votes, total_votes);
+    }
+
+    let (required_quorum, required_threshold) =
+        config.required_quorum_and_threshold(&proposal.kind);
+    let quorum_met = quorum >= required_quorum;
+    let threshold_met = threshold > required_threshold;
+
+    let projected_status = match proposal.status {
+        ProposalStatus::Active => {
+            if quorum_met && threshold_met {
+                ProposalStatus::Passed
+            } else {
+                ProposalStatus::Rejected
+            }
+        }
+        _ => proposal.status.clone(),
+    };
+
+    Ok(ProposalResultResponse {
+        proposal_id: proposal.proposal_id,
+        quorum,
+        threshold,
+        quorum_met,
+        threshold_met,
+        projected_status,
+    })
+}
+
+/// The total voting power is defined as the sum of two parts:
+///
+/// - Free voting power: the total supply of xMARS token at the block
+/// - Locked voting power: the total amount of MARS token locked in the vesting contract, at the
+///   block
+///
+/// The reason we can use the amount of MARS (instead of xMARS) for locked voting power is that,
+/// since vesting allocations can only be created when 1 MARS == 1 xMARS, these MARS tokens would
+/// have produced the same amount of xMARS if they were staked.
+fn get_total_voting_power_at(
+    querier: &QuerierWrapper,
+    xmars_token_address: Addr,
+    vesting_address: Addr,
+    block: u64,
+) -> StdResult<TotalVotingPowerResponse> {
+    let free = xmars_get_total_supply_at(querier, xmars_token_address, block)?;
+    let locked = vesting_get_total_voting_power_at(querier, vesting_address, block)?;
+
+    Ok(TotalVotingPowerResponse {
+        height: block,
+        free,
+        locked,
+        total: free.checked_add(locked)?,
+    })
+}
+
+fn xmars_get_total_supply_at(
+    querier: &QuerierWrapper,
+    xmars_address: Addr,
+    block: u64,
+) -> StdResult<Uint128> {
+    let query: xmars_token::TotalSupplyResponse =
         querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: xmars_address.into(),
             msg: to_binary(&xmars_token::msg::QueryMsg::TotalSupplyAt { block })?,
@@ -713,8 +1593,11 @@ mod tests {
     };
 
     use crate::msg::ExecuteMsg::UpdateConfig;
+    use crate::ProposalKindRequirements;
 
     const TEST_PROPOSAL_VOTING_PERIOD: u64 = 2000;
+    const TEST_PROPOSAL_MIN_VOTING_PERIOD: u64 = 100;
+    const TEST_PROPOSAL_MAX_VOTING_PERIOD: u64 = 5000;
     const TEST_PROPOSAL_EFFECTIVE_DELAY: u64 = 200;
     const TEST_PROPOSAL_EXPIRATION_PERIOD: u64 = 300;
     const TEST_PROPOSAL_REQUIRED_DEPOSIT: Uint128 = Uint128::new(10000);
@@ -731,10 +1614,16 @@ mod tests {
                 address_provider_address: None,
 
                 proposal_voting_period: None,
+                proposal_min_voting_period: None,
+                proposal_max_voting_period: None,
                 proposal_effective_delay: None,
                 proposal_expiration_period: None,
                 proposal_required_deposit: None,
                 proposal_required_threshold: None,
+                proposal_submission_cooldown: None,
+                proposal_voting_delay: None,
+                proposal_max_voter_power_share: None,
+                proposal_kind_requirements: None,
                 proposal_required_quorum: None,
             };
             let msg = InstantiateMsg {
@@ -747,6 +1636,8 @@ mod tests {
         let init_config = CreateOrUpdateConfig {
             address_provider_address: Some(String::from("address_provider")),
             proposal_voting_period: Some(1),
+            proposal_min_voting_period: Some(1),
+            proposal_max_voting_period: Some(10_000),
             proposal_effective_delay: Some(1),
             proposal_expiration_period: Some(1),
             proposal_required_deposit: Some(Uint128::new(1)),
@@ -754,6 +1645,10 @@ mod tests {
             proposal_required_threshold: Some(Decimal::percent(
                 MINIMUM_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE,
             )),
+            proposal_submission_cooldown: None,
+            proposal_voting_delay: None,
+            proposal_max_voter_power_share: None,
+            proposal_kind_requirements: None,
         };
 
         // *
@@ -777,6 +1672,40 @@ mod tests {
                 .into()
             );
 
+            // init with zero proposal_max_voter_power_share
+            let config = CreateOrUpdateConfig {
+                proposal_max_voter_power_share: Some(Decimal::zero()),
+                ..init_config.clone()
+            };
+            let msg = InstantiateMsg { config };
+            let error_res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(
+                error_res,
+                MarsError::InvalidParam {
+                    param_name: "proposal_max_voter_power_share".to_string(),
+                    invalid_value: "0".to_string(),
+                    predicate: "> 0".to_string(),
+                }
+                .into()
+            );
+
+            // init with proposal_voting_period outside of min and max voting periods
+            let config = CreateOrUpdateConfig {
+                proposal_voting_period: Some(10_001),
+                ..init_config.clone()
+            };
+            let msg = InstantiateMsg { config };
+            let error_res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+            assert_eq!(
+                error_res,
+                MarsError::InvalidParam {
+                    param_name: "proposal_voting_period".to_string(),
+                    invalid_value: "10001".to_string(),
+                    predicate: ">= 1 and <= 10000".to_string(),
+                }
+                .into()
+            );
+
             // init with proposal_required_threshold less than 50%
             let config = CreateOrUpdateConfig {
                 proposal_required_threshold: Some(Decimal::percent(49)),
@@ -842,12 +1771,18 @@ mod tests {
             address_provider_address: Some(String::from("address_provider")),
 
             proposal_voting_period: Some(10),
+            proposal_min_voting_period: Some(1),
+            proposal_max_voting_period: Some(10_000),
             proposal_effective_delay: Some(11),
             proposal_expiration_period: Some(12),
             proposal_required_deposit: Some(Uint128::new(111)),
             proposal_required_threshold: Some(Decimal::percent(
                 MINIMUM_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE,
             )),
+            proposal_submission_cooldown: None,
+            proposal_voting_delay: None,
+            proposal_max_voter_power_share: None,
+            proposal_kind_requirements: None,
             proposal_required_quorum: Some(Decimal::one()),
         };
         let msg = InstantiateMsg {
@@ -936,10 +1871,16 @@ mod tests {
                 address_provider_address: Some(String::from("new_address_provider")),
 
                 proposal_voting_period: Some(101),
+                proposal_min_voting_period: Some(50),
+                proposal_max_voting_period: Some(5_000),
                 proposal_effective_delay: Some(111),
                 proposal_expiration_period: Some(121),
                 proposal_required_deposit: Some(Uint128::new(1111)),
                 proposal_required_threshold: Some(Decimal::from_ratio(4u128, 5u128)),
+                proposal_submission_cooldown: None,
+                proposal_voting_delay: None,
+                proposal_max_voter_power_share: None,
+                proposal_kind_requirements: None,
                 proposal_required_quorum: Some(Decimal::from_ratio(1u128, 5u128)),
             };
             let msg = UpdateConfig {
@@ -960,6 +1901,14 @@ mod tests {
                 new_config.proposal_voting_period,
                 config.proposal_voting_period.unwrap()
             );
+            assert_eq!(
+                new_config.proposal_min_voting_period,
+                config.proposal_min_voting_period.unwrap()
+            );
+            assert_eq!(
+                new_config.proposal_max_voting_period,
+                config.proposal_max_voting_period.unwrap()
+            );
             assert_eq!(
                 new_config.proposal_effective_delay,
                 config.proposal_effective_delay.unwrap()
@@ -996,7 +1945,9 @@ mod tests {
                     title: "a".to_string(),
                     description: "A valid description".to_string(),
                     link: None,
+                    kind: ProposalKind::TextOnly,
                     messages: None,
+                    voting_period_override: None,
                 })
                 .unwrap(),
                 sender: String::from("submitter"),
@@ -1014,7 +1965,9 @@ mod tests {
                     title: (0..100).map(|_| "a").collect::<String>(),
                     description: "A valid description".to_string(),
                     link: None,
+                    kind: ProposalKind::TextOnly,
                     messages: None,
+                    voting_period_override: None,
                 })
                 .unwrap(),
                 sender: String::from("submitter"),
@@ -1035,7 +1988,9 @@ mod tests {
                     title: "A valid Title".to_string(),
                     description: "a".to_string(),
                     link: None,
+                    kind: ProposalKind::TextOnly,
                     messages: None,
+                    voting_period_override: None,
                 })
                 .unwrap(),
                 sender: String::from("submitter"),
@@ -1056,7 +2011,9 @@ mod tests {
                     title: "A valid Title".to_string(),
                     description: (0..1030).map(|_| "a").collect::<String>(),
                     link: None,
+                    kind: ProposalKind::TextOnly,
                     messages: None,
+                    voting_period_override: None,
                 })
                 .unwrap(),
                 sender: String::from("submitter"),
@@ -1080,7 +2037,9 @@ mod tests {
                     title: "A valid Title".to_string(),
                     description: "A valid description".to_string(),
                     link: Some("a".to_string()),
+                    kind: ProposalKind::TextOnly,
                     messages: None,
+                    voting_period_override: None,
                 })
                 .unwrap(),
                 sender: String::from("submitter"),
@@ -1098,7 +2057,9 @@ mod tests {
                     title: "A valid Title".to_string(),
                     description: "A valid description".to_string(),
                     link: Some((0..150).map(|_| "a").collect::<String>()),
+                    kind: ProposalKind::TextOnly,
                     messages: None,
+                    voting_period_override: None,
                 })
                 .unwrap(),
                 sender: String::from("submitter"),
@@ -1110,6 +2071,116 @@ mod tests {
             assert_eq!(response, ContractError::invalid_proposal("Link too long"));
         }
 
+        // *
+        // Invalid messages
+        // *
+        {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                msg: to_binary(&ReceiveMsg::SubmitProposal {
+                    title: "A valid Title".to_string(),
+                    description: "A valid description".to_string(),
+                    link: None,
+                    kind: ProposalKind::TextOnly,
+                    messages: Some(vec![ProposalMessage {
+                        execution_order: 0,
+                        msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                            contract_addr: String::from(MOCK_CONTRACT_ADDR),
+                            msg: to_binary(&ExecuteMsg::UpdateConfig {
+                                config: CreateOrUpdateConfig::default(),
+                            })
+                            .unwrap(),
+                            funds: vec![],
+                        }),
+                    }]),
+                    voting_period_override: None,
+                })
+                .unwrap(),
+                sender: String::from("submitter"),
+                amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+            });
+            let env = mock_env(MockEnvParams::default());
+            let info = mock_info("mars_token");
+            let response = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert_eq!(
+                response,
+                ContractError::invalid_proposal("text only proposal cannot have messages")
+            );
+        }
+
+        for messages in [None, Some(vec![])] {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                msg: to_binary(&ReceiveMsg::SubmitProposal {
+                    title: "A valid Title".to_string(),
+                    description: "A valid description".to_string(),
+                    link: None,
+                    kind: ProposalKind::ParameterChange,
+                    messages,
+                    voting_period_override: None,
+                })
+                .unwrap(),
+                sender: String::from("submitter"),
+                amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+            });
+            let env = mock_env(MockEnvParams::default());
+            let info = mock_info("mars_token");
+            let response = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert_eq!(
+                response,
+                ContractError::invalid_proposal(
+                    "proposal must have at least one message unless it is text only"
+                )
+            );
+        }
+
+        // *
+        // Invalid voting period override
+        // *
+        {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                msg: to_binary(&ReceiveMsg::SubmitProposal {
+                    title: "A valid Title".to_string(),
+                    description: "A valid description".to_string(),
+                    link: None,
+                    kind: ProposalKind::TextOnly,
+                    messages: None,
+                    voting_period_override: Some(TEST_PROPOSAL_MIN_VOTING_PERIOD - 1),
+                })
+                .unwrap(),
+                sender: String::from("submitter"),
+                amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+            });
+            let env = mock_env(MockEnvParams::default());
+            let info = mock_info("mars_token");
+            let response = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert_eq!(
+                response,
+                ContractError::invalid_proposal("voting period override too short")
+            );
+        }
+
+        {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                msg: to_binary(&ReceiveMsg::SubmitProposal {
+                    title: "A valid Title".to_string(),
+                    description: "A valid description".to_string(),
+                    link: None,
+                    kind: ProposalKind::TextOnly,
+                    messages: None,
+                    voting_period_override: Some(TEST_PROPOSAL_MAX_VOTING_PERIOD + 1),
+                })
+                .unwrap(),
+                sender: String::from("submitter"),
+                amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+            });
+            let env = mock_env(MockEnvParams::default());
+            let info = mock_info("mars_token");
+            let response = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert_eq!(
+                response,
+                ContractError::invalid_proposal("voting period override too long")
+            );
+        }
+
         // *
         // Invalid deposit amount
         // *
@@ -1119,7 +2190,9 @@ mod tests {
                     title: "A valid Title".to_string(),
                     description: "A valid description".to_string(),
                     link: None,
+                    kind: ProposalKind::TextOnly,
                     messages: None,
+                    voting_period_override: None,
                 })
                 .unwrap(),
                 sender: String::from("submitter"),
@@ -1143,7 +2216,9 @@ mod tests {
                     title: "A valid Title".to_string(),
                     description: "A valid description".to_string(),
                     link: None,
+                    kind: ProposalKind::TextOnly,
                     messages: None,
+                    voting_period_override: None,
                 })
                 .unwrap(),
                 sender: String::from("submitter"),
@@ -1170,7 +2245,9 @@ mod tests {
                 title: "A valid title".to_string(),
                 description: "A valid description".to_string(),
                 link: None,
+                kind: ProposalKind::TextOnly,
                 messages: None,
+                voting_period_override: None,
             })
             .unwrap(),
             sender: submitter_address.to_string(),
@@ -1189,7 +2266,9 @@ mod tests {
                 attr("action", "submit_proposal"),
                 attr("submitter", "submitter"),
                 attr("proposal_id", 1.to_string()),
+                attr("proposal_start_height", 100_000.to_string()),
                 attr("proposal_end_height", expected_end_height.to_string()),
+                attr("deposit_amount", TEST_PROPOSAL_REQUIRED_DEPOSIT.to_string()),
             ]
         );
 
@@ -1216,6 +2295,7 @@ mod tests {
                 title: "A valid title".to_string(),
                 description: "A valid description".to_string(),
                 link: Some("https://www.avalidlink.com".to_string()),
+                kind: ProposalKind::ParameterChange,
                 messages: Some(vec![ProposalMessage {
                     execution_order: 0,
                     msg: CosmosMsg::Wasm(WasmMsg::Execute {
@@ -1227,6 +2307,7 @@ mod tests {
                         funds: vec![],
                     }),
                 }]),
+                voting_period_override: None,
             })
             .unwrap(),
             sender: submitter_address.to_string(),
@@ -1245,7 +2326,9 @@ mod tests {
                 attr("action", "submit_proposal"),
                 attr("submitter", "submitter"),
                 attr("proposal_id", 2.to_string()),
+                attr("proposal_start_height", 100_000.to_string()),
                 attr("proposal_end_height", expected_end_height.to_string()),
+                attr("deposit_amount", TEST_PROPOSAL_REQUIRED_DEPOSIT.to_string()),
             ]
         );
 
@@ -1271,11 +2354,165 @@ mod tests {
                 }),
             }])
         );
-    }
 
-    #[test]
-    fn test_invalid_cast_votes() {
-        let mut deps = th_setup(&[]);
+        // Submit Proposal with voting period override
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            msg: to_binary(&ReceiveMsg::SubmitProposal {
+                title: "A valid title".to_string(),
+                description: "A valid description".to_string(),
+                link: None,
+                kind: ProposalKind::TextOnly,
+                messages: None,
+                voting_period_override: Some(TEST_PROPOSAL_MIN_VOTING_PERIOD),
+            })
+            .unwrap(),
+            sender: submitter_address.to_string(),
+            amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+        });
+        let env = mock_env(MockEnvParams {
+            block_height: 100_000,
+            ..Default::default()
+        });
+        let info = mock_info("mars_token");
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let expected_end_height = 100_000 + TEST_PROPOSAL_MIN_VOTING_PERIOD;
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "submit_proposal"),
+                attr("submitter", "submitter"),
+                attr("proposal_id", 3.to_string()),
+                attr("proposal_start_height", 100_000.to_string()),
+                attr("proposal_end_height", expected_end_height.to_string()),
+                attr("deposit_amount", TEST_PROPOSAL_REQUIRED_DEPOSIT.to_string()),
+            ]
+        );
+
+        let proposal = PROPOSALS.load(&deps.storage, U64Key::new(3_u64)).unwrap();
+        assert_eq!(proposal.start_height, 100_000);
+        assert_eq!(proposal.end_height, expected_end_height);
+    }
+
+    #[test]
+    fn test_submit_proposal_cooldown() {
+        let mut deps = th_setup(&[]);
+
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.proposal_submission_cooldown = 1_000;
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+
+        let submit = |deps: DepsMut, submitter: &str, block_height: u64| {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                msg: to_binary(&ReceiveMsg::SubmitProposal {
+                    title: "A valid title".to_string(),
+                    description: "A valid description".to_string(),
+                    link: None,
+                    kind: ProposalKind::TextOnly,
+                    messages: None,
+                    voting_period_override: None,
+                })
+                .unwrap(),
+                sender: submitter.to_string(),
+                amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+            });
+            let env = mock_env(MockEnvParams {
+                block_height,
+                ..Default::default()
+            });
+            execute(deps, env, mock_info("mars_token"), msg)
+        };
+
+        submit(deps.as_mut(), "submitter", 100_000).unwrap();
+
+        // Resubmitting within the cooldown fails
+        let error_res = submit(deps.as_mut(), "submitter", 100_999).unwrap_err();
+        assert_eq!(
+            error_res,
+            ContractError::ProposalSubmissionCooldown {
+                next_submission_height: 101_000
+            }
+        );
+
+        // Other addresses are not affected
+        submit(deps.as_mut(), "other_submitter", 100_999).unwrap();
+
+        // Resubmitting after the cooldown succeeds
+        submit(deps.as_mut(), "submitter", 101_000).unwrap();
+
+        let global_state = GLOBAL_STATE.load(&deps.storage).unwrap();
+        assert_eq!(global_state.proposal_count, 3);
+        assert_eq!(
+            LAST_PROPOSAL_SUBMISSION_HEIGHTS
+                .load(&deps.storage, &Addr::unchecked("submitter"))
+                .unwrap(),
+            101_000
+        );
+    }
+
+    #[test]
+    fn test_submit_proposal_kinds() {
+        let mut deps = th_setup(&[]);
+
+        let proposal_kinds = vec![
+            ProposalKind::ParameterChange,
+            ProposalKind::ContractUpgrade,
+            ProposalKind::TreasurySpend,
+            ProposalKind::TextOnly,
+        ];
+
+        for (i, kind) in proposal_kinds.into_iter().enumerate() {
+            let messages = if kind == ProposalKind::TextOnly {
+                None
+            } else {
+                Some(vec![ProposalMessage {
+                    execution_order: 0,
+                    msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: String::from(MOCK_CONTRACT_ADDR),
+                        msg: to_binary(&ExecuteMsg::UpdateConfig {
+                            config: CreateOrUpdateConfig::default(),
+                        })
+                        .unwrap(),
+                        funds: vec![],
+                    }),
+                }])
+            };
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                msg: to_binary(&ReceiveMsg::SubmitProposal {
+                    title: "A valid title".to_string(),
+                    description: "A valid description".to_string(),
+                    link: None,
+                    kind: kind.clone(),
+                    messages,
+                    voting_period_override: None,
+                })
+                .unwrap(),
+                sender: String::from("submitter"),
+                amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+            });
+            let env = mock_env(MockEnvParams::default());
+            let info = mock_info("mars_token");
+            execute(deps.as_mut(), env, info, msg).unwrap();
+
+            let proposal_id = i as u64 + 1;
+            let proposal = PROPOSALS
+                .load(&deps.storage, U64Key::new(proposal_id))
+                .unwrap();
+            assert_eq!(proposal.kind, kind);
+
+            let res = query(
+                deps.as_ref(),
+                mock_env(MockEnvParams::default()),
+                QueryMsg::Proposal { proposal_id },
+            )
+            .unwrap();
+            let queried_proposal: Proposal = from_binary(&res).unwrap();
+            assert_eq!(queried_proposal.kind, kind);
+        }
+    }
+
+    #[test]
+    fn test_invalid_cast_votes() {
+        let mut deps = th_setup(&[]);
         let voter_address = Addr::unchecked("valid_voter");
         let invalid_voter_address = Addr::unchecked("invalid_voter");
 
@@ -1351,6 +2588,23 @@ mod tests {
             assert_eq!(response, ContractError::ProposalNotActive {});
         }
 
+        // *
+        // voting before proposal start should fail
+        // *
+        {
+            let msg = ExecuteMsg::CastVote {
+                proposal_id: active_proposal_id,
+                vote: ProposalVoteOption::For,
+            };
+            let env = mock_env(MockEnvParams {
+                block_height: 99_999,
+                ..Default::default()
+            });
+            let info = mock_info("valid_voter");
+            let response = execute(deps.as_mut(), env, info, msg).unwrap_err();
+            assert_eq!(response, ContractError::VoteVotingPeriodNotStarted {});
+        }
+
         // *
         // voting after proposal end should fail
         // *
@@ -1386,6 +2640,147 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_amend_proposal() {
+        let mut deps = th_setup(&[]);
+
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.proposal_voting_delay = 100;
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            msg: to_binary(&ReceiveMsg::SubmitProposal {
+                title: "A valid title".to_string(),
+                description: "A valid description".to_string(),
+                link: None,
+                kind: ProposalKind::ParameterChange,
+                messages: Some(vec![ProposalMessage {
+                    execution_order: 0,
+                    msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: String::from(MOCK_CONTRACT_ADDR),
+                        msg: to_binary(&ExecuteMsg::UpdateConfig {
+                            config: CreateOrUpdateConfig::default(),
+                        })
+                        .unwrap(),
+                        funds: vec![],
+                    }),
+                }]),
+                voting_period_override: None,
+            })
+            .unwrap(),
+            sender: String::from("submitter"),
+            amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+        });
+        let env = mock_env(MockEnvParams {
+            block_height: 100_000,
+            ..Default::default()
+        });
+        execute(deps.as_mut(), env, mock_info("mars_token"), msg).unwrap();
+
+        let proposal = PROPOSALS.load(&deps.storage, U64Key::new(1)).unwrap();
+        assert_eq!(proposal.start_height, 100_100);
+        assert_eq!(proposal.end_height, 100_100 + TEST_PROPOSAL_VOTING_PERIOD);
+
+        let new_messages = vec![ProposalMessage {
+            execution_order: 0,
+            msg: CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from(MOCK_CONTRACT_ADDR),
+                msg: to_binary(&ExecuteMsg::UpdateConfig {
+                    config: CreateOrUpdateConfig {
+                        proposal_voting_delay: Some(0),
+                        ..Default::default()
+                    },
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        }];
+        let amend_msg = ExecuteMsg::AmendProposal {
+            proposal_id: 1,
+            title: Some("An amended title".to_string()),
+            description: None,
+            link: Some("https://www.avalidlink.com".to_string()),
+            messages: Some(new_messages.clone()),
+        };
+
+        // only the submitter can amend the proposal
+        {
+            let env = mock_env(MockEnvParams {
+                block_height: 100_050,
+                ..Default::default()
+            });
+            let error_res =
+                execute(deps.as_mut(), env, mock_info("someone"), amend_msg.clone()).unwrap_err();
+            assert_eq!(error_res, MarsError::Unauthorized {}.into());
+        }
+
+        // amended proposal is validated
+        {
+            let env = mock_env(MockEnvParams {
+                block_height: 100_050,
+                ..Default::default()
+            });
+            let msg = ExecuteMsg::AmendProposal {
+                proposal_id: 1,
+                title: None,
+                description: None,
+                link: None,
+                messages: Some(vec![]),
+            };
+            let error_res = execute(deps.as_mut(), env, mock_info("submitter"), msg).unwrap_err();
+            assert_eq!(
+                error_res,
+                ContractError::invalid_proposal(
+                    "proposal must have at least one message unless it is text only"
+                )
+            );
+        }
+
+        // submitter can amend the proposal before voting starts
+        {
+            let env = mock_env(MockEnvParams {
+                block_height: 100_099,
+                ..Default::default()
+            });
+            let res = execute(
+                deps.as_mut(),
+                env,
+                mock_info("submitter"),
+                amend_msg.clone(),
+            )
+            .unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "amend_proposal"),
+                    attr("proposal_id", "1"),
+                    attr("submitter", "submitter"),
+                ]
+            );
+
+            let proposal = PROPOSALS.load(&deps.storage, U64Key::new(1)).unwrap();
+            assert_eq!(proposal.title, "An amended title");
+            assert_eq!(proposal.description, "A valid description");
+            assert_eq!(
+                proposal.link,
+                Some("https://www.avalidlink.com".to_string())
+            );
+            assert_eq!(proposal.messages, Some(new_messages));
+            assert_eq!(proposal.start_height, 100_100);
+        }
+
+        // proposal cannot be amended once voting starts
+        {
+            let env = mock_env(MockEnvParams {
+                block_height: 100_100,
+                ..Default::default()
+            });
+            let error_res =
+                execute(deps.as_mut(), env, mock_info("submitter"), amend_msg).unwrap_err();
+            assert_eq!(error_res, ContractError::ProposalAmendmentNotAllowed {});
+        }
+    }
+
     #[test]
     fn test_cast_vote() {
         // setup
@@ -1538,24 +2933,370 @@ mod tests {
             execute(deps.as_mut(), env, info, msg).unwrap();
         }
 
-        {
-            let msg = ExecuteMsg::CastVote {
-                proposal_id: active_proposal_id,
-                vote: ProposalVoteOption::Against,
+        {
+            let msg = ExecuteMsg::CastVote {
+                proposal_id: active_proposal_id,
+                vote: ProposalVoteOption::Against,
+            };
+            let env = mock_env(MockEnvParams {
+                block_height: active_proposal.start_height + 1,
+                ..Default::default()
+            });
+            let info = mock_info("voter4");
+            execute(deps.as_mut(), env, info, msg).unwrap();
+        }
+
+        let proposal = PROPOSALS
+            .load(&deps.storage, U64Key::new(active_proposal_id))
+            .unwrap();
+        assert_eq!(proposal.for_votes, Uint128::new(123 + 300));
+        assert_eq!(proposal.against_votes, Uint128::new(200 + 400));
+    }
+
+    #[test]
+    fn test_cast_vote_max_voter_power_share() {
+        let mut deps = th_setup(&[]);
+
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.proposal_max_voter_power_share = Decimal::percent(10);
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+
+        deps.querier
+            .set_xmars_address(Addr::unchecked("xmars_token"));
+        deps.querier.set_vesting_address(Addr::unchecked("vesting"));
+        deps.querier
+            .set_xmars_total_supply_at(99_999, Uint128::new(800_000));
+        deps.querier
+            .set_vesting_total_voting_power_at(99_999, Uint128::new(200_000));
+        deps.querier
+            .set_xmars_balance_at(Addr::unchecked("whale"), 99_999, Uint128::new(400_000));
+        deps.querier.set_vesting_voting_power_at(
+            Addr::unchecked("whale"),
+            99_999,
+            Uint128::new(100_000),
+        );
+        deps.querier
+            .set_xmars_balance_at(Addr::unchecked("voter"), 99_999, Uint128::new(50_000));
+
+        th_build_mock_proposal(
+            deps.as_mut(),
+            MockProposal {
+                id: 1,
+                status: ProposalStatus::Active,
+                start_height: 100_000,
+                end_height: 100_100,
+                ..Default::default()
+            },
+        );
+
+        let env = mock_env(MockEnvParams {
+            block_height: 100_001,
+            ..Default::default()
+        });
+
+        // whale voting power is clamped to 10% of the total voting power
+        let msg = ExecuteMsg::CastVote {
+            proposal_id: 1,
+            vote: ProposalVoteOption::For,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("whale"), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "cast_vote"),
+                attr("proposal_id", "1"),
+                attr("voter", "whale"),
+                attr("vote", "for"),
+                attr("voting_power", "100000"),
+            ]
+        );
+        let whale_vote = PROPOSAL_VOTES
+            .load(&deps.storage, (U64Key::new(1), &Addr::unchecked("whale")))
+            .unwrap();
+        assert_eq!(whale_vote.power, Uint128::new(100_000));
+
+        // voting power below the cap is fully counted
+        let msg = ExecuteMsg::CastVote {
+            proposal_id: 1,
+            vote: ProposalVoteOption::Against,
+        };
+        execute(deps.as_mut(), env, mock_info("voter"), msg).unwrap();
+
+        let proposal = PROPOSALS.load(&deps.storage, U64Key::new(1)).unwrap();
+        assert_eq!(proposal.for_votes, Uint128::new(100_000));
+        assert_eq!(proposal.against_votes, Uint128::new(50_000));
+    }
+
+    #[test]
+    fn test_delegate_voting_power() {
+        let mut deps = th_setup(&[]);
+        let delegate_address = Addr::unchecked("delegate");
+        let delegator_address = Addr::unchecked("delegator");
+        let other_delegator_address = Addr::unchecked("other_delegator");
+
+        deps.querier
+            .set_xmars_address(Addr::unchecked("xmars_token"));
+        deps.querier.set_vesting_address(Addr::unchecked("vesting"));
+        deps.querier
+            .set_xmars_balance_at(delegate_address.clone(), 99_999, Uint128::new(100));
+        deps.querier
+            .set_xmars_balance_at(delegator_address.clone(), 99_999, Uint128::new(200));
+        deps.querier.set_vesting_voting_power_at(
+            delegator_address.clone(),
+            99_999,
+            Uint128::new(50),
+        );
+        deps.querier.set_xmars_balance_at(
+            other_delegator_address.clone(),
+            99_999,
+            Uint128::new(300),
+        );
+
+        for id in 1..=2 {
+            th_build_mock_proposal(
+                deps.as_mut(),
+                MockProposal {
+                    id,
+                    status: ProposalStatus::Active,
+                    start_height: 100_000,
+                    end_height: 100_100,
+                    ..Default::default()
+                },
+            );
+        }
+        let env = mock_env(MockEnvParams {
+            block_height: 100_001,
+            ..Default::default()
+        });
+
+        // cannot delegate to self
+        let msg = ExecuteMsg::DelegateVotingPower {
+            delegate: "delegator".to_string(),
+        };
+        let error_res =
+            execute(deps.as_mut(), env.clone(), mock_info("delegator"), msg).unwrap_err();
+        assert_eq!(error_res, ContractError::DelegateToSelf {});
+
+        // cannot revoke without delegation
+        let error_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("delegator"),
+            ExecuteMsg::RevokeDelegation {},
+        )
+        .unwrap_err();
+        assert_eq!(error_res, ContractError::DelegationNotFound {});
+
+        // delegate
+        for delegator in ["delegator", "other_delegator"] {
+            let msg = ExecuteMsg::DelegateVotingPower {
+                delegate: "delegate".to_string(),
+            };
+            let res = execute(deps.as_mut(), env.clone(), mock_info(delegator), msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "delegate_voting_power"),
+                    attr("delegator", delegator),
+                    attr("delegate", "delegate"),
+                ]
+            );
+        }
+        assert!(DELEGATIONS
+            .may_load(&deps.storage, &delegator_address)
+            .unwrap()
+            .is_none());
+
+        // only the requested delegate can accept the delegation
+        let msg = ExecuteMsg::AcceptDelegation {
+            delegator: "delegator".to_string(),
+        };
+        let error_res = execute(deps.as_mut(), env.clone(), mock_info("other"), msg).unwrap_err();
+        assert_eq!(error_res, ContractError::DelegationNotFound {});
+
+        for delegator in ["delegator", "other_delegator"] {
+            let msg = ExecuteMsg::AcceptDelegation {
+                delegator: delegator.to_string(),
+            };
+            let res = execute(deps.as_mut(), env.clone(), mock_info("delegate"), msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "accept_delegation"),
+                    attr("delegator", delegator),
+                    attr("delegate", "delegate"),
+                ]
+            );
+        }
+        assert_eq!(
+            DELEGATIONS.load(&deps.storage, &delegator_address).unwrap(),
+            delegate_address
+        );
+        assert!(PENDING_DELEGATIONS
+            .may_load(&deps.storage, &delegator_address)
+            .unwrap()
+            .is_none());
+
+        // a delegation can only be accepted once
+        let msg = ExecuteMsg::AcceptDelegation {
+            delegator: "delegator".to_string(),
+        };
+        let error_res =
+            execute(deps.as_mut(), env.clone(), mock_info("delegate"), msg).unwrap_err();
+        assert_eq!(error_res, ContractError::DelegationNotFound {});
+
+        // other delegator votes on proposal 1 by itself so it's not counted by delegate
+        let msg = ExecuteMsg::CastVote {
+            proposal_id: 1,
+            vote: ProposalVoteOption::Against,
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other_delegator"),
+            msg,
+        )
+        .unwrap();
+
+        // delegate votes with own and delegated voting power
+        let msg = ExecuteMsg::CastVote {
+            proposal_id: 1,
+            vote: ProposalVoteOption::For,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("delegate"), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "cast_vote"),
+                attr("proposal_id", "1"),
+                attr("voter", "delegate"),
+                attr("vote", "for"),
+                attr("voting_power", 350.to_string()), // 100 (own) + 200 + 50 (delegated)
+            ]
+        );
+
+        let proposal = PROPOSALS.load(&deps.storage, U64Key::new(1)).unwrap();
+        assert_eq!(proposal.for_votes, Uint128::new(350));
+        assert_eq!(proposal.against_votes, Uint128::new(300));
+
+        let delegate_vote = PROPOSAL_VOTES
+            .load(&deps.storage, (U64Key::new(1), &delegate_address))
+            .unwrap();
+        assert_eq!(delegate_vote.power, Uint128::new(100));
+        let delegator_vote = PROPOSAL_VOTES
+            .load(&deps.storage, (U64Key::new(1), &delegator_address))
+            .unwrap();
+        assert_eq!(delegator_vote.option, ProposalVoteOption::For);
+        assert_eq!(delegator_vote.power, Uint128::new(250));
+
+        // delegator cannot vote again after its power was used by the delegate
+        let msg = ExecuteMsg::CastVote {
+            proposal_id: 1,
+            vote: ProposalVoteOption::Against,
+        };
+        let error_res =
+            execute(deps.as_mut(), env.clone(), mock_info("delegator"), msg).unwrap_err();
+        assert_eq!(error_res, ContractError::VoteUserAlreadyVoted {});
+
+        // revoke delegation
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("delegator"),
+            ExecuteMsg::RevokeDelegation {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "revoke_delegation"),
+                attr("delegator", "delegator"),
+                attr("delegate", "delegate"),
+            ]
+        );
+        assert!(DELEGATIONS
+            .may_load(&deps.storage, &delegator_address)
+            .unwrap()
+            .is_none());
+
+        // delegate only counts the remaining delegator on proposal 2
+        let msg = ExecuteMsg::CastVote {
+            proposal_id: 2,
+            vote: ProposalVoteOption::For,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("delegate"), msg).unwrap();
+        let proposal = PROPOSALS.load(&deps.storage, U64Key::new(2)).unwrap();
+        assert_eq!(proposal.for_votes, Uint128::new(400)); // 100 (own) + 300 (delegated)
+
+        // revoked delegator can vote by itself
+        let msg = ExecuteMsg::CastVote {
+            proposal_id: 2,
+            vote: ProposalVoteOption::Against,
+        };
+        execute(deps.as_mut(), env, mock_info("delegator"), msg).unwrap();
+        let proposal = PROPOSALS.load(&deps.storage, U64Key::new(2)).unwrap();
+        assert_eq!(proposal.against_votes, Uint128::new(250));
+    }
+
+    #[test]
+    fn test_delegation_limits() {
+        let mut deps = th_setup(&[]);
+        let delegate_address = Addr::unchecked("delegate");
+        let env = mock_env(MockEnvParams::default());
+
+        // pending delegations can be revoked
+        let msg = ExecuteMsg::DelegateVotingPower {
+            delegate: "delegate".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info("pending"), msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("pending"),
+            ExecuteMsg::RevokeDelegation {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "revoke_delegation"),
+                attr("delegator", "pending"),
+                attr("delegate", "delegate"),
+            ]
+        );
+        let msg = ExecuteMsg::AcceptDelegation {
+            delegator: "pending".to_string(),
+        };
+        let error_res =
+            execute(deps.as_mut(), env.clone(), mock_info("delegate"), msg).unwrap_err();
+        assert_eq!(error_res, ContractError::DelegationNotFound {});
+
+        // delegates cannot accept more than the max delegators
+        for i in 0..=MAX_DELEGATORS_PER_DELEGATE {
+            let delegator = format!("delegator{}", i);
+            let msg = ExecuteMsg::DelegateVotingPower {
+                delegate: "delegate".to_string(),
             };
-            let env = mock_env(MockEnvParams {
-                block_height: active_proposal.start_height + 1,
-                ..Default::default()
-            });
-            let info = mock_info("voter4");
-            execute(deps.as_mut(), env, info, msg).unwrap();
+            execute(deps.as_mut(), env.clone(), mock_info(&delegator), msg).unwrap();
+
+            let msg = ExecuteMsg::AcceptDelegation { delegator };
+            let res = execute(deps.as_mut(), env.clone(), mock_info("delegate"), msg);
+            if i < MAX_DELEGATORS_PER_DELEGATE {
+                res.unwrap();
+            } else {
+                assert_eq!(
+                    res.unwrap_err(),
+                    ContractError::DelegateMaxDelegatorsReached {
+                        max_delegators: MAX_DELEGATORS_PER_DELEGATE
+                    }
+                );
+            }
         }
-
-        let proposal = PROPOSALS
-            .load(&deps.storage, U64Key::new(active_proposal_id))
-            .unwrap();
-        assert_eq!(proposal.for_votes, Uint128::new(123 + 300));
-        assert_eq!(proposal.against_votes, Uint128::new(200 + 400));
+        let delegators_count = DELEGATORS
+            .prefix(&delegate_address)
+            .keys(&deps.storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(delegators_count, MAX_DELEGATORS_PER_DELEGATE);
     }
 
     #[test]
@@ -1744,6 +3485,10 @@ mod tests {
                 attr("action", "end_proposal"),
                 attr("proposal_id", 1.to_string()),
                 attr("proposal_result", "passed"),
+                attr("deposit", "refunded"),
+                attr("deposit_amount", TEST_PROPOSAL_REQUIRED_DEPOSIT.to_string()),
+                attr("for_votes", "11000"),
+                attr("against_votes", "10000"),
             ]
         );
 
@@ -1763,7 +3508,7 @@ mod tests {
         let final_passed_proposal = PROPOSALS.load(&deps.storage, U64Key::new(1u64)).unwrap();
         assert_eq!(final_passed_proposal.status, ProposalStatus::Passed);
 
-        // end rejected proposal (no quorum)
+        // end rejected proposal (no quorum), deposit is slashed
         let initial_passed_proposal = th_build_mock_proposal(
             deps.as_mut(),
             MockProposal {
@@ -1793,6 +3538,10 @@ mod tests {
                 attr("action", "end_proposal"),
                 attr("proposal_id", 2.to_string()),
                 attr("proposal_result", "rejected"),
+                attr("deposit", "slashed"),
+                attr("deposit_amount", TEST_PROPOSAL_REQUIRED_DEPOSIT.to_string()),
+                attr("for_votes", "11"),
+                attr("against_votes", "10"),
             ]
         );
 
@@ -1812,7 +3561,7 @@ mod tests {
         let final_passed_proposal = PROPOSALS.load(&deps.storage, U64Key::new(2_u64)).unwrap();
         assert_eq!(final_passed_proposal.status, ProposalStatus::Rejected);
 
-        // end rejected proposal (no threshold)
+        // end rejected proposal (quorum met but no threshold), deposit is refunded
         let initial_passed_proposal = th_build_mock_proposal(
             deps.as_mut(),
             MockProposal {
@@ -1842,6 +3591,10 @@ mod tests {
                 attr("action", "end_proposal"),
                 attr("proposal_id", 3.to_string()),
                 attr("proposal_result", "rejected"),
+                attr("deposit", "refunded"),
+                attr("deposit_amount", TEST_PROPOSAL_REQUIRED_DEPOSIT.to_string()),
+                attr("for_votes", "10000"),
+                attr("against_votes", "11000"),
             ]
         );
 
@@ -1849,12 +3602,12 @@ mod tests {
             res.messages,
             vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: String::from("mars_token"),
+                funds: vec![],
                 msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                    recipient: String::from("staking"),
+                    recipient: String::from("submitter"),
                     amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
                 })
                 .unwrap(),
-                funds: vec![],
             }))]
         );
 
@@ -1862,6 +3615,410 @@ mod tests {
         assert_eq!(final_passed_proposal.status, ProposalStatus::Rejected);
     }
 
+    #[test]
+    fn test_query_total_voting_power() {
+        let mut deps = th_setup(&[]);
+
+        deps.querier
+            .set_xmars_address(Addr::unchecked("xmars_token"));
+        deps.querier
+            .set_xmars_total_supply_at(89_999, Uint128::new(100_000));
+        deps.querier
+            .set_xmars_total_supply_at(99_999, Uint128::new(150_000));
+        deps.querier.set_vesting_address(Addr::unchecked("vesting"));
+        deps.querier
+            .set_vesting_total_voting_power_at(89_999, Uint128::new(20_000));
+        deps.querier
+            .set_vesting_total_voting_power_at(99_999, Uint128::new(10_000));
+
+        // at given height
+        let res: TotalVotingPowerResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(MockEnvParams::default()),
+                QueryMsg::TotalVotingPower {
+                    height: Some(89_999),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            TotalVotingPowerResponse {
+                height: 89_999,
+                free: Uint128::new(100_000),
+                locked: Uint128::new(20_000),
+                total: Uint128::new(120_000),
+            }
+        );
+
+        // at current height
+        let res: TotalVotingPowerResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(MockEnvParams {
+                    block_height: 99_999,
+                    ..Default::default()
+                }),
+                QueryMsg::TotalVotingPower { height: None },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            TotalVotingPowerResponse {
+                height: 99_999,
+                free: Uint128::new(150_000),
+                locked: Uint128::new(10_000),
+                total: Uint128::new(160_000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_query_proposal_deposits() {
+        let mut deps = th_setup(&[]);
+
+        deps.querier
+            .set_xmars_address(Addr::unchecked("xmars_token"));
+        deps.querier
+            .set_xmars_total_supply_at(89_999, Uint128::new(100_000));
+        deps.querier.set_vesting_address(Addr::unchecked("vesting"));
+        deps.querier
+            .set_vesting_total_voting_power_at(89_999, Uint128::zero());
+
+        CONFIG
+            .update(&mut deps.storage, |mut config| -> StdResult<Config> {
+                config.proposal_required_threshold = Decimal::from_ratio(51_u128, 100_u128);
+                config.proposal_required_quorum = Decimal::from_ratio(2_u128, 100_u128);
+                Ok(config)
+            })
+            .unwrap();
+
+        // proposal 1 meets quorum, proposal 2 does not and proposal 3 is still being voted on
+        for (id, for_votes, end_height) in [(1, 11_000, 100_000), (2, 11, 100_000), (3, 0, 200_000)]
+        {
+            th_build_mock_proposal(
+                deps.as_mut(),
+                MockProposal {
+                    id,
+                    status: ProposalStatus::Active,
+                    for_votes: Uint128::new(for_votes),
+                    against_votes: Uint128::new(10),
+                    start_height: 90_000,
+                    end_height,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let env = mock_env(MockEnvParams {
+            block_height: 100_001,
+            ..Default::default()
+        });
+        for proposal_id in [1, 2] {
+            let msg = ExecuteMsg::EndProposal { proposal_id };
+            execute(deps.as_mut(), env.clone(), mock_info("sender"), msg).unwrap();
+        }
+
+        let res: ProposalDepositsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ProposalDeposits {
+                    start: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.deposits,
+            vec![
+                ProposalDepositResponse {
+                    proposal_id: 1,
+                    status: ProposalDepositStatus::Refunded,
+                    amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+                },
+                ProposalDepositResponse {
+                    proposal_id: 2,
+                    status: ProposalDepositStatus::Slashed,
+                    amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+                },
+                ProposalDepositResponse {
+                    proposal_id: 3,
+                    status: ProposalDepositStatus::Locked,
+                    amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+                },
+            ]
+        );
+
+        // paginated
+        let res: ProposalDepositsResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::ProposalDeposits {
+                    start: Some(2),
+                    limit: Some(1),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.deposits,
+            vec![ProposalDepositResponse {
+                proposal_id: 2,
+                status: ProposalDepositStatus::Slashed,
+                amount: TEST_PROPOSAL_REQUIRED_DEPOSIT,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_end_proposal_with_kind_requirements() {
+        let mut deps = th_setup(&[]);
+
+        deps.querier
+            .set_xmars_address(Addr::unchecked("xmars_token"));
+        deps.querier
+            .set_xmars_total_supply_at(89_999, Uint128::new(100_000));
+        deps.querier.set_vesting_address(Addr::unchecked("vesting"));
+        deps.querier
+            .set_vesting_total_voting_power_at(89_999, Uint128::zero());
+
+        // contract upgrades need a higher threshold than other proposals
+        let proposal_kind_requirements = vec![ProposalKindRequirements {
+            kind: ProposalKind::ContractUpgrade,
+            required_quorum: Decimal::percent(2),
+            required_threshold: Decimal::percent(75),
+        }];
+        let msg = ExecuteMsg::UpdateConfig {
+            config: CreateOrUpdateConfig {
+                proposal_required_quorum: Some(Decimal::percent(2)),
+                proposal_required_threshold: Some(Decimal::percent(51)),
+                proposal_kind_requirements: Some(proposal_kind_requirements.clone()),
+                ..Default::default()
+            },
+        };
+        let env = mock_env(MockEnvParams::default());
+        execute(deps.as_mut(), env, mock_info(MOCK_CONTRACT_ADDR), msg).unwrap();
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(
+            config.proposal_kind_requirements,
+            proposal_kind_requirements
+        );
+
+        // same votes (70% in favor) on a parameter change and on a contract upgrade
+        for id in [1, 2] {
+            th_build_mock_proposal(
+                deps.as_mut(),
+                MockProposal {
+                    id,
+                    status: ProposalStatus::Active,
+                    for_votes: Uint128::new(7_000),
+                    against_votes: Uint128::new(3_000),
+                    start_height: 90_000,
+                    end_height: 100_000,
+                    messages: Some(vec![]),
+                },
+            );
+        }
+        PROPOSALS
+            .update(
+                &mut deps.storage,
+                U64Key::new(2),
+                |proposal| -> StdResult<Proposal> {
+                    let mut proposal = proposal.unwrap();
+                    proposal.kind = ProposalKind::ContractUpgrade;
+                    Ok(proposal)
+                },
+            )
+            .unwrap();
+
+        let env = mock_env(MockEnvParams {
+            block_height: 100_001,
+            ..Default::default()
+        });
+        for (proposal_id, expected_result, expected_status) in [
+            (1, "passed", ProposalStatus::Passed),
+            (2, "rejected", ProposalStatus::Rejected),
+        ] {
+            let msg = ExecuteMsg::EndProposal { proposal_id };
+            let res = execute(deps.as_mut(), env.clone(), mock_info("sender"), msg).unwrap();
+            assert_eq!(
+                res.attributes,
+                vec![
+                    attr("action", "end_proposal"),
+                    attr("proposal_id", proposal_id.to_string()),
+                    attr("proposal_result", expected_result),
+                    attr("deposit", "refunded"),
+                    attr("deposit_amount", TEST_PROPOSAL_REQUIRED_DEPOSIT.to_string()),
+                    attr("for_votes", "7000"),
+                    attr("against_votes", "3000"),
+                ]
+            );
+
+            let proposal = PROPOSALS
+                .load(&deps.storage, U64Key::new(proposal_id))
+                .unwrap();
+            assert_eq!(proposal.status, expected_status);
+        }
+
+        // only one entry per kind, with valid values
+        let mut invalid_config = config.clone();
+        invalid_config
+            .proposal_kind_requirements
+            .push(proposal_kind_requirements[0].clone());
+        assert_eq!(
+            invalid_config.validate().unwrap_err(),
+            ContractError::Mars(MarsError::InvalidParam {
+                param_name: "proposal_kind_requirements".to_string(),
+                invalid_value: "ContractUpgrade".to_string(),
+                predicate: "one entry per proposal kind".to_string(),
+            })
+        );
+
+        let mut invalid_config = config;
+        invalid_config.proposal_kind_requirements[0].required_threshold = Decimal::percent(45);
+        assert_eq!(
+            invalid_config.validate().unwrap_err(),
+            ContractError::Mars(MarsError::InvalidParam {
+                param_name: "required_threshold".to_string(),
+                invalid_value: Decimal::percent(45).to_string(),
+                predicate: ">= 0.5 and <= 1".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_query_proposal_result() {
+        let mut deps = th_setup(&[]);
+
+        deps.querier
+            .set_xmars_address(Addr::unchecked("xmars_token"));
+        deps.querier
+            .set_xmars_total_supply_at(89_999, Uint128::new(100_000));
+        deps.querier.set_vesting_address(Addr::unchecked("vesting"));
+        deps.querier
+            .set_vesting_total_voting_power_at(89_999, Uint128::new(100_000));
+
+        CONFIG
+            .update(&mut deps.storage, |mut config| -> StdResult<Config> {
+                config.proposal_required_threshold = Decimal::from_ratio(51_u128, 100_u128);
+                config.proposal_required_quorum = Decimal::from_ratio(10_u128, 100_u128);
+                Ok(config)
+            })
+            .unwrap();
+
+        // (status, for votes, against votes, expected quorum met, expected threshold met,
+        // expected projected status)
+        let cases = [
+            // passing
+            (
+                ProposalStatus::Active,
+                15_000_u128,
+                5_000_u128,
+                true,
+                true,
+                ProposalStatus::Passed,
+            ),
+            // no votes
+            (
+                ProposalStatus::Active,
+                0,
+                0,
+                false,
+                false,
+                ProposalStatus::Rejected,
+            ),
+            // threshold met but no quorum
+            (
+                ProposalStatus::Active,
+                19_000,
+                0,
+                false,
+                true,
+                ProposalStatus::Rejected,
+            ),
+            // quorum met but threshold not exceeded
+            (
+                ProposalStatus::Active,
+                8_000,
+                12_000,
+                true,
+                false,
+                ProposalStatus::Rejected,
+            ),
+            // threshold exactly equal to the required one is not enough
+            (
+                ProposalStatus::Active,
+                10_200,
+                9_800,
+                true,
+                false,
+                ProposalStatus::Rejected,
+            ),
+            // proposal already ended keeps its status
+            (
+                ProposalStatus::Executed,
+                0,
+                20_000,
+                true,
+                false,
+                ProposalStatus::Executed,
+            ),
+        ];
+
+        for (i, (status, for_votes, against_votes, quorum_met, threshold_met, projected_status)) in
+            cases.iter().enumerate()
+        {
+            let proposal_id = i as u64 + 1;
+            th_build_mock_proposal(
+                deps.as_mut(),
+                MockProposal {
+                    id: proposal_id,
+                    status: status.clone(),
+                    for_votes: Uint128::new(*for_votes),
+                    against_votes: Uint128::new(*against_votes),
+                    start_height: 90_000,
+                    end_height: 100_000,
+                    ..Default::default()
+                },
+            );
+
+            let res = query(
+                deps.as_ref(),
+                mock_env(MockEnvParams::default()),
+                QueryMsg::ProposalResult { proposal_id },
+            )
+            .unwrap();
+            let result: ProposalResultResponse = from_binary(&res).unwrap();
+
+            let total_votes = for_votes + against_votes;
+            let expected_threshold = if total_votes > 0 {
+                Decimal::from_ratio(*for_votes, total_votes)
+            } else {
+                Decimal::zero()
+            };
+
+            assert_eq!(result.proposal_id, proposal_id);
+            assert_eq!(
+                result.quorum,
+                Decimal::from_ratio(total_votes, 200_000_u128)
+            );
+            assert_eq!(result.threshold, expected_threshold);
+            assert_eq!(result.quorum_met, *quorum_met);
+            assert_eq!(result.threshold_met, *threshold_met);
+            assert_eq!(result.projected_status, *projected_status);
+        }
+    }
+
     #[test]
     fn test_invalid_execute_proposals() {
         let mut deps = th_setup(&[]);
@@ -2140,6 +4297,124 @@ mod tests {
     }
 
     // TEST HELPERS
+    #[test]
+    fn test_query_proposal_messages_summary() {
+        let mut deps = th_setup(&[]);
+
+        let update_config_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "red_bank".to_string(),
+            msg: to_binary(&red_bank::msg::ExecuteMsg::UpdateConfig {
+                config: red_bank::msg::CreateOrUpdateConfig {
+                    close_factor: Some(Decimal::percent(40)),
+                    max_markets_per_user: Some(5),
+                    ..Default::default()
+                },
+            })
+            .unwrap(),
+            funds: vec![],
+        });
+        let unknown_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "other_contract".to_string(),
+            msg: to_binary(&red_bank::msg::ExecuteMsg::UpdateConfig {
+                config: Default::default(),
+            })
+            .unwrap(),
+            funds: vec![],
+        });
+        let send_msg = CosmosMsg::Bank(BankMsg::Send {
+            to_address: "recipient".to_string(),
+            amount: vec![Coin::new(1_000, "uusd")],
+        });
+
+        th_build_mock_proposal(
+            deps.as_mut(),
+            MockProposal {
+                id: 1,
+                messages: Some(vec![
+                    ProposalMessage {
+                        execution_order: 2,
+                        msg: unknown_msg.clone(),
+                    },
+                    ProposalMessage {
+                        execution_order: 1,
+                        msg: update_config_msg,
+                    },
+                    ProposalMessage {
+                        execution_order: 3,
+                        msg: send_msg,
+                    },
+                ]),
+                ..Default::default()
+            },
+        );
+
+        let res: ProposalMessagesSummaryResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(MockEnvParams::default()),
+                QueryMsg::ProposalMessagesSummary { proposal_id: 1 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            ProposalMessagesSummaryResponse {
+                proposal_id: 1,
+                messages: vec![
+                    ProposalMessageSummary {
+                        execution_order: 1,
+                        description: Some("Update Red Bank config".to_string()),
+                        params: vec![
+                            ProposalMessageParam {
+                                name: "close_factor".to_string(),
+                                value: "0.4".to_string(),
+                            },
+                            ProposalMessageParam {
+                                name: "max_markets_per_user".to_string(),
+                                value: "5".to_string(),
+                            },
+                        ],
+                        raw: None,
+                    },
+                    // Messages to contracts that are not known are not decoded
+                    ProposalMessageSummary {
+                        execution_order: 2,
+                        description: None,
+                        params: vec![],
+                        raw: Some(to_binary(&unknown_msg).unwrap()),
+                    },
+                    ProposalMessageSummary {
+                        execution_order: 3,
+                        description: Some("Send 1000uusd to recipient".to_string()),
+                        params: vec![],
+                        raw: None,
+                    },
+                ],
+            }
+        );
+
+        // Proposal without messages
+        th_build_mock_proposal(
+            deps.as_mut(),
+            MockProposal {
+                id: 2,
+                ..Default::default()
+            },
+        );
+        let res: ProposalMessagesSummaryResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(MockEnvParams::default()),
+                QueryMsg::ProposalMessagesSummary { proposal_id: 2 },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+    }
+
     fn th_setup(contract_balances: &[Coin]) -> OwnedDeps<MockStorage, MockApi, MarsMockQuerier> {
         let mut deps = mock_dependencies(contract_balances);
 
@@ -2147,11 +4422,17 @@ mod tests {
             address_provider_address: Some(String::from("address_provider")),
 
             proposal_voting_period: Some(TEST_PROPOSAL_VOTING_PERIOD),
+            proposal_min_voting_period: Some(TEST_PROPOSAL_MIN_VOTING_PERIOD),
+            proposal_max_voting_period: Some(TEST_PROPOSAL_MAX_VOTING_PERIOD),
             proposal_effective_delay: Some(TEST_PROPOSAL_EFFECTIVE_DELAY),
             proposal_expiration_period: Some(TEST_PROPOSAL_EXPIRATION_PERIOD),
             proposal_required_deposit: Some(TEST_PROPOSAL_REQUIRED_DEPOSIT),
             proposal_required_quorum: Some(Decimal::one()),
             proposal_required_threshold: Some(Decimal::one()),
+            proposal_submission_cooldown: None,
+            proposal_voting_delay: None,
+            proposal_max_voter_power_share: None,
+            proposal_kind_requirements: None,
         };
 
         let msg = InstantiateMsg { config };
@@ -2192,6 +4473,11 @@ mod tests {
             proposal_id: mock_proposal.id,
             submitter_address: Addr::unchecked("submitter"),
             status: mock_proposal.status,
+            kind: if mock_proposal.messages.is_some() {
+                ProposalKind::ParameterChange
+            } else {
+                ProposalKind::TextOnly
+            },
             for_votes: mock_proposal.for_votes,
             against_votes: mock_proposal.against_votes,
             start_height: mock_proposal.start_height,
@@ -1,8 +1,20 @@
-use crate::{Config, GlobalState, Proposal, ProposalVote};
-use cosmwasm_std::Addr;
+use crate::{Config, GlobalState, Proposal, ProposalDepositStatus, ProposalVote};
+use cosmwasm_std::{Addr, Empty};
 use cw_storage_plus::{Item, Map, U64Key};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const GLOBAL_STATE: Item<GlobalState> = Item::new("global_state");
 pub const PROPOSALS: Map<U64Key, Proposal> = Map::new("proposals");
 pub const PROPOSAL_VOTES: Map<(U64Key, &Addr), ProposalVote> = Map::new("proposal_votes");
+/// What happened to the deposit of each ended proposal
+pub const PROPOSAL_DEPOSIT_STATUSES: Map<U64Key, ProposalDepositStatus> =
+    Map::new("proposal_deposit_statuses");
+/// Delegate of each delegator
+pub const DELEGATIONS: Map<&Addr, Addr> = Map::new("delegations");
+/// Delegate requested by each delegator, until the delegate accepts the delegation
+pub const PENDING_DELEGATIONS: Map<&Addr, Addr> = Map::new("pending_delegations");
+/// Delegators of each delegate, used to aggregate delegated voting power when the delegate votes
+pub const DELEGATORS: Map<(&Addr, &Addr), Empty> = Map::new("delegators");
+/// Block at which each address last submitted a proposal
+pub const LAST_PROPOSAL_SUBMISSION_HEIGHTS: Map<&Addr, u64> =
+    Map::new("last_proposal_submission_heights");
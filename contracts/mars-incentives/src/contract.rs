@@ -17,7 +17,10 @@ use mars_core::staking;
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use crate::state::{ASSET_INCENTIVES, CONFIG, USER_ASSET_INDICES, USER_UNCLAIMED_REWARDS};
-use crate::{AssetIncentive, AssetIncentiveResponse, Config};
+use crate::{
+    AssetIncentive, AssetIncentiveResponse, AssetUnclaimedRewards, Config, IncentiveSchedule,
+    UserUnclaimedRewardsBreakdownResponse,
+};
 
 // INIT
 
@@ -52,6 +55,10 @@ pub fn execute(
             ma_token_address,
             emission_per_second,
         } => execute_set_asset_incentive(deps, env, info, ma_token_address, emission_per_second),
+        ExecuteMsg::SetAssetIncentiveSchedule {
+            ma_token_address,
+            schedule,
+        } => execute_set_asset_incentive_schedule(deps, env, info, ma_token_address, schedule),
         ExecuteMsg::BalanceChange {
             user_address,
             user_balance_before,
@@ -64,7 +71,9 @@ pub fn execute(
             user_balance_before,
             total_supply_before,
         ),
-        ExecuteMsg::ClaimRewards {} => execute_claim_rewards(deps, env, info),
+        ExecuteMsg::ClaimRewards { ma_token_addresses } => {
+            execute_claim_rewards(deps, env, info, ma_token_addresses)
+        }
         ExecuteMsg::UpdateConfig {
             owner,
             address_provider_address,
@@ -119,6 +128,7 @@ pub fn execute_set_asset_incentive(
             emission_per_second,
             index: Decimal::zero(),
             last_updated: env.block.time.seconds(),
+            schedule: None,
         },
     };
 
@@ -132,6 +142,66 @@ pub fn execute_set_asset_incentive(
     Ok(response)
 }
 
+pub fn execute_set_asset_incentive_schedule(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ma_token_address: String,
+    schedule: Option<IncentiveSchedule>,
+) -> Result<Response, ContractError> {
+    // only owner can call this
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(MarsError::Unauthorized {}.into());
+    }
+
+    if let Some(schedule) = &schedule {
+        schedule.validate()?;
+    }
+
+    // use lower case address to prevent duplicate assets
+    let ma_token_address = ma_token_address.to_lowercase();
+    let ma_asset_address = deps.api.addr_validate(&ma_token_address)?;
+
+    let new_asset_incentive = match ASSET_INCENTIVES.may_load(deps.storage, &ma_asset_address)? {
+        Some(mut asset_incentive) => {
+            // Update index up to now with the previous emission
+            let total_supply =
+                mars_core::helpers::cw20_get_total_supply(&deps.querier, ma_asset_address.clone())?;
+            asset_incentive_update_index(
+                &mut asset_incentive,
+                total_supply,
+                env.block.time.seconds(),
+            )?;
+
+            asset_incentive.schedule = schedule.clone();
+
+            asset_incentive
+        }
+        None => AssetIncentive {
+            emission_per_second: Uint128::zero(),
+            index: Decimal::zero(),
+            last_updated: env.block.time.seconds(),
+            schedule: schedule.clone(),
+        },
+    };
+
+    ASSET_INCENTIVES.save(deps.storage, &ma_asset_address, &new_asset_incentive)?;
+
+    let mut response = Response::new().add_attributes(vec![
+        attr("action", "set_asset_incentive_schedule"),
+        attr("ma_asset", ma_token_address),
+    ]);
+    if let Some(schedule) = schedule {
+        response = response.add_attributes(vec![
+            attr("start_time", schedule.start_time.to_string()),
+            attr("end_time", schedule.end_time.to_string()),
+            attr("emission_per_second", schedule.emission_per_second),
+        ]);
+    }
+    Ok(response)
+}
+
 pub fn execute_balance_change(
     deps: DepsMut,
     env: Env,
@@ -207,10 +277,24 @@ pub fn execute_claim_rewards(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    ma_token_addresses_unchecked: Option<Vec<String>>,
 ) -> Result<Response, ContractError> {
     let user_address = info.sender;
+    let ma_token_addresses = ma_token_addresses_unchecked
+        .map(|addresses| {
+            addresses
+                .iter()
+                .map(|address| deps.api.addr_validate(address))
+                .collect::<StdResult<Vec<_>>>()
+        })
+        .transpose()?;
     let (total_unclaimed_rewards, user_asset_incentive_statuses_to_update) =
-        compute_user_unclaimed_rewards(deps.as_ref(), &env, &user_address)?;
+        compute_user_unclaimed_rewards(
+            deps.as_ref(),
+            &env,
+            &user_address,
+            ma_token_addresses.as_deref(),
+        )?;
 
     // Commit updated asset_incentives and user indexes
     for user_asset_incentive_status in user_asset_incentive_statuses_to_update {
@@ -319,7 +403,7 @@ pub fn execute_execute_cosmos_msg(
 
 /// Updates asset incentive index and last updated timestamp by computing
 /// how many rewards were accrued since last time updated given incentive's
-/// emission per second.
+/// emission per second (or its schedule if it has one).
 /// Total supply is the total (liquidity) token supply during the period being computed.
 /// Note that this method does not commit updates to state as that should be executed by the
 /// caller
@@ -328,22 +412,70 @@ fn asset_incentive_update_index(
     total_supply: Uint128,
     current_block_time: u64,
 ) -> StdResult<()> {
-    if (current_block_time != asset_incentive.last_updated)
-        && !total_supply.is_zero()
-        && !asset_incentive.emission_per_second.is_zero()
-    {
-        asset_incentive.index = asset_incentive_compute_index(
-            asset_incentive.index,
-            asset_incentive.emission_per_second,
-            total_supply,
-            asset_incentive.last_updated,
-            current_block_time,
-        )?
+    if (current_block_time != asset_incentive.last_updated) && !total_supply.is_zero() {
+        match &asset_incentive.schedule {
+            Some(schedule) => {
+                let emission = schedule_compute_emission(
+                    schedule,
+                    asset_incentive.last_updated,
+                    current_block_time,
+                )?;
+                asset_incentive.index =
+                    asset_incentive.index + Decimal::from_ratio(emission, total_supply);
+            }
+            None if !asset_incentive.emission_per_second.is_zero() => {
+                asset_incentive.index = asset_incentive_compute_index(
+                    asset_incentive.index,
+                    asset_incentive.emission_per_second,
+                    total_supply,
+                    asset_incentive.last_updated,
+                    current_block_time,
+                )?
+            }
+            None => {}
+        }
     }
     asset_incentive.last_updated = current_block_time;
     Ok(())
 }
 
+/// Computes the MARS emitted by the schedule between two times. Nothing is emitted outside of
+/// the schedule bounds and the emission decays at the end of each decay period
+fn schedule_compute_emission(
+    schedule: &IncentiveSchedule,
+    time_start: u64,
+    time_end: u64,
+) -> StdResult<Uint128> {
+    let start = time_start.max(schedule.start_time);
+    let end = time_end.min(schedule.end_time);
+    if start >= end {
+        return Ok(Uint128::zero());
+    }
+
+    let decay = match &schedule.decay {
+        Some(decay) => decay,
+        None => {
+            return Ok(schedule
+                .emission_per_second
+                .checked_mul(Uint128::from(end - start))?)
+        }
+    };
+
+    let mut emission = Uint128::zero();
+    let mut time = start;
+    while time < end {
+        let period = (time - schedule.start_time) / decay.period_seconds;
+        let period_end = schedule.start_time + (period + 1) * decay.period_seconds;
+        let segment_end = period_end.min(end);
+        let emission_per_second =
+            (Decimal::one() - decay.rate).checked_pow(period)? * schedule.emission_per_second;
+        emission = emission
+            .checked_add(emission_per_second.checked_mul(Uint128::from(segment_end - time))?)?;
+        time = segment_end;
+    }
+    Ok(emission)
+}
+
 fn asset_incentive_compute_index(
     previous_index: Decimal,
     emission_per_second: Uint128,
@@ -389,12 +521,18 @@ struct UserAssetIncentiveStatus {
     /// Asset incentive with values updated to the current block (not neccesarily commited
     /// to storage)
     asset_incentive_updated: AssetIncentive,
+    /// Rewards accrued by the user on the asset since its index was last updated
+    accrued_rewards: Uint128,
 }
 
+/// Computes the rewards recorded for the user plus the ones accrued on each maToken since
+/// the user's index was last updated. If `ma_token_addresses` is given, only those maTokens
+/// are taken into account
 fn compute_user_unclaimed_rewards(
     deps: Deps,
     env: &Env,
     user_address: &Addr,
+    ma_token_addresses: Option<&[Addr]>,
 ) -> StdResult<(Uint128, Vec<UserAssetIncentiveStatus>)> {
     let mut total_unclaimed_rewards = USER_UNCLAIMED_REWARDS
         .may_load(deps.storage, user_address)?
@@ -411,6 +549,12 @@ fn compute_user_unclaimed_rewards(
             .api
             .addr_validate(&String::from_utf8(ma_token_address_bytes)?)?;
 
+        if let Some(ma_token_addresses) = ma_token_addresses {
+            if !ma_token_addresses.contains(&ma_token_address) {
+                continue;
+            }
+        }
+
         // Get asset user balances and total supply
         let balance_and_total_supply: mars_core::ma_token::msg::BalanceAndTotalSupplyResponse =
             deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
@@ -437,9 +581,10 @@ fn compute_user_unclaimed_rewards(
             .may_load(deps.storage, (user_address, &ma_token_address))?
             .unwrap_or_else(Decimal::zero);
 
+        let mut asset_accrued_rewards = Uint128::zero();
         if user_asset_index != asset_incentive.index {
             // Compute user accrued rewards and update user index
-            let asset_accrued_rewards = user_compute_accrued_rewards(
+            asset_accrued_rewards = user_compute_accrued_rewards(
                 balance_and_total_supply.balance,
                 user_asset_index,
                 asset_incentive.index,
@@ -451,6 +596,7 @@ fn compute_user_unclaimed_rewards(
             ma_token_address,
             user_index_current: user_asset_index,
             asset_incentive_updated: asset_incentive,
+            accrued_rewards: asset_accrued_rewards,
         });
     }
 
@@ -472,6 +618,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::UserUnclaimedRewards { user_address } => {
             to_binary(&query_user_unclaimed_rewards(deps, env, user_address)?)
         }
+        QueryMsg::UserUnclaimedRewardsBreakdown { user_address } => to_binary(
+            &query_user_unclaimed_rewards_breakdown(deps, env, user_address)?,
+        ),
     }
 }
 
@@ -497,22 +646,53 @@ fn query_user_unclaimed_rewards(
     user_address_unchecked: String,
 ) -> StdResult<Uint128> {
     let user_address = deps.api.addr_validate(&user_address_unchecked)?;
-    let (unclaimed_rewards, _) = compute_user_unclaimed_rewards(deps, &env, &user_address)?;
+    let (unclaimed_rewards, _) = compute_user_unclaimed_rewards(deps, &env, &user_address, None)?;
 
     Ok(unclaimed_rewards)
 }
 
+fn query_user_unclaimed_rewards_breakdown(
+    deps: Deps,
+    env: Env,
+    user_address_unchecked: String,
+) -> StdResult<UserUnclaimedRewardsBreakdownResponse> {
+    let user_address = deps.api.addr_validate(&user_address_unchecked)?;
+    let recorded_rewards = USER_UNCLAIMED_REWARDS
+        .may_load(deps.storage, &user_address)?
+        .unwrap_or_else(Uint128::zero);
+    let (total_rewards, user_asset_incentive_statuses) =
+        compute_user_unclaimed_rewards(deps, &env, &user_address, None)?;
+
+    let assets = user_asset_incentive_statuses
+        .into_iter()
+        .filter(|status| !status.accrued_rewards.is_zero())
+        .map(|status| AssetUnclaimedRewards {
+            ma_token_address: status.ma_token_address,
+            rewards: status.accrued_rewards,
+        })
+        .collect();
+
+    Ok(UserUnclaimedRewardsBreakdownResponse {
+        recorded_rewards,
+        assets,
+        total_rewards,
+    })
+}
+
 // TESTS
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use cosmwasm_std::{
+        from_binary,
         testing::{mock_env, mock_info, MockApi, MockStorage},
         Addr, BankMsg, Coin, OwnedDeps, SubMsg, Timestamp, Uint128,
     };
     use mars_core::testing::{mock_dependencies, MarsMockQuerier, MockEnvParams};
 
+    use crate::{EmissionDecay, MAX_DECAY_PERIODS};
+
     // init
     #[test]
     fn test_proper_initialization() {
@@ -669,6 +849,7 @@ mod tests {
                     emission_per_second: Uint128::new(100),
                     index: Decimal::from_ratio(1_u128, 2_u128),
                     last_updated: 500_000,
+                    schedule: None,
                 },
             )
             .unwrap();
@@ -714,6 +895,176 @@ mod tests {
         assert_eq!(asset_incentive.last_updated, 1_000_000);
     }
 
+    #[test]
+    fn test_set_asset_incentive_schedule() {
+        let mut deps = th_setup(&[]);
+        let ma_asset_address = Addr::unchecked("ma_asset");
+        deps.querier
+            .set_cw20_total_supply(ma_asset_address.clone(), Uint128::new(2_000_000));
+
+        let schedule = IncentiveSchedule {
+            start_time: 1_000,
+            end_time: 2_000,
+            emission_per_second: Uint128::new(100),
+            decay: None,
+        };
+
+        // only owner can set schedules
+        let msg = ExecuteMsg::SetAssetIncentiveSchedule {
+            ma_token_address: ma_asset_address.to_string(),
+            schedule: Some(schedule.clone()),
+        };
+        let res_error =
+            execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg).unwrap_err();
+        assert_eq!(res_error, ContractError::Mars(MarsError::Unauthorized {}));
+
+        // schedule must end after it starts
+        let msg = ExecuteMsg::SetAssetIncentiveSchedule {
+            ma_token_address: ma_asset_address.to_string(),
+            schedule: Some(IncentiveSchedule {
+                end_time: 1_000,
+                ..schedule.clone()
+            }),
+        };
+        let res_error =
+            execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+        assert_eq!(
+            res_error,
+            ContractError::Mars(MarsError::InvalidParam {
+                param_name: "end_time".to_string(),
+                invalid_value: "1000".to_string(),
+                predicate: "> 1000".to_string(),
+            })
+        );
+
+        // decay periods are bounded so computing the emission stays cheap
+        let decay_schedule = |period_seconds: u64| IncentiveSchedule {
+            end_time: 1_000 + 2 * MAX_DECAY_PERIODS + 1,
+            decay: Some(EmissionDecay {
+                rate: Decimal::percent(1),
+                period_seconds,
+            }),
+            ..schedule.clone()
+        };
+        let msg = ExecuteMsg::SetAssetIncentiveSchedule {
+            ma_token_address: ma_asset_address.to_string(),
+            schedule: Some(decay_schedule(2)),
+        };
+        let res_error =
+            execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+        assert_eq!(
+            res_error,
+            ContractError::Mars(MarsError::InvalidParam {
+                param_name: "decay_period_seconds".to_string(),
+                invalid_value: "2".to_string(),
+                predicate: ">= 3".to_string(),
+            })
+        );
+        decay_schedule(3).validate().unwrap();
+
+        let env = mars_core::testing::mock_env(MockEnvParams {
+            block_time: Timestamp::from_seconds(500),
+            ..Default::default()
+        });
+        let msg = ExecuteMsg::SetAssetIncentiveSchedule {
+            ma_token_address: ma_asset_address.to_string(),
+            schedule: Some(schedule.clone()),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("owner", &[]), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "set_asset_incentive_schedule"),
+                attr("ma_asset", "ma_asset"),
+                attr("start_time", "1000"),
+                attr("end_time", "2000"),
+                attr("emission_per_second", "100"),
+            ]
+        );
+
+        let asset_incentive = ASSET_INCENTIVES
+            .load(deps.as_ref().storage, &ma_asset_address)
+            .unwrap();
+        assert_eq!(
+            asset_incentive,
+            AssetIncentive {
+                emission_per_second: Uint128::zero(),
+                index: Decimal::zero(),
+                last_updated: 500,
+                schedule: Some(schedule),
+            }
+        );
+    }
+
+    #[test]
+    fn test_balance_change_across_schedule_end() {
+        let mut deps = th_setup(&[]);
+        let ma_asset_address = Addr::unchecked("ma_asset");
+        let user_address = Addr::unchecked("user");
+
+        ASSET_INCENTIVES
+            .save(
+                deps.as_mut().storage,
+                &ma_asset_address,
+                &AssetIncentive {
+                    emission_per_second: Uint128::new(1_000),
+                    index: Decimal::zero(),
+                    last_updated: 500,
+                    schedule: Some(IncentiveSchedule {
+                        start_time: 1_000,
+                        end_time: 2_000,
+                        emission_per_second: Uint128::new(100),
+                        decay: None,
+                    }),
+                },
+            )
+            .unwrap();
+
+        let balance_change = |deps: DepsMut, block_time: u64| {
+            let env = mars_core::testing::mock_env(MockEnvParams {
+                block_time: Timestamp::from_seconds(block_time),
+                ..Default::default()
+            });
+            let msg = ExecuteMsg::BalanceChange {
+                user_address: user_address.clone(),
+                user_balance_before: Uint128::new(1_000),
+                total_supply_before: Uint128::new(100_000),
+            };
+            execute(deps, env, mock_info("ma_asset", &[]), msg).unwrap()
+        };
+
+        // only the 1_000 seconds within the schedule emit MARS (at the schedule rate)
+        let res = balance_change(deps.as_mut(), 3_000);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "balance_change"),
+                attr("ma_asset", "ma_asset"),
+                attr("user", "user"),
+                attr("rewards_accrued", "1000"),
+                attr("asset_index", "1"),
+            ]
+        );
+
+        // emissions stopped at the end of the schedule
+        let res = balance_change(deps.as_mut(), 4_000);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "balance_change"),
+                attr("ma_asset", "ma_asset"),
+                attr("user", "user"),
+                attr("rewards_accrued", "0"),
+                attr("asset_index", "1"),
+            ]
+        );
+        let asset_incentive = ASSET_INCENTIVES
+            .load(deps.as_ref().storage, &ma_asset_address)
+            .unwrap();
+        assert_eq!(asset_incentive.index, Decimal::one());
+        assert_eq!(asset_incentive.last_updated, 4_000);
+    }
+
     // BalanceChange
 
     #[test]
@@ -747,6 +1098,7 @@ mod tests {
                     emission_per_second: Uint128::zero(),
                     index: asset_incentive_index,
                     last_updated: 500_000,
+                    schedule: None,
                 },
             )
             .unwrap();
@@ -822,6 +1174,7 @@ mod tests {
                     emission_per_second,
                     index: start_index,
                     last_updated: time_last_updated,
+                    schedule: None,
                 },
             )
             .unwrap();
@@ -898,6 +1251,7 @@ mod tests {
                     emission_per_second,
                     index: start_index,
                     last_updated: time_last_updated,
+                    schedule: None,
                 },
             )
             .unwrap();
@@ -972,6 +1326,7 @@ mod tests {
                         emission_per_second,
                         index: asset_incentive_index,
                         last_updated: time_last_updated,
+                        schedule: None,
                     },
                 )
                 .unwrap();
@@ -1063,6 +1418,7 @@ mod tests {
                     emission_per_second,
                     index: expected_asset_incentive_index,
                     last_updated: expected_time_last_updated,
+                    schedule: None,
                 },
             )
             .unwrap();
@@ -1302,6 +1658,7 @@ mod tests {
                     emission_per_second: Uint128::new(100),
                     index: Decimal::one(),
                     last_updated: time_start,
+                    schedule: None,
                 },
             )
             .unwrap();
@@ -1313,6 +1670,7 @@ mod tests {
                     emission_per_second: Uint128::zero(),
                     index: Decimal::one(),
                     last_updated: time_start,
+                    schedule: None,
                 },
             )
             .unwrap();
@@ -1324,6 +1682,7 @@ mod tests {
                     emission_per_second: Uint128::new(200),
                     index: Decimal::one(),
                     last_updated: time_start,
+                    schedule: None,
                 },
             )
             .unwrap();
@@ -1387,7 +1746,9 @@ mod tests {
             block_time: Timestamp::from_seconds(time_contract_call),
             ..Default::default()
         });
-        let msg = ExecuteMsg::ClaimRewards {};
+        let msg = ExecuteMsg::ClaimRewards {
+            ma_token_addresses: None,
+        };
 
         // query a bit before gives less rewards
         let env_before = mars_core::testing::mock_env(MockEnvParams {
@@ -1481,13 +1842,216 @@ mod tests {
         assert_eq!(user_unclaimed_rewards, Uint128::zero())
     }
 
+    #[test]
+    fn test_execute_claim_rewards_for_markets() {
+        let mut deps = th_setup(&[]);
+        let user_address = Addr::unchecked("user");
+
+        // every index grows by 0.1 over 100 seconds
+        for (ma_token, emission_per_second, total_supply, user_balance) in [
+            ("ma_uusd", 100, 100_000, 10_000),
+            ("ma_uluna", 50, 50_000, 20_000),
+            ("ma_ukrw", 200, 200_000, 30_000),
+            ("ma_umnt", 100, 100_000, 40_000),
+        ] {
+            let ma_token_address = Addr::unchecked(ma_token);
+            deps.querier
+                .set_cw20_total_supply(ma_token_address.clone(), Uint128::new(total_supply));
+            deps.querier.set_cw20_balances(
+                ma_token_address.clone(),
+                &[(user_address.clone(), Uint128::new(user_balance))],
+            );
+            ASSET_INCENTIVES
+                .save(
+                    deps.as_mut().storage,
+                    &ma_token_address,
+                    &AssetIncentive {
+                        emission_per_second: Uint128::new(emission_per_second),
+                        index: Decimal::one(),
+                        last_updated: 1_000,
+                        schedule: None,
+                    },
+                )
+                .unwrap();
+            USER_ASSET_INDICES
+                .save(
+                    deps.as_mut().storage,
+                    (&user_address, &ma_token_address),
+                    &Decimal::one(),
+                )
+                .unwrap();
+        }
+        USER_UNCLAIMED_REWARDS
+            .save(deps.as_mut().storage, &user_address, &Uint128::new(50))
+            .unwrap();
+
+        let env = mars_core::testing::mock_env(MockEnvParams {
+            block_time: Timestamp::from_seconds(1_100),
+            ..Default::default()
+        });
+        let msg = ExecuteMsg::ClaimRewards {
+            ma_token_addresses: Some(vec![
+                String::from("ma_uusd"),
+                String::from("ma_uluna"),
+                String::from("ma_ukrw"),
+            ]),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("user", &[]), msg).unwrap();
+
+        // recorded rewards plus the ones accrued on the three markets are sent at once
+        let expected_rewards = Uint128::new(50 + 1_000 + 2_000 + 3_000);
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("mars_token"),
+                msg: to_binary(&cw20::Cw20ExecuteMsg::Send {
+                    contract: String::from("staking"),
+                    amount: expected_rewards,
+                    msg: to_binary(&staking::msg::ReceiveMsg::Stake {
+                        recipient: Some(user_address.to_string()),
+                    })
+                    .unwrap()
+                })
+                .unwrap(),
+                funds: vec![],
+            }))]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "claim_rewards"),
+                attr("user", "user"),
+                attr("mars_staked_as_rewards", expected_rewards),
+            ]
+        );
+
+        // claimed markets get updated, the other one does not
+        let expected_index = Decimal::from_ratio(11_u128, 10_u128);
+        for ma_token in ["ma_uusd", "ma_uluna", "ma_ukrw"] {
+            let ma_token_address = Addr::unchecked(ma_token);
+            let asset_incentive = ASSET_INCENTIVES
+                .load(deps.as_ref().storage, &ma_token_address)
+                .unwrap();
+            assert_eq!(asset_incentive.index, expected_index);
+            let user_index = USER_ASSET_INDICES
+                .load(deps.as_ref().storage, (&user_address, &ma_token_address))
+                .unwrap();
+            assert_eq!(user_index, expected_index);
+        }
+        let ma_umnt_address = Addr::unchecked("ma_umnt");
+        let asset_incentive = ASSET_INCENTIVES
+            .load(deps.as_ref().storage, &ma_umnt_address)
+            .unwrap();
+        assert_eq!(asset_incentive.last_updated, 1_000);
+        let user_index = USER_ASSET_INDICES
+            .load(deps.as_ref().storage, (&user_address, &ma_umnt_address))
+            .unwrap();
+        assert_eq!(user_index, Decimal::one());
+
+        // rewards of the unclaimed market are still pending
+        let rewards =
+            query_user_unclaimed_rewards(deps.as_ref(), env, String::from("user")).unwrap();
+        assert_eq!(rewards, Uint128::new(4_000));
+    }
+
+    #[test]
+    fn test_query_user_unclaimed_rewards_breakdown() {
+        let mut deps = th_setup(&[]);
+        let user_address = Addr::unchecked("user");
+
+        for (ma_token, emission_per_second, total_supply, user_balance) in [
+            ("ma_uusd", 100, 100_000, 10_000),
+            ("ma_uluna", 50, 50_000, 20_000),
+            ("ma_ukrw", 100, 100_000, 0),
+        ] {
+            let ma_token_address = Addr::unchecked(ma_token);
+            deps.querier
+                .set_cw20_total_supply(ma_token_address.clone(), Uint128::new(total_supply));
+            deps.querier.set_cw20_balances(
+                ma_token_address.clone(),
+                &[(user_address.clone(), Uint128::new(user_balance))],
+            );
+            ASSET_INCENTIVES
+                .save(
+                    deps.as_mut().storage,
+                    &ma_token_address,
+                    &AssetIncentive {
+                        emission_per_second: Uint128::new(emission_per_second),
+                        index: Decimal::one(),
+                        last_updated: 1_000,
+                        schedule: None,
+                    },
+                )
+                .unwrap();
+            USER_ASSET_INDICES
+                .save(
+                    deps.as_mut().storage,
+                    (&user_address, &ma_token_address),
+                    &Decimal::one(),
+                )
+                .unwrap();
+        }
+        USER_UNCLAIMED_REWARDS
+            .save(deps.as_mut().storage, &user_address, &Uint128::new(50))
+            .unwrap();
+
+        // both indexes grow by 0.1 over 100 seconds
+        let env = mars_core::testing::mock_env(MockEnvParams {
+            block_time: Timestamp::from_seconds(1_100),
+            ..Default::default()
+        });
+        let res: UserUnclaimedRewardsBreakdownResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::UserUnclaimedRewardsBreakdown {
+                    user_address: user_address.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            UserUnclaimedRewardsBreakdownResponse {
+                recorded_rewards: Uint128::new(50),
+                assets: vec![
+                    AssetUnclaimedRewards {
+                        ma_token_address: Addr::unchecked("ma_uluna"),
+                        rewards: Uint128::new(2_000),
+                    },
+                    AssetUnclaimedRewards {
+                        ma_token_address: Addr::unchecked("ma_uusd"),
+                        rewards: Uint128::new(1_000),
+                    },
+                ],
+                total_rewards: Uint128::new(3_050),
+            }
+        );
+
+        let total_rewards: Uint128 = from_binary(
+            &query(
+                deps.as_ref(),
+                env,
+                QueryMsg::UserUnclaimedRewards {
+                    user_address: user_address.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(total_rewards, res.total_rewards);
+    }
+
     #[test]
     fn test_claim_zero_rewards() {
         // SETUP
         let mut deps = th_setup(&[]);
 
         let info = mock_info("user", &[]);
-        let msg = ExecuteMsg::ClaimRewards {};
+        let msg = ExecuteMsg::ClaimRewards {
+            ma_token_addresses: None,
+        };
 
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
         assert_eq!(res.messages.len(), 0);
@@ -1608,6 +2172,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_schedule_compute_emission() {
+        let schedule = IncentiveSchedule {
+            start_time: 1_000,
+            end_time: 3_000,
+            emission_per_second: Uint128::new(100),
+            decay: None,
+        };
+
+        // nothing is emitted outside of the schedule
+        assert_eq!(
+            schedule_compute_emission(&schedule, 0, 1_000).unwrap(),
+            Uint128::zero()
+        );
+        assert_eq!(
+            schedule_compute_emission(&schedule, 3_000, 5_000).unwrap(),
+            Uint128::zero()
+        );
+        assert_eq!(
+            schedule_compute_emission(&schedule, 500, 1_500).unwrap(),
+            Uint128::new(50_000)
+        );
+        assert_eq!(
+            schedule_compute_emission(&schedule, 2_500, 5_000).unwrap(),
+            Uint128::new(50_000)
+        );
+
+        // emission halves every 500 seconds: 100, 50, 25 and 12 (rounded down) per second
+        let schedule = IncentiveSchedule {
+            decay: Some(EmissionDecay {
+                rate: Decimal::percent(50),
+                period_seconds: 500,
+            }),
+            ..schedule
+        };
+        assert_eq!(
+            schedule_compute_emission(&schedule, 0, 5_000).unwrap(),
+            Uint128::new(93_500)
+        );
+        assert_eq!(
+            schedule_compute_emission(&schedule, 1_250, 1_750).unwrap(),
+            Uint128::new(37_500)
+        );
+    }
+
     #[test]
     fn test_user_compute_accrued_rewards() {
         assert_eq!(
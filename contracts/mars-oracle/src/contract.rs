@@ -1,8 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Attribute, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
-    Uint128,
+    attr, to_binary, Attribute, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128,
 };
 use mars_core::error::MarsError;
 use terra_cosmwasm::TerraQuerier;
@@ -28,8 +28,18 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    let base_denom = msg.base_denom.unwrap_or_else(|| "uusd".to_string());
+    // Protocol contracts value positions in uusd, so prices need to be quoted in it
+    if base_denom != "uusd" {
+        return Err(StdError::generic_err(format!(
+            "Unsupported base denom: {}, only uusd is supported",
+            base_denom
+        )));
+    }
+
     let config = Config {
         owner: deps.api.addr_validate(&msg.owner)?,
+        base_denom,
     };
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::default())
@@ -91,11 +101,11 @@ pub fn execute_set_asset(
     PRICE_SOURCES.save(deps.storage, &asset_reference, &price_source)?;
 
     // for spot and TWAP sources, we must make sure: the astroport pair indicated by `pair_address`
-    // consists of UST and the asset of interest
+    // consists of the base asset and the asset of interest
     match &price_source {
         PriceSourceChecked::AstroportSpot { pair_address }
         | PriceSourceChecked::AstroportTwap { pair_address, .. } => {
-            assert_astroport_pool_assets(&deps.querier, &asset, pair_address)?;
+            assert_astroport_pool_assets(&deps.querier, &asset, pair_address, &config.base_denom)?;
         }
         _ => (),
     }
@@ -114,6 +124,7 @@ pub fn execute_record_twap_snapshots(
     _info: MessageInfo,
     assets: Vec<Asset>,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
     let timestamp = env.block.time.seconds();
     let mut attrs: Vec<Attribute> = vec![];
 
@@ -149,7 +160,8 @@ pub fn execute_record_twap_snapshots(
         }
 
         // Query new price data
-        let price_cumulative = query_astroport_cumulative_price(&deps.querier, &pair_address)?;
+        let price_cumulative =
+            query_astroport_cumulative_price(&deps.querier, &pair_address, &config.base_denom)?;
 
         // Purge snapshots that are too old, i.e. more than (window_size + tolerance) away from the
         // current timestamp. These snapshots will never be used in the future for calculating
@@ -206,6 +218,13 @@ fn query_asset_price(
     env: Env,
     asset_reference: Vec<u8>,
 ) -> Result<Decimal, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // The base asset is what every other price is quoted in
+    if asset_reference == config.base_denom.as_bytes() {
+        return Ok(Decimal::one());
+    }
+
     let price_source = PRICE_SOURCES.load(deps.storage, &asset_reference)?;
 
     match price_source {
@@ -216,9 +235,9 @@ fn query_asset_price(
 
             // NOTE: Exchange rate returns how much of the quote (second argument) is required to
             // buy one unit of the base_denom (first argument).
-            // We want to know how much uusd we need to buy 1 of the target currency
+            // We want to know how much of the base asset we need to buy 1 of the target currency
             let asset_prices_query = terra_querier
-                .query_exchange_rates(denom, vec!["uusd".to_string()])?
+                .query_exchange_rates(denom, vec![config.base_denom])?
                 .exchange_rates
                 .pop();
 
@@ -238,7 +257,7 @@ fn query_asset_price(
         // Why not just take the quotient of the two assets depths? (E.g. if the pool has 120 UST and
         // 100 MARS, then 1 MARS = 1.2 UST) Because this only works for XYK pools, not StableSwap pools.
         PriceSourceChecked::AstroportSpot { pair_address } => {
-            query_astroport_spot_price(&deps.querier, &pair_address)
+            query_astroport_spot_price(&deps.querier, &pair_address, &config.base_denom)
         }
 
         PriceSourceChecked::AstroportTwap {
@@ -251,7 +270,11 @@ fn query_asset_price(
             // First, query the current TWAP snapshot
             let current_snapshot = AstroportTwapSnapshot {
                 timestamp: env.block.time.seconds(),
-                price_cumulative: query_astroport_cumulative_price(&deps.querier, &pair_address)?,
+                price_cumulative: query_astroport_cumulative_price(
+                    &deps.querier,
+                    &pair_address,
+                    &config.base_denom,
+                )?,
             };
 
             // Find the oldest snapshot whose period from current snapshot is within the tolerable window
@@ -346,24 +369,25 @@ mod helpers {
         diff(diff(snapshot1.timestamp, snapshot2.timestamp), window_size)
     }
 
-    pub fn ust() -> AstroportAssetInfo {
+    pub fn base_asset_info(base_denom: &str) -> AstroportAssetInfo {
         AstroportAssetInfo::NativeToken {
-            denom: "uusd".to_string(),
+            denom: base_denom.to_string(),
         }
     }
 
-    /// Assert the astroport pair indicated by `pair_address` consists of UST and `asset`
+    /// Assert the astroport pair indicated by `pair_address` consists of the base asset and `asset`
     pub fn assert_astroport_pool_assets(
         querier: &QuerierWrapper,
         asset: &Asset,
         pair_address: &Addr,
+        base_denom: &str,
     ) -> Result<(), ContractError> {
         let pool = query_astroport_pool(querier, pair_address)?;
         let asset0: Asset = (&pool.assets[0].info).into();
         let asset1: Asset = (&pool.assets[1].info).into();
-        let ust: Asset = (&ust()).into();
+        let base: Asset = (&base_asset_info(base_denom)).into();
 
-        if (asset0 == ust && &asset1 == asset) || (asset1 == ust && &asset0 == asset) {
+        if (asset0 == base && &asset1 == asset) || (asset1 == base && &asset0 == asset) {
             Ok(())
         } else {
             Err(ContractError::InvalidPair {})
@@ -383,6 +407,7 @@ mod helpers {
     pub fn query_astroport_spot_price(
         querier: &QuerierWrapper,
         pair_address: &Addr,
+        base_denom: &str,
     ) -> Result<Decimal, ContractError> {
         let response: PoolResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: pair_address.to_string(),
@@ -390,9 +415,10 @@ mod helpers {
         }))?;
 
         // During the configuration of the price source, we have asserted that the pool indeed consists
-        // of UST and the asset of interest
-        // Here,  we use the one asset in the pool that is *not* UST as `offer_asset` to simulate the swap
-        let offer_asset_info = if response.assets[0].info == ust() {
+        // of the base asset and the asset of interest
+        // Here,  we use the one asset in the pool that is *not* the base asset as `offer_asset` to
+        // simulate the swap
+        let offer_asset_info = if response.assets[0].info == base_asset_info(base_denom) {
             response.assets[1].info.clone()
         } else {
             response.assets[0].info.clone()
@@ -417,6 +443,7 @@ mod helpers {
     pub fn query_astroport_cumulative_price(
         querier: &QuerierWrapper,
         pair_address: &Addr,
+        base_denom: &str,
     ) -> StdResult<Uint128> {
         let response: CumulativePricesResponse =
             querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
@@ -425,9 +452,9 @@ mod helpers {
             }))?;
 
         // during the configuration of the price source, we have asserted that the pool indeed consists
-        // of UST and the asset of interest.
-        // Here, we return cumulative price of the one asset in the pool that is *not* UST
-        let price_cumulative = if response.assets[0].info == ust() {
+        // of the base asset and the asset of interest.
+        // Here, we return cumulative price of the one asset in the pool that is *not* the base asset
+        let price_cumulative = if response.assets[0].info == base_asset_info(base_denom) {
             response.price1_cumulative_last
         } else {
             response.price0_cumulative_last
@@ -454,6 +481,7 @@ mod tests {
 
         let msg = InstantiateMsg {
             owner: String::from("owner"),
+            base_denom: None,
         };
         let info = mock_info("owner", &[]);
 
@@ -462,6 +490,20 @@ mod tests {
 
         let config = CONFIG.load(&deps.storage).unwrap();
         assert_eq!(Addr::unchecked("owner"), config.owner);
+        assert_eq!(config.base_denom, "uusd");
+
+        // other base denoms are rejected
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            owner: String::from("owner"),
+            base_denom: Some(String::from("ukrw")),
+        };
+        let error_res =
+            instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+        assert_eq!(
+            error_res,
+            StdError::generic_err("Unsupported base denom: ukrw, only uusd is supported")
+        );
     }
 
     #[test]
@@ -924,6 +966,62 @@ mod tests {
         assert_eq!(price, Decimal::from_ratio(4_u128, 1_u128));
     }
 
+    #[test]
+    fn test_query_asset_price_base_asset() {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            owner: String::from("owner"),
+            base_denom: Some(String::from("uusd")),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+        deps.querier.set_native_exchange_rates(
+            "ukrw".to_string(),
+            &[("uusd".to_string(), Decimal::from_ratio(1_u128, 1_250_u128))],
+        );
+        let ukrw = Asset::Native {
+            denom: String::from("ukrw"),
+        };
+        PRICE_SOURCES
+            .save(
+                &mut deps.storage,
+                ukrw.get_reference().as_slice(),
+                &PriceSourceChecked::Native {
+                    denom: "ukrw".to_string(),
+                },
+            )
+            .unwrap();
+
+        // base asset needs no price source and is valued at its amount
+        let uusd = Asset::Native {
+            denom: String::from("uusd"),
+        };
+        let uusd_price: Decimal = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AssetPrice { asset: uusd },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(uusd_price, Decimal::one());
+        assert_eq!(uusd_price * Uint128::new(2_500), Uint128::new(2_500));
+
+        // other assets are quoted in the base asset
+        let ukrw_price: Decimal = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AssetPrice { asset: ukrw },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(ukrw_price, Decimal::from_ratio(1_u128, 1_250_u128));
+        assert_eq!(ukrw_price * Uint128::new(3_125_000), Uint128::new(2_500));
+    }
+
     #[test]
     fn test_query_asset_price_astroport_spot() {
         let mut deps = th_setup();
@@ -1109,6 +1207,7 @@ mod tests {
 
         let msg = InstantiateMsg {
             owner: String::from("owner"),
+            base_denom: None,
         };
         let info = mock_info("owner", &[]);
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
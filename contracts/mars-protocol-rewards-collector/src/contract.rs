@@ -1,8 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
-    WasmMsg,
+    to_binary, Api, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128, WasmMsg,
 };
 
 use astroport::asset::AssetInfo;
@@ -10,15 +10,15 @@ use astroport::asset::AssetInfo;
 use mars_core::asset::{build_send_asset_with_tax_deduction_msg, get_asset_balance, Asset};
 use mars_core::error::MarsError;
 use mars_core::helpers::{option_string_to_addr, zero_address};
-use mars_core::swapping::execute_swap;
+use mars_core::swapping::{execute_swap_with_fill_mode, SwapFillMode};
 
 use mars_core::address_provider::{self, MarsContract};
 use mars_core::red_bank;
 
 use crate::error::ContractError;
-use crate::msg::{CreateOrUpdateConfig, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{ASSET_CONFIG, CONFIG};
-use crate::{AssetConfig, Config};
+use crate::msg::{CreateOrUpdateConfig, ExecuteMsg, FeeRecipientParams, InstantiateMsg, QueryMsg};
+use crate::state::{ASSET_CONFIG, CONFIG, LAST_DISTRIBUTION_TIMES, SWAP_LEFTOVERS};
+use crate::{AssetConfig, Config, FeeRecipient, SwapLeftoverResponse};
 
 // INIT
 
@@ -34,8 +34,7 @@ pub fn instantiate(
     let CreateOrUpdateConfig {
         owner,
         address_provider_address,
-        safety_fund_fee_share,
-        treasury_fee_share,
+        fee_recipients,
         astroport_factory_address,
         astroport_max_spread,
     } = msg.config;
@@ -43,8 +42,7 @@ pub fn instantiate(
     // All fields should be available
     let available = owner.is_some()
         && address_provider_address.is_some()
-        && safety_fund_fee_share.is_some()
-        && treasury_fee_share.is_some()
+        && fee_recipients.is_some()
         && astroport_factory_address.is_some()
         && astroport_max_spread.is_some();
 
@@ -59,8 +57,7 @@ pub fn instantiate(
             address_provider_address,
             zero_address(),
         )?,
-        safety_fund_fee_share: safety_fund_fee_share.unwrap(),
-        treasury_fee_share: treasury_fee_share.unwrap(),
+        fee_recipients: fee_recipients_from_params(deps.api, fee_recipients.unwrap())?,
         astroport_factory_address: option_string_to_addr(
             deps.api,
             astroport_factory_address,
@@ -87,9 +84,20 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::UpdateConfig { config } => execute_update_config(deps, env, info, config),
-        ExecuteMsg::UpdateAssetConfig { asset, enabled } => {
-            execute_update_asset_config(deps, env, info, asset, enabled)
-        }
+        ExecuteMsg::UpdateAssetConfig {
+            asset,
+            enabled,
+            min_distribution_amount,
+            min_distribution_interval_seconds,
+        } => execute_update_asset_config(
+            deps,
+            env,
+            info,
+            asset,
+            enabled,
+            min_distribution_amount,
+            min_distribution_interval_seconds,
+        ),
         ExecuteMsg::WithdrawFromRedBank { asset, amount } => {
             execute_withdraw_from_red_bank(deps, env, info, asset, amount)
         }
@@ -99,11 +107,13 @@ pub fn execute(
         ExecuteMsg::SwapAssetToUusd {
             offer_asset_info,
             amount,
+            fill_mode,
         } => Ok(execute_swap_asset_to_uusd(
             deps,
             env,
             offer_asset_info,
             amount,
+            fill_mode,
         )?),
         ExecuteMsg::ExecuteCosmosMsg(cosmos_msg) => {
             Ok(execute_execute_cosmos_msg(deps, env, info, cosmos_msg)?)
@@ -129,8 +139,7 @@ pub fn execute_update_config(
     let CreateOrUpdateConfig {
         owner,
         address_provider_address,
-        safety_fund_fee_share,
-        treasury_fee_share,
+        fee_recipients,
         astroport_factory_address,
         astroport_max_spread,
     } = new_config;
@@ -141,8 +150,9 @@ pub fn execute_update_config(
         address_provider_address,
         config.address_provider_address,
     )?;
-    config.safety_fund_fee_share = safety_fund_fee_share.unwrap_or(config.safety_fund_fee_share);
-    config.treasury_fee_share = treasury_fee_share.unwrap_or(config.treasury_fee_share);
+    if let Some(fee_recipients) = fee_recipients {
+        config.fee_recipients = fee_recipients_from_params(deps.api, fee_recipients)?;
+    }
     config.astroport_factory_address = option_string_to_addr(
         deps.api,
         astroport_factory_address,
@@ -165,6 +175,8 @@ pub fn execute_update_asset_config(
     info: MessageInfo,
     asset: Asset,
     enabled: bool,
+    min_distribution_amount: Option<Uint128>,
+    min_distribution_interval_seconds: Option<u64>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -174,8 +186,15 @@ pub fn execute_update_asset_config(
 
     let (asset_label, asset_reference, _) = asset.get_attributes();
 
+    let asset_config = ASSET_CONFIG
+        .may_load(deps.storage, asset_reference.as_slice())?
+        .unwrap_or_default();
     let new_asset_config = AssetConfig {
         enabled_for_distribution: enabled,
+        min_distribution_amount: min_distribution_amount
+            .unwrap_or(asset_config.min_distribution_amount),
+        min_distribution_interval_seconds: min_distribution_interval_seconds
+            .unwrap_or(asset_config.min_distribution_interval_seconds),
     };
 
     ASSET_CONFIG.save(deps.storage, asset_reference.as_slice(), &new_asset_config)?;
@@ -253,50 +272,62 @@ pub fn execute_distribute_protocol_rewards(
         None => balance,
     };
 
-    let mars_contracts = vec![
-        MarsContract::SafetyFund,
-        MarsContract::Staking,
-        MarsContract::Treasury,
-    ];
-    let mut addresses_query = address_provider::helpers::query_addresses(
+    // Skip dust and too frequent distributions without failing so keepers can call it blindly
+    let block_time = env.block.time.seconds();
+    let last_distribution_time =
+        LAST_DISTRIBUTION_TIMES.may_load(deps.storage, &asset_reference)?;
+    let skipped_reason = if amount_to_distribute < asset_config.min_distribution_amount {
+        Some("below_min_distribution_amount")
+    } else {
+        match last_distribution_time {
+            Some(last_time)
+                if block_time < last_time + asset_config.min_distribution_interval_seconds =>
+            {
+                Some("before_min_distribution_interval")
+            }
+            _ => None,
+        }
+    };
+    if let Some(reason) = skipped_reason {
+        return Ok(Response::new()
+            .add_attribute("action", "distribute_protocol_income")
+            .add_attribute("asset", asset_label)
+            .add_attribute("skipped", reason));
+    }
+    LAST_DISTRIBUTION_TIMES.save(deps.storage, &asset_reference, &block_time)?;
+
+    let staking_address = address_provider::helpers::query_address(
         &deps.querier,
         config.address_provider_address,
-        mars_contracts,
+        MarsContract::Staking,
     )?;
 
-    let treasury_address = addresses_query.pop().unwrap();
-    let staking_address = addresses_query.pop().unwrap();
-    let safety_fund_address = addresses_query.pop().unwrap();
-
-    let safety_fund_amount = amount_to_distribute * config.safety_fund_fee_share;
-    let treasury_amount = amount_to_distribute * config.treasury_fee_share;
-    let amount_to_distribute_before_staking_rewards =
-        safety_fund_amount.checked_add(treasury_amount)?;
-    let staking_amount =
-        amount_to_distribute.checked_sub(amount_to_distribute_before_staking_rewards)?;
-
     // only build and add send message if fee is non-zero
     let mut messages = vec![];
-    if !safety_fund_amount.is_zero() {
-        let safety_fund_msg = build_send_asset_with_tax_deduction_msg(
-            deps.as_ref(),
-            safety_fund_address,
-            asset_label.clone(),
-            asset_type,
-            safety_fund_amount,
-        )?;
-        messages.push(safety_fund_msg);
-    }
-    if !treasury_amount.is_zero() {
-        let treasury_msg = build_send_asset_with_tax_deduction_msg(
-            deps.as_ref(),
-            treasury_address,
-            asset_label.clone(),
-            asset_type,
-            treasury_amount,
-        )?;
-        messages.push(treasury_msg);
+    let mut fee_recipients_attributes = vec![];
+    let mut amount_to_distribute_before_staking_rewards = Uint128::zero();
+    for fee_recipient in config.fee_recipients {
+        let fee_recipient_amount = amount_to_distribute * fee_recipient.fee_share;
+        amount_to_distribute_before_staking_rewards =
+            amount_to_distribute_before_staking_rewards.checked_add(fee_recipient_amount)?;
+
+        if !fee_recipient_amount.is_zero() {
+            let fee_recipient_msg = build_send_asset_with_tax_deduction_msg(
+                deps.as_ref(),
+                fee_recipient.address.clone(),
+                asset_label.clone(),
+                asset_type,
+                fee_recipient_amount,
+            )?;
+            messages.push(fee_recipient_msg);
+        }
+
+        fee_recipients_attributes.push(("fee_recipient", fee_recipient.address.to_string()));
+        fee_recipients_attributes.push(("fee_recipient_amount", fee_recipient_amount.to_string()));
     }
+
+    let staking_amount =
+        amount_to_distribute.checked_sub(amount_to_distribute_before_staking_rewards)?;
     if !staking_amount.is_zero() {
         let staking_msg = build_send_asset_with_tax_deduction_msg(
             deps.as_ref(),
@@ -311,24 +342,22 @@ pub fn execute_distribute_protocol_rewards(
     let res = Response::new()
         .add_attribute("action", "distribute_protocol_income")
         .add_attribute("asset", asset_label)
-        .add_attribute(
-            "total_distributed_amount",
-            safety_fund_amount + treasury_amount + staking_amount,
-        )
-        .add_attribute("safety_fund_amount", safety_fund_amount)
-        .add_attribute("treasury_amount", treasury_amount)
+        .add_attribute("total_distributed_amount", amount_to_distribute)
+        .add_attributes(fee_recipients_attributes)
         .add_attribute("staking_amount", staking_amount)
         .add_messages(messages);
 
     Ok(res)
 }
 
-/// Swap any asset on the contract to uusd
+/// Swap any asset on the contract to uusd. The amount left unswapped after a partial fill
+/// is recorded for the offer asset
 pub fn execute_swap_asset_to_uusd(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     offer_asset_info: AssetInfo,
     amount: Option<Uint128>,
+    fill_mode: Option<SwapFillMode>,
 ) -> StdResult<Response> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -337,16 +366,26 @@ pub fn execute_swap_asset_to_uusd(
     };
 
     let astroport_max_spread = Some(config.astroport_max_spread);
+    let leftover_key = offer_asset_info.to_string();
 
-    execute_swap(
-        deps,
+    let (response, leftover_amount) = execute_swap_with_fill_mode(
+        deps.branch(),
         env,
         offer_asset_info,
         ask_asset_info,
         amount,
         config.astroport_factory_address,
         astroport_max_spread,
-    )
+        fill_mode.unwrap_or(SwapFillMode::Full),
+    )?;
+
+    if leftover_amount.is_zero() {
+        SWAP_LEFTOVERS.remove(deps.storage, leftover_key.as_bytes());
+    } else {
+        SWAP_LEFTOVERS.save(deps.storage, leftover_key.as_bytes(), &leftover_amount)?;
+    }
+
+    Ok(response)
 }
 
 /// Execute Cosmos message
@@ -369,6 +408,23 @@ pub fn execute_execute_cosmos_msg(
     Ok(response)
 }
 
+// HELPERS
+
+fn fee_recipients_from_params(
+    api: &dyn Api,
+    fee_recipients: Vec<FeeRecipientParams>,
+) -> StdResult<Vec<FeeRecipient>> {
+    fee_recipients
+        .into_iter()
+        .map(|params| {
+            Ok(FeeRecipient {
+                address: api.addr_validate(&params.address)?,
+                fee_share: params.fee_share,
+            })
+        })
+        .collect()
+}
+
 // QUERIES
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -376,6 +432,9 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::AssetConfig { asset } => to_binary(&query_asset_config(deps, asset)?),
+        QueryMsg::SwapLeftover { offer_asset_info } => {
+            to_binary(&query_swap_leftover(deps, offer_asset_info)?)
+        }
     }
 }
 
@@ -395,6 +454,14 @@ fn query_asset_config(deps: Deps, asset: Asset) -> StdResult<AssetConfig> {
     Ok(asset_config)
 }
 
+fn query_swap_leftover(deps: Deps, offer_asset_info: AssetInfo) -> StdResult<SwapLeftoverResponse> {
+    let amount = SWAP_LEFTOVERS
+        .may_load(deps.storage, offer_asset_info.to_string().as_bytes())?
+        .unwrap_or_default();
+
+    Ok(SwapLeftoverResponse { amount })
+}
+
 // TESTS
 
 #[cfg(test)]
@@ -407,6 +474,9 @@ mod tests {
         Addr, BankMsg, Coin, Decimal as StdDecimal, OwnedDeps, StdError, SubMsg,
     };
 
+    use astroport::asset::{Asset as AstroportAsset, PairInfo};
+    use astroport::factory::PairType;
+    use astroport::pair::PoolResponse;
     use cw20::Cw20ExecuteMsg;
 
     use mars_core::math::decimal::Decimal;
@@ -426,8 +496,10 @@ mod tests {
         let base_config = CreateOrUpdateConfig {
             owner: Some("owner".to_string()),
             address_provider_address: Some("address_provider".to_string()),
-            safety_fund_fee_share: Some(Decimal::from_ratio(2u128, 10u128)),
-            treasury_fee_share: Some(Decimal::from_ratio(1u128, 10u128)),
+            fee_recipients: Some(th_fee_recipients(&[
+                ("safety_fund", Decimal::from_ratio(2u128, 10u128)),
+                ("treasury", Decimal::from_ratio(1u128, 10u128)),
+            ])),
             astroport_factory_address: Some("astroport".to_string()),
             astroport_max_spread: Some(astroport_max_spread),
         };
@@ -440,8 +512,7 @@ mod tests {
         let empty_config = CreateOrUpdateConfig {
             owner: None,
             address_provider_address: None,
-            safety_fund_fee_share: None,
-            treasury_fee_share: None,
+            fee_recipients: None,
             astroport_factory_address: None,
             astroport_max_spread: None,
         };
@@ -452,11 +523,11 @@ mod tests {
         assert_eq!(err, MarsError::InstantiateParamsUnavailable {}.into());
 
         // *
-        // init config with safety_fund_fee_share greater than 1
+        // init config with a fee share greater than 1
         // *
-        let mut safety_fund_fee_share = Decimal::from_ratio(11u128, 10u128);
+        let fee_share = Decimal::from_ratio(11u128, 10u128);
         let config = CreateOrUpdateConfig {
-            safety_fund_fee_share: Some(safety_fund_fee_share),
+            fee_recipients: Some(th_fee_recipients(&[("safety_fund", fee_share)])),
             ..base_config.clone()
         };
         let msg = InstantiateMsg { config };
@@ -464,41 +535,35 @@ mod tests {
         assert_eq!(
             response,
             ConfigError::Mars(MarsError::InvalidParam {
-                param_name: "safety_fund_fee_share".to_string(),
-                invalid_value: safety_fund_fee_share.to_string(),
+                param_name: "fee_share".to_string(),
+                invalid_value: fee_share.to_string(),
                 predicate: "<= 1".to_string(),
             })
             .into()
         );
 
         // *
-        // init config with treasury_fee_share greater than 1
+        // init config with invalid fee share amounts
         // *
-        let mut treasury_fee_share = Decimal::from_ratio(12u128, 10u128);
         let config = CreateOrUpdateConfig {
-            treasury_fee_share: Some(treasury_fee_share),
+            fee_recipients: Some(th_fee_recipients(&[
+                ("safety_fund", Decimal::from_ratio(7u128, 10u128)),
+                ("treasury", Decimal::from_ratio(4u128, 10u128)),
+            ])),
             ..base_config.clone()
         };
-        let msg = InstantiateMsg { config };
-        let response = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
-        assert_eq!(
-            response,
-            ConfigError::Mars(MarsError::InvalidParam {
-                param_name: "treasury_fee_share".to_string(),
-                invalid_value: treasury_fee_share.to_string(),
-                predicate: "<= 1".to_string(),
-            })
-            .into()
-        );
+        let exceeding_fees_msg = InstantiateMsg { config };
+        let response =
+            instantiate(deps.as_mut(), mock_env(), info.clone(), exceeding_fees_msg).unwrap_err();
+        assert_eq!(response, ConfigError::InvalidFeeShareAmounts {}.into());
 
-        // *
-        // init config with invalid fee share amounts
-        // *
-        safety_fund_fee_share = Decimal::from_ratio(7u128, 10u128);
-        treasury_fee_share = Decimal::from_ratio(4u128, 10u128);
+        // shares that are valid on their own but sum over one across recipients
         let config = CreateOrUpdateConfig {
-            safety_fund_fee_share: Some(safety_fund_fee_share),
-            treasury_fee_share: Some(treasury_fee_share),
+            fee_recipients: Some(th_fee_recipients(&[
+                ("safety_fund", Decimal::percent(50)),
+                ("treasury", Decimal::percent(30)),
+                ("insurance_fund", Decimal::percent(21)),
+            ])),
             ..base_config.clone()
         };
         let exceeding_fees_msg = InstantiateMsg { config };
@@ -506,14 +571,19 @@ mod tests {
             instantiate(deps.as_mut(), mock_env(), info.clone(), exceeding_fees_msg).unwrap_err();
         assert_eq!(response, ConfigError::InvalidFeeShareAmounts {}.into());
 
+        // invalid fee shares are rejected before anything is saved
+        assert!(CONFIG.may_load(&deps.storage).unwrap().is_none());
+
         // *
         // init config with valid params
         // *
-        safety_fund_fee_share = Decimal::from_ratio(5u128, 10u128);
-        treasury_fee_share = Decimal::from_ratio(3u128, 10u128);
+        let safety_fund_fee_share = Decimal::from_ratio(5u128, 10u128);
+        let treasury_fee_share = Decimal::from_ratio(3u128, 10u128);
         let config = CreateOrUpdateConfig {
-            safety_fund_fee_share: Some(safety_fund_fee_share),
-            treasury_fee_share: Some(treasury_fee_share),
+            fee_recipients: Some(th_fee_recipients(&[
+                ("safety_fund", safety_fund_fee_share),
+                ("treasury", treasury_fee_share),
+            ])),
             ..base_config
         };
         let msg = InstantiateMsg { config };
@@ -527,8 +597,19 @@ mod tests {
         let value: Config = from_binary(&res).unwrap();
         assert_eq!(value.owner, "owner");
         assert_eq!(value.address_provider_address, "address_provider");
-        assert_eq!(value.safety_fund_fee_share, safety_fund_fee_share);
-        assert_eq!(value.treasury_fee_share, treasury_fee_share);
+        assert_eq!(
+            value.fee_recipients,
+            vec![
+                FeeRecipient {
+                    address: Addr::unchecked("safety_fund"),
+                    fee_share: safety_fund_fee_share,
+                },
+                FeeRecipient {
+                    address: Addr::unchecked("treasury"),
+                    fee_share: treasury_fee_share,
+                },
+            ]
+        );
         assert_eq!(value.astroport_factory_address, "astroport");
         assert_eq!(value.astroport_max_spread, astroport_max_spread);
     }
@@ -537,14 +618,14 @@ mod tests {
     fn test_update_config() {
         let mut deps = th_setup(&[]);
 
-        let mut safety_fund_fee_share = Decimal::percent(10);
-        let mut treasury_fee_share = Decimal::percent(20);
         let mut astroport_max_spread = StdDecimal::percent(1);
         let base_config = CreateOrUpdateConfig {
             owner: Some("owner".to_string()),
             address_provider_address: Some("address_provider".to_string()),
-            safety_fund_fee_share: Some(safety_fund_fee_share),
-            treasury_fee_share: Some(treasury_fee_share),
+            fee_recipients: Some(th_fee_recipients(&[
+                ("safety_fund", Decimal::percent(10)),
+                ("treasury", Decimal::percent(20)),
+            ])),
             astroport_factory_address: Some("astroport".to_string()),
             astroport_max_spread: Some(astroport_max_spread),
         };
@@ -560,45 +641,26 @@ mod tests {
         assert_eq!(error_res, MarsError::Unauthorized {}.into());
 
         // *
-        // update config with safety_fund_fee_share greater than 1
+        // update config with a fee share greater than 1
         // *
         let info = mock_info("owner");
 
-        safety_fund_fee_share = Decimal::from_ratio(11u128, 10u128);
-        let config = CreateOrUpdateConfig {
-            owner: None,
-            safety_fund_fee_share: Some(safety_fund_fee_share),
-            ..base_config.clone()
-        };
-        let msg = ExecuteMsg::UpdateConfig { config };
-        let error_res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
-        assert_eq!(
-            error_res,
-            ConfigError::Mars(MarsError::InvalidParam {
-                param_name: "safety_fund_fee_share".to_string(),
-                invalid_value: safety_fund_fee_share.to_string(),
-                predicate: "<= 1".to_string(),
-            })
-            .into()
-        );
-
-        // *
-        // update config with treasury_fee_share greater than 1
-        // *
-        treasury_fee_share = Decimal::from_ratio(12u128, 10u128);
+        let fee_share = Decimal::from_ratio(12u128, 10u128);
         let config = CreateOrUpdateConfig {
             owner: None,
-            treasury_fee_share: Some(treasury_fee_share),
+            fee_recipients: Some(th_fee_recipients(&[
+                ("safety_fund", Decimal::percent(10)),
+                ("treasury", fee_share),
+            ])),
             ..base_config.clone()
         };
         let msg = ExecuteMsg::UpdateConfig { config };
-        let info = mock_info("owner");
         let error_res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
         assert_eq!(
             error_res,
             ConfigError::Mars(MarsError::InvalidParam {
-                param_name: "treasury_fee_share".to_string(),
-                invalid_value: treasury_fee_share.to_string(),
+                param_name: "fee_share".to_string(),
+                invalid_value: fee_share.to_string(),
                 predicate: "<= 1".to_string(),
             })
             .into()
@@ -607,11 +669,13 @@ mod tests {
         // *
         // update config with invalid fee share amounts
         // *
-        safety_fund_fee_share = Decimal::from_ratio(10u128, 10u128);
         let config = CreateOrUpdateConfig {
             owner: None,
-            safety_fund_fee_share: Some(safety_fund_fee_share),
-            treasury_fee_share: None,
+            fee_recipients: Some(th_fee_recipients(&[
+                ("safety_fund", Decimal::percent(40)),
+                ("treasury", Decimal::percent(30)),
+                ("insurance_fund", Decimal::percent(31)),
+            ])),
             ..base_config
         };
         let exceeding_fees_msg = ExecuteMsg::UpdateConfig { config };
@@ -622,14 +686,14 @@ mod tests {
         // *
         // update config with all new params
         // *
-        safety_fund_fee_share = Decimal::from_ratio(5u128, 100u128);
-        treasury_fee_share = Decimal::from_ratio(3u128, 100u128);
         astroport_max_spread = StdDecimal::percent(2);
         let config = CreateOrUpdateConfig {
             owner: Some("new_owner".to_string()),
             address_provider_address: Some("new_address_provider".to_string()),
-            safety_fund_fee_share: Some(safety_fund_fee_share),
-            treasury_fee_share: Some(treasury_fee_share),
+            fee_recipients: Some(th_fee_recipients(&[(
+                "new_treasury",
+                Decimal::from_ratio(3u128, 100u128),
+            )])),
             astroport_factory_address: Some("new_astroport".to_string()),
             astroport_max_spread: Some(astroport_max_spread),
         };
@@ -649,12 +713,11 @@ mod tests {
             config.address_provider_address.unwrap()
         );
         assert_eq!(
-            new_config.safety_fund_fee_share,
-            config.safety_fund_fee_share.unwrap()
-        );
-        assert_eq!(
-            new_config.treasury_fee_share,
-            config.treasury_fee_share.unwrap()
+            new_config.fee_recipients,
+            vec![FeeRecipient {
+                address: Addr::unchecked("new_treasury"),
+                fee_share: Decimal::from_ratio(3u128, 100u128),
+            }]
         );
         assert_eq!(
             new_config.astroport_factory_address,
@@ -664,8 +727,24 @@ mod tests {
             new_config.astroport_max_spread,
             config.astroport_max_spread.unwrap()
         );
-    }
 
+        // *
+        // fee recipients are kept if not specified
+        // *
+        let config = CreateOrUpdateConfig {
+            owner: None,
+            address_provider_address: None,
+            fee_recipients: None,
+            astroport_factory_address: None,
+            astroport_max_spread: None,
+        };
+        let msg = ExecuteMsg::UpdateConfig { config };
+        let info = mock_info("new_owner");
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let new_config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(new_config.fee_recipients.len(), 1);
+    }
     #[test]
     fn test_update_asset_config() {
         let mut deps = th_setup(&[]);
@@ -677,6 +756,8 @@ mod tests {
         let msg = ExecuteMsg::UpdateAssetConfig {
             asset: asset.clone(),
             enabled: true,
+            min_distribution_amount: None,
+            min_distribution_interval_seconds: None,
         };
 
         // *
@@ -720,6 +801,8 @@ mod tests {
         let msg = ExecuteMsg::UpdateAssetConfig {
             asset: asset.clone(),
             enabled: false,
+            min_distribution_amount: None,
+            min_distribution_interval_seconds: None,
         };
         // we can just call .unwrap() to assert this was a success
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -833,6 +916,8 @@ mod tests {
         let msg = ExecuteMsg::UpdateAssetConfig {
             asset: asset.clone(),
             enabled: true,
+            min_distribution_amount: None,
+            min_distribution_interval_seconds: None,
         };
         let info = mock_info("owner");
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -862,8 +947,8 @@ mod tests {
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
         let config = CONFIG.load(&deps.storage).unwrap();
-        let expected_safety_fund_amount = permissible_amount * config.safety_fund_fee_share;
-        let expected_treasury_amount = permissible_amount * config.treasury_fee_share;
+        let expected_safety_fund_amount = permissible_amount * config.fee_recipients[0].fee_share;
+        let expected_treasury_amount = permissible_amount * config.fee_recipients[1].fee_share;
         let expected_staking_amount =
             permissible_amount - (expected_safety_fund_amount + expected_treasury_amount);
 
@@ -911,8 +996,10 @@ mod tests {
                 attr("action", "distribute_protocol_income"),
                 attr("asset", "somecoin"),
                 attr("total_distributed_amount", permissible_amount),
-                attr("safety_fund_amount", expected_safety_fund_amount),
-                attr("treasury_amount", expected_treasury_amount),
+                attr("fee_recipient", "safety_fund"),
+                attr("fee_recipient_amount", expected_safety_fund_amount),
+                attr("fee_recipient", "treasury"),
+                attr("fee_recipient_amount", expected_treasury_amount),
                 attr("staking_amount", expected_staking_amount),
             ]
         );
@@ -927,9 +1014,9 @@ mod tests {
         // verify messages are correct
         let expected_rewards_to_be_distributed = Uint128::new(balance);
         let expected_safety_fund_amount =
-            expected_rewards_to_be_distributed * config.safety_fund_fee_share;
+            expected_rewards_to_be_distributed * config.fee_recipients[0].fee_share;
         let expected_treasury_amount =
-            expected_rewards_to_be_distributed * config.treasury_fee_share;
+            expected_rewards_to_be_distributed * config.fee_recipients[1].fee_share;
         let expected_staking_amount = expected_rewards_to_be_distributed
             - (expected_safety_fund_amount + expected_treasury_amount);
 
@@ -980,8 +1067,10 @@ mod tests {
                     "total_distributed_amount",
                     expected_rewards_to_be_distributed
                 ),
-                attr("safety_fund_amount", expected_safety_fund_amount),
-                attr("treasury_amount", expected_treasury_amount),
+                attr("fee_recipient", "safety_fund"),
+                attr("fee_recipient_amount", expected_safety_fund_amount),
+                attr("fee_recipient", "treasury"),
+                attr("fee_recipient_amount", expected_treasury_amount),
                 attr("staking_amount", expected_staking_amount),
             ]
         );
@@ -1021,6 +1110,8 @@ mod tests {
         let msg = ExecuteMsg::UpdateAssetConfig {
             asset: asset.clone(),
             enabled: true,
+            min_distribution_amount: None,
+            min_distribution_interval_seconds: None,
         };
         let info = mock_info("owner");
         execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -1051,8 +1142,8 @@ mod tests {
         let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
 
         let config = CONFIG.load(&deps.storage).unwrap();
-        let expected_safety_fund_amount = permissible_amount * config.safety_fund_fee_share;
-        let expected_treasury_amount = permissible_amount * config.treasury_fee_share;
+        let expected_safety_fund_amount = permissible_amount * config.fee_recipients[0].fee_share;
+        let expected_treasury_amount = permissible_amount * config.fee_recipients[1].fee_share;
         let expected_staking_amount =
             permissible_amount - (expected_safety_fund_amount + expected_treasury_amount);
 
@@ -1094,8 +1185,10 @@ mod tests {
                 attr("action", "distribute_protocol_income"),
                 attr("asset", "cw20_address"),
                 attr("total_distributed_amount", permissible_amount),
-                attr("safety_fund_amount", expected_safety_fund_amount),
-                attr("treasury_amount", expected_treasury_amount),
+                attr("fee_recipient", "safety_fund"),
+                attr("fee_recipient_amount", expected_safety_fund_amount),
+                attr("fee_recipient", "treasury"),
+                attr("fee_recipient_amount", expected_treasury_amount),
                 attr("staking_amount", expected_staking_amount),
             ]
         );
@@ -1110,9 +1203,9 @@ mod tests {
         // verify messages are correct
         let expected_rewards_to_be_distributed = Uint128::new(balance);
         let expected_safety_fund_amount =
-            expected_rewards_to_be_distributed * config.safety_fund_fee_share;
+            expected_rewards_to_be_distributed * config.fee_recipients[0].fee_share;
         let expected_treasury_amount =
-            expected_rewards_to_be_distributed * config.treasury_fee_share;
+            expected_rewards_to_be_distributed * config.fee_recipients[1].fee_share;
         let expected_staking_amount = expected_rewards_to_be_distributed
             - (expected_safety_fund_amount + expected_treasury_amount);
 
@@ -1157,13 +1250,271 @@ mod tests {
                     "total_distributed_amount",
                     expected_rewards_to_be_distributed
                 ),
-                attr("safety_fund_amount", expected_safety_fund_amount),
-                attr("treasury_amount", expected_treasury_amount),
+                attr("fee_recipient", "safety_fund"),
+                attr("fee_recipient_amount", expected_safety_fund_amount),
+                attr("fee_recipient", "treasury"),
+                attr("fee_recipient_amount", expected_treasury_amount),
                 attr("staking_amount", expected_staking_amount),
             ]
         );
     }
 
+    #[test]
+    fn test_distribute_protocol_rewards_to_several_fee_recipients() {
+        let mut deps = th_setup(&[]);
+
+        let balance = 1_000_000u128;
+        deps.querier.set_cw20_balances(
+            Addr::unchecked("cw20_address"),
+            &[(Addr::unchecked(MOCK_CONTRACT_ADDR), balance.into())],
+        );
+        let asset = Asset::Cw20 {
+            contract_addr: "cw20_address".to_string(),
+        };
+
+        let msg = ExecuteMsg::UpdateAssetConfig {
+            asset: asset.clone(),
+            enabled: true,
+            min_distribution_amount: None,
+            min_distribution_interval_seconds: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner"), msg).unwrap();
+
+        // split across three recipients, the remaining 25% goes to staking
+        let msg = ExecuteMsg::UpdateConfig {
+            config: CreateOrUpdateConfig {
+                owner: None,
+                address_provider_address: None,
+                fee_recipients: Some(th_fee_recipients(&[
+                    ("safety_fund", Decimal::percent(50)),
+                    ("treasury", Decimal::percent(15)),
+                    ("insurance_fund", Decimal::percent(10)),
+                ])),
+                astroport_factory_address: None,
+                astroport_max_spread: None,
+            },
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner"), msg).unwrap();
+
+        let msg = ExecuteMsg::DistributeProtocolRewards {
+            asset,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anybody"), msg).unwrap();
+
+        let expected_transfers = [
+            ("safety_fund", 500_000u128),
+            ("treasury", 150_000u128),
+            ("insurance_fund", 100_000u128),
+            ("staking", 250_000u128),
+        ];
+        let expected_messages: Vec<_> = expected_transfers
+            .iter()
+            .map(|(recipient, amount)| {
+                SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "cw20_address".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: recipient.to_string(),
+                        amount: Uint128::new(*amount),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }))
+            })
+            .collect();
+        assert_eq!(res.messages, expected_messages);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "distribute_protocol_income"),
+                attr("asset", "cw20_address"),
+                attr("total_distributed_amount", "1000000"),
+                attr("fee_recipient", "safety_fund"),
+                attr("fee_recipient_amount", "500000"),
+                attr("fee_recipient", "treasury"),
+                attr("fee_recipient_amount", "150000"),
+                attr("fee_recipient", "insurance_fund"),
+                attr("fee_recipient_amount", "100000"),
+                attr("staking_amount", "250000"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distribute_protocol_rewards_min_amount_and_interval() {
+        let mut deps = th_setup(&[]);
+
+        deps.querier.set_cw20_balances(
+            Addr::unchecked("cw20_address"),
+            &[(Addr::unchecked(MOCK_CONTRACT_ADDR), Uint128::new(999))],
+        );
+        let asset = Asset::Cw20 {
+            contract_addr: "cw20_address".to_string(),
+        };
+
+        let msg = ExecuteMsg::UpdateAssetConfig {
+            asset: asset.clone(),
+            enabled: true,
+            min_distribution_amount: Some(Uint128::new(1_000)),
+            min_distribution_interval_seconds: Some(3_600),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner"), msg).unwrap();
+
+        let msg = ExecuteMsg::DistributeProtocolRewards {
+            asset: asset.clone(),
+            amount: None,
+        };
+        let mut env = mock_env();
+
+        // below min amount: nothing is distributed
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anybody"),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "distribute_protocol_income"),
+                attr("asset", "cw20_address"),
+                attr("skipped", "below_min_distribution_amount"),
+            ]
+        );
+
+        // above min amount: balance is distributed
+        deps.querier.set_cw20_balances(
+            Addr::unchecked("cw20_address"),
+            &[(Addr::unchecked(MOCK_CONTRACT_ADDR), Uint128::new(2_000))],
+        );
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anybody"),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(res.attributes[2], attr("total_distributed_amount", "2000"));
+
+        // before min interval: nothing is distributed
+        env.block.time = env.block.time.plus_seconds(3_599);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anybody"),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(
+            res.attributes[2],
+            attr("skipped", "before_min_distribution_interval")
+        );
+
+        // after min interval: balance is distributed
+        env.block.time = env.block.time.plus_seconds(1);
+        let res = execute(deps.as_mut(), env, mock_info("anybody"), msg).unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(res.attributes[2], attr("total_distributed_amount", "2000"));
+
+        // asset config update keeps the thresholds when not given
+        let msg = ExecuteMsg::UpdateAssetConfig {
+            asset: asset.clone(),
+            enabled: true,
+            min_distribution_amount: None,
+            min_distribution_interval_seconds: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("owner"), msg).unwrap();
+        let asset_config = ASSET_CONFIG
+            .load(&deps.storage, asset.get_reference().as_slice())
+            .unwrap();
+        assert_eq!(asset_config.min_distribution_amount, Uint128::new(1_000));
+        assert_eq!(asset_config.min_distribution_interval_seconds, 3_600);
+    }
+
+    #[test]
+    fn test_swap_asset_to_uusd_leftovers() {
+        let mut deps = th_setup(&[coin(5_000, "uluna")]);
+
+        let offer_asset_info = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ask_asset_info = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        deps.querier.set_astroport_pair(PairInfo {
+            asset_infos: [offer_asset_info.clone(), ask_asset_info.clone()],
+            contract_addr: Addr::unchecked("pair_uluna_uusd"),
+            liquidity_token: Addr::unchecked("lp_uluna_uusd"),
+            pair_type: PairType::Xyk {},
+        });
+        // offering 1_000 uluna gives the max spread of 1%
+        deps.querier.set_astroport_pair_pool(
+            "pair_uluna_uusd".to_string(),
+            PoolResponse {
+                assets: [
+                    AstroportAsset {
+                        info: offer_asset_info.clone(),
+                        amount: Uint128::new(99_000),
+                    },
+                    AstroportAsset {
+                        info: ask_asset_info,
+                        amount: Uint128::new(990_000),
+                    },
+                ],
+                total_share: Uint128::new(100_000),
+            },
+        );
+
+        let query_leftover = |deps: Deps| -> Uint128 {
+            let res: SwapLeftoverResponse = from_binary(
+                &query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::SwapLeftover {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            res.amount
+        };
+
+        // partial fill records the leftover
+        let msg = ExecuteMsg::SwapAssetToUusd {
+            offer_asset_info: offer_asset_info.clone(),
+            amount: None,
+            fill_mode: Some(SwapFillMode::Partial),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone"), msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(query_leftover(deps.as_ref()), Uint128::new(4_000));
+
+        // full fill clears the leftover
+        let msg = ExecuteMsg::SwapAssetToUusd {
+            offer_asset_info,
+            amount: Some(Uint128::new(500)),
+            fill_mode: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone"), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "swap"),
+                attr("offer_asset", "uluna"),
+                attr("ask_asset", "uusd"),
+                attr("offer_asset_amount", "500"),
+            ]
+        );
+        assert_eq!(query_leftover(deps.as_ref()), Uint128::zero());
+    }
+
     #[test]
     fn test_execute_cosmos_msg() {
         let mut deps = th_setup(&[]);
@@ -1202,8 +1553,10 @@ mod tests {
         let config = CreateOrUpdateConfig {
             owner: Some("owner".to_string()),
             address_provider_address: Some("address_provider".to_string()),
-            safety_fund_fee_share: Some(Decimal::percent(10)),
-            treasury_fee_share: Some(Decimal::percent(20)),
+            fee_recipients: Some(th_fee_recipients(&[
+                ("safety_fund", Decimal::percent(10)),
+                ("treasury", Decimal::percent(20)),
+            ])),
             astroport_factory_address: Some("astroport".to_string()),
             astroport_max_spread: Some(StdDecimal::percent(1)),
         };
@@ -1211,4 +1564,14 @@ mod tests {
         instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
         deps
     }
+
+    fn th_fee_recipients(fee_recipients: &[(&str, Decimal)]) -> Vec<FeeRecipientParams> {
+        fee_recipients
+            .iter()
+            .map(|(address, fee_share)| FeeRecipientParams {
+                address: address.to_string(),
+                fee_share: *fee_share,
+            })
+            .collect()
+    }
 }
@@ -1,6 +1,11 @@
+use cosmwasm_std::Uint128;
 use cw_storage_plus::{Item, Map};
 
 use crate::{AssetConfig, Config};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const ASSET_CONFIG: Map<&[u8], AssetConfig> = Map::new("assets");
+/// Amount of each offer asset left unswapped by its last partial fill swap
+pub const SWAP_LEFTOVERS: Map<&[u8], Uint128> = Map::new("swap_leftovers");
+/// Timestamp (seconds) of the last distribution of each asset
+pub const LAST_DISTRIBUTION_TIMES: Map<&[u8], u64> = Map::new("last_distribution_times");
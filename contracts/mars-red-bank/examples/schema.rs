@@ -5,8 +5,8 @@ use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use mars_red_bank::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg};
 use mars_red_bank::{
-    ConfigResponse, Market, MarketsListResponse, UserAssetDebtResponse, UserCollateralResponse,
-    UserDebtResponse, UserPositionResponse,
+    ConfigResponse, Market, MarketIndexesResponse, MarketsListResponse, UserAssetDebtResponse,
+    UserCollateralResponse, UserDebtResponse, UserPositionResponse,
 };
 
 fn main() {
@@ -23,6 +23,7 @@ fn main() {
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(Market), &out_dir);
     export_schema(&schema_for!(MarketsListResponse), &out_dir);
+    export_schema(&schema_for!(MarketIndexesResponse), &out_dir);
     export_schema(&schema_for!(UserDebtResponse), &out_dir);
     export_schema(&schema_for!(UserAssetDebtResponse), &out_dir);
     export_schema(&schema_for!(UserCollateralResponse), &out_dir);
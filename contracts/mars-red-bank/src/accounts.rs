@@ -1,16 +1,18 @@
-use cosmwasm_std::{Addr, Deps, StdError, StdResult, Uint128};
+use cosmwasm_std::{Addr, Deps, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::U32Key;
 
 use mars_core::asset::AssetType;
 use mars_core::helpers::cw20_get_balance;
 use mars_core::math::decimal::Decimal;
+use mars_core::math::{compute_asset_amount, compute_asset_value};
 
 use mars_core::oracle;
 
 use crate::contract::{get_bit, market_get_from_index};
 use crate::error::ContractError;
-use crate::interest_rates::{get_underlying_debt_amount, get_underlying_liquidity_amount};
-use crate::state::DEBTS;
-use crate::{Debt, User, UserHealthStatus};
+use crate::interest_rates::{get_debt_underlying_amount, get_underlying_liquidity_amount};
+use crate::state::{DEBTS, EMODE_CATEGORIES, USER_EMODE_CATEGORIES};
+use crate::{Debt, EModeCategory, Market, User, UserHealthStatus, UserPositionResponse};
 
 /// User global position
 pub struct UserPosition {
@@ -42,7 +44,22 @@ impl UserPosition {
     }
 }
 
+impl From<&UserPosition> for UserPositionResponse {
+    fn from(user_position: &UserPosition) -> Self {
+        UserPositionResponse {
+            total_collateral_in_uusd: user_position.total_collateral_in_uusd,
+            total_debt_in_uusd: user_position.total_debt_in_uusd,
+            total_collateralized_debt_in_uusd: user_position.total_collateralized_debt_in_uusd,
+            max_debt_in_uusd: user_position.max_debt_in_uusd,
+            weighted_liquidation_threshold_in_uusd: user_position
+                .weighted_liquidation_threshold_in_uusd,
+            health_status: user_position.health_status.clone(),
+        }
+    }
+}
+
 /// User asset settlement
+#[derive(Clone)]
 pub struct UserAssetPosition {
     pub asset_label: String,
     pub asset_type: AssetType,
@@ -53,11 +70,37 @@ pub struct UserAssetPosition {
     pub max_ltv: Decimal,
     pub liquidation_threshold: Decimal,
     pub asset_price: Decimal,
+    pub asset_decimals: u8,
+    pub price_decimals: u8,
+}
+
+impl UserAssetPosition {
+    /// Value in uusd of an amount of the asset at the position price
+    pub fn compute_value(&self, amount: Uint128) -> StdResult<Uint128> {
+        compute_asset_value(
+            amount,
+            self.asset_decimals,
+            self.asset_price,
+            self.price_decimals,
+        )
+    }
+
+    /// Amount of the asset worth a value in uusd at the position price
+    pub fn compute_amount(&self, value: Uint128) -> StdResult<Uint128> {
+        compute_asset_amount(
+            value,
+            self.asset_decimals,
+            self.asset_price,
+            self.price_decimals,
+        )
+    }
 }
 
 /// Calculates the user data across the markets.
 /// This includes the total debt/collateral balances in uusd,
 /// the max debt in uusd, the average Liquidation threshold, and the Health factor.
+/// Collateral positions worth less than `min_collateral_value_in_uusd` are not taken into
+/// account for the max debt.
 pub fn get_user_position(
     deps: Deps,
     block_time: u64,
@@ -65,6 +108,7 @@ pub fn get_user_position(
     oracle_address: Addr,
     user: &User,
     market_count: u32,
+    min_collateral_value_in_uusd: Uint128,
 ) -> StdResult<UserPosition> {
     let user_asset_positions = get_user_asset_positions(
         deps,
@@ -75,6 +119,16 @@ pub fn get_user_position(
         block_time,
     )?;
 
+    build_user_position(user_asset_positions, min_collateral_value_in_uusd)
+}
+
+/// Computes the user totals, and the Health factor from a list of asset positions.
+/// Collateral positions worth less than `min_collateral_value_in_uusd` are not taken into
+/// account for the max debt.
+pub fn build_user_position(
+    user_asset_positions: Vec<UserAssetPosition>,
+    min_collateral_value_in_uusd: Uint128,
+) -> StdResult<UserPosition> {
     let mut total_collateral_in_uusd = Uint128::zero();
     let mut total_debt_in_uusd = Uint128::zero();
     let mut total_collateralized_debt_in_uusd = Uint128::zero();
@@ -82,16 +136,21 @@ pub fn get_user_position(
     let mut weighted_liquidation_threshold_in_uusd = Uint128::zero();
 
     for user_asset_position in &user_asset_positions {
-        let asset_price = user_asset_position.asset_price;
-        let collateral_in_uusd = user_asset_position.collateral_amount * asset_price;
+        let collateral_in_uusd =
+            user_asset_position.compute_value(user_asset_position.collateral_amount)?;
         total_collateral_in_uusd = total_collateral_in_uusd.checked_add(collateral_in_uusd)?;
 
-        max_debt_in_uusd =
-            max_debt_in_uusd.checked_add(collateral_in_uusd * user_asset_position.max_ltv)?;
+        // Collateral positions that are too small to be worth liquidating don't add
+        // borrowing power. They still count for the health factor so that raising the
+        // minimum only restricts new borrows and doesn't make existing positions liquidatable
+        if collateral_in_uusd >= min_collateral_value_in_uusd {
+            max_debt_in_uusd =
+                max_debt_in_uusd.checked_add(collateral_in_uusd * user_asset_position.max_ltv)?;
+        }
         weighted_liquidation_threshold_in_uusd = weighted_liquidation_threshold_in_uusd
             .checked_add(collateral_in_uusd * user_asset_position.liquidation_threshold)?;
 
-        let debt_in_uusd = user_asset_position.debt_amount * asset_price;
+        let debt_in_uusd = user_asset_position.compute_value(user_asset_position.debt_amount)?;
         total_debt_in_uusd = total_debt_in_uusd.checked_add(debt_in_uusd)?;
 
         if !user_asset_position.uncollateralized_debt {
@@ -137,6 +196,7 @@ fn get_user_asset_positions(
     block_time: u64,
 ) -> StdResult<Vec<UserAssetPosition>> {
     let mut ret: Vec<UserAssetPosition> = vec![];
+    let user_emode_category = load_user_emode_category(deps.storage, user_address)?;
 
     for i in 0_u32..market_count {
         let user_is_using_as_collateral = get_bit(user.collateral_assets, i)?;
@@ -158,11 +218,10 @@ fn get_user_asset_positions(
             let collateral_amount =
                 get_underlying_liquidity_amount(asset_balance_scaled, &market, block_time)?;
 
-            (
-                collateral_amount,
-                market.max_loan_to_value,
-                market.liquidation_threshold,
-            )
+            let (max_ltv, liquidation_threshold) =
+                get_collateral_params(&market, &user_emode_category);
+
+            (collateral_amount, max_ltv, liquidation_threshold)
         } else {
             (Uint128::zero(), Decimal::zero(), Decimal::zero())
         };
@@ -172,8 +231,7 @@ fn get_user_asset_positions(
             let user_debt: Debt =
                 DEBTS.load(deps.storage, (asset_reference_vec.as_slice(), user_address))?;
 
-            let debt_amount =
-                get_underlying_debt_amount(user_debt.amount_scaled, &market, block_time)?;
+            let debt_amount = get_debt_underlying_amount(&user_debt, &market, block_time)?;
 
             (debt_amount, user_debt.uncollateralized)
         } else {
@@ -213,9 +271,40 @@ fn get_user_asset_positions(
             max_ltv,
             liquidation_threshold,
             asset_price,
+            asset_decimals: market.asset_decimals,
+            price_decimals: market.price_decimals,
         };
         ret.push(user_asset_position);
     }
 
     Ok(ret)
 }
+
+/// Loads the e-mode category the user opted in to, if any
+pub fn load_user_emode_category(
+    storage: &dyn Storage,
+    user_address: &Addr,
+) -> StdResult<Option<(u32, EModeCategory)>> {
+    match USER_EMODE_CATEGORIES.may_load(storage, user_address)? {
+        Some(category_id) => {
+            let category = EMODE_CATEGORIES.load(storage, U32Key::new(category_id))?;
+            Ok(Some((category_id, category)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Returns the max ltv and the liquidation threshold of the market's collateral for a user
+/// in the given e-mode category. Collateral of assets in the user's category uses the
+/// category params, any other collateral uses the market params
+pub fn get_collateral_params(
+    market: &Market,
+    user_emode_category: &Option<(u32, EModeCategory)>,
+) -> (Decimal, Decimal) {
+    match user_emode_category {
+        Some((category_id, category)) if *category_id == market.emode_category_id => {
+            (category.max_loan_to_value, category.liquidation_threshold)
+        }
+        _ => (market.max_loan_to_value, market.liquidation_threshold),
+    }
+}
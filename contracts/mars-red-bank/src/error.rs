@@ -1,8 +1,9 @@
 use thiserror::Error;
 
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 
 use mars_core::error::MarsError;
+use mars_core::math::decimal::Decimal;
 
 use crate::interest_rate_models::InterestRateModelError;
 use crate::MarketError;
@@ -46,15 +47,42 @@ pub enum ContractError {
     #[error("User's health factor can't be less than 1 after withdraw")]
     InvalidHealthFactorAfterWithdraw {},
 
+    #[error("User's health factor can't be less than 1 after changing e-mode category")]
+    InvalidHealthFactorAfterEModeChange {},
+
+    #[error("E-mode category {category_id} not found")]
+    EModeCategoryNotFound { category_id: u32 },
+
+    #[error("Cannot change e-mode category {category_id} params or assets while users are opted in to it")]
+    EModeCategoryInUse { category_id: u32 },
+
+    #[error("Interest rate strategy {strategy_id} not found")]
+    InterestRateStrategyNotFound { strategy_id: u32 },
+
+    #[error("User is borrowing assets outside of the e-mode category")]
+    EModeCategoryBorrowMismatch {},
+
+    #[error("Cannot borrow an asset outside of the user's e-mode category (asset: {asset:?})")]
+    BorrowOutsideEModeCategory { asset: String },
+
     #[error("Asset is already initialized")]
     AssetAlreadyInitialized {},
 
+    #[error("No assets to initialize")]
+    NoAssetsToInit {},
+
     #[error("Asset not initialized")]
     AssetNotInitialized {},
 
+    #[error("Asset param {param_name} can only be tightened")]
+    AssetParamLoosened { param_name: String },
+
     #[error("Deposit amount must be greater than 0 {asset:?}")]
     InvalidDepositAmount { asset: String },
 
+    #[error("First deposit of the market must be above the locked liquidity (asset: {asset:?})")]
+    InitialDepositTooSmall { asset: String },
+
     #[error("Cannot have 0 as liquidity index")]
     InvalidLiquidityIndex {},
 
@@ -67,6 +95,24 @@ pub enum ContractError {
     #[error("Borrow amount exceeds maximum allowed given current collateral value")]
     BorrowAmountExceedsGivenCollateral {},
 
+    #[error("Borrow amount exceeds the {asset} debt ceiling of {debt_ceiling_in_uusd} uusd")]
+    DebtCeilingExceeded {
+        asset: String,
+        debt_ceiling_in_uusd: Uint128,
+    },
+
+    #[error("Health factor after borrow would be below the required buffer")]
+    BorrowHealthFactorBelowBuffer {},
+
+    #[error("User cannot use more than {max_markets_per_user} markets as collateral or debt")]
+    MaxMarketsPerUserExceeded { max_markets_per_user: u32 },
+
+    #[error("Health factor after repaying with collateral must be at least one or not lower than before")]
+    InvalidHealthFactorAfterRepayWithCollateral {},
+
+    #[error("Price of {asset} deviates from the last used price more than allowed, operations depending on it are paused")]
+    PriceDeviationExceeded { asset: String },
+
     #[error("Borrow amount exceeds uncollateralized loan limit given existing debt")]
     BorrowAmountExceedsUncollateralizedLoanLimit {},
 
@@ -76,6 +122,12 @@ pub enum ContractError {
     #[error("Cannot repay 0 debt")]
     CannotRepayZeroDebt {},
 
+    #[error("Cannot repay all debt of a user borrowing cw20 asset {asset:?}")]
+    CannotRepayAllCw20Debt { asset: String },
+
+    #[error("Sent funds are not enough to repay the full debt of asset {asset:?}")]
+    InsufficientFundsToRepayAll { asset: String },
+
     #[error("Amount to repay is greater than total debt")]
     CannotRepayMoreThanDebt {},
 
@@ -113,6 +165,12 @@ pub enum ContractError {
     #[error("Contract current asset balance cannot be less than liquidity taken")]
     OperationExceedsAvailableLiquidity {},
 
+    #[error("Borrow index {borrow_index} cannot be less than liquidity index {liquidity_index}")]
+    BorrowIndexBelowLiquidityIndex {
+        borrow_index: Decimal,
+        liquidity_index: Decimal,
+    },
+
     #[error("Deposit for {asset:?} is not enabled")]
     DepositNotEnabled { asset: String },
 
@@ -122,6 +180,9 @@ pub enum ContractError {
     #[error("Cannot perform operation. Market for {asset:?} is not active")]
     MarketNotActive { asset: String },
 
+    #[error("Cannot perform operation. maToken of market for {asset:?} is not registered")]
+    MaTokenNotInitialized { asset: String },
+
     #[error("Cannot liquidate. Debt asset {asset:?}")]
     LiquidationNotAllowedWhenDebtMarketInactive { asset: String },
 
@@ -136,6 +197,59 @@ pub enum ContractError {
 
     #[error("Cannot repay uncollateralized loan on behalf of another user")]
     CannotRepayUncollateralizedLoanOnBehalfOf {},
+
+    #[error("Cannot borrow {asset:?} before the borrow cooldown ends at {cooldown_end:?}")]
+    BorrowCooldownNotElapsed { asset: String, cooldown_end: u64 },
+
+    #[error(
+        "Cannot update {subject} before the update cooldown ends at {next_update_allowed_at:?}"
+    )]
+    UpdateCooldownNotElapsed {
+        subject: String,
+        next_update_allowed_at: u64,
+    },
+
+    #[error("Stable rate borrowing is not enabled for {asset:?}")]
+    StableBorrowNotEnabled { asset: String },
+
+    #[error("Cannot swap borrow rate mode of zero debt")]
+    CannotSwapBorrowRateModeOfZeroDebt {},
+
+    #[error("Market for {asset} is already deprecated")]
+    MarketAlreadyDeprecated { asset: String },
+
+    #[error("Conditions to rebalance the stable borrow rate are not met")]
+    StableBorrowRateRebalanceNotAllowed {},
+
+    #[error("Cannot sweep dust while user is borrowing")]
+    CannotSweepDustWhenBorrowing {},
+
+    #[error("User balance is not below the dust threshold (asset: {asset:?})")]
+    BalanceAboveDustThreshold { asset: String },
+
+    #[error("Market has no rounding dust to sweep (asset: {asset:?})")]
+    NoRoundingDust { asset: String },
+
+    #[error("Market has no liquidation fees to claim (asset: {asset:?})")]
+    NoLiquidationFees { asset: String },
+
+    #[error("Cannot update asset type of a market with deposits or debt (asset: {asset:?})")]
+    MarketNotEmpty { asset: String },
+
+    #[error("User has not authorized auto-repayments")]
+    AutoRepayNotAuthorized {},
+
+    #[error("User health factor is not below the auto-repay threshold")]
+    AutoRepayThresholdNotReached {},
+
+    #[error("Cannot socialize the debt of a user that has collateral")]
+    CannotSocializeDebtOfUserWithCollateral {},
+
+    #[error("Loss of {loss_amount} cannot be socialized as it is not lower than the market deposits ({deposits_amount})")]
+    LossExceedsDeposits {
+        loss_amount: Uint128,
+        deposits_amount: Uint128,
+    },
 }
 
 impl ContractError {
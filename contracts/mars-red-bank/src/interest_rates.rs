@@ -1,46 +1,83 @@
 use std::str;
 
 use cosmwasm_std::{
-    to_binary, Addr, CosmosMsg, DepsMut, Env, Event, Response, StdError, StdResult, Uint128,
-    WasmMsg,
+    to_binary, Addr, CosmosMsg, Deps, DepsMut, Env, Event, Response, StdError, StdResult, Storage,
+    Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::U32Key;
 
 use mars_core::asset::get_asset_balance;
 use mars_core::math::decimal::Decimal;
-use mars_core::math::uint128_checked_div_with_ceil;
+use mars_core::math::{decimal_multiplication, uint128_checked_div_with_ceil};
+use mars_core::red_bank::scaled_amount::ScaledAmount;
 
 use crate::error::ContractError;
-use crate::interest_rate_models::update_market_interest_rates_with_model;
-use crate::Market;
+use crate::interest_rate_models::{
+    get_liquidity_rate, init_interest_rate_model, update_market_interest_rates_with_model,
+    InterestRateModel, InterestRateModelParams,
+};
+use crate::state::INTEREST_RATE_STRATEGIES;
+use crate::{BorrowRateMode, Debt, Market};
 
-/// Scaling factor used to keep more precision during division / multiplication by index.
-pub const SCALING_FACTOR: Uint128 = Uint128::new(1_000_000);
+pub use mars_core::red_bank::scaled_amount::SCALING_FACTOR;
 
 const SECONDS_PER_YEAR: u64 = 31536000u64;
 
 /// Calculates accumulated interest for the time between last time market index was updated
 /// and current block.
 /// Applies desired side effects:
-/// 1. Updates market borrow and liquidity indices.
-/// 2. If there are any protocol rewards, builds a mint to the rewards collector and adds it
-///    to the returned response
+/// 1. Updates market borrow and liquidity indices and accrues the stable debt at the average
+///    stable borrow rate.
+/// 2. Pays the liquidity rate subsidy (see `Market::min_liquidity_rate`) from the accrued
+///    protocol rewards, keeping track of the part that could not be covered yet
+/// 3. If there are any protocol rewards left, builds a mint to the rewards collector and adds
+///    it to the returned response
 /// NOTE: it does not save the market to store
 /// WARNING: For a given block, this function should be called before updating interest rates
 /// as it would apply the new interest rates instead of the ones that were valid during
 /// the period between indexes_last_updated and current_block
+///
+/// Returns an error if the liquidity index grows more than the market debt (at both variable and
+/// stable rates) since the last update, as borrowers should always pay at least what depositors
+/// earn
 pub fn apply_accumulated_interests(
     env: &Env,
     protocol_rewards_collector_address: Addr,
     market: &mut Market,
     mut response: Response,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let current_timestamp = env.block.time.seconds();
     let previous_borrow_index = market.borrow_index;
+    let previous_liquidity_index = market.liquidity_index;
+    let mut liquidity_subsidy_accrued = Uint128::zero();
+    let mut stable_interest_accrued = Uint128::zero();
 
     // Update market indices
     if market.indexes_last_updated < current_timestamp {
         let time_elapsed = current_timestamp - market.indexes_last_updated;
+        let overall_borrow_rate = get_overall_borrow_rate(market, market.indexes_last_updated)?;
+
+        if market.liquidity_rate_subsidy > Decimal::zero() {
+            let previous_deposits_total = compute_underlying_amount(
+                market.total_deposits_scaled,
+                market.liquidity_index,
+                ScalingOperation::Truncate,
+            )?;
+            let subsidy_factor = market
+                .liquidity_rate_subsidy
+                .checked_mul(Decimal::from_ratio(
+                    Uint128::from(time_elapsed),
+                    Uint128::from(SECONDS_PER_YEAR),
+                ))?;
+            liquidity_subsidy_accrued = previous_deposits_total * subsidy_factor;
+        }
+
+        if !market.stable_debt_total.is_zero() {
+            let stable_debt_total = get_stable_debt_total(market, current_timestamp)?;
+            stable_interest_accrued = stable_debt_total.checked_sub(market.stable_debt_total)?;
+            market.stable_debt_total = stable_debt_total;
+        }
 
         if market.borrow_rate > Decimal::zero() {
             market.borrow_index = calculate_applied_linear_interest_rate(
@@ -57,6 +94,21 @@ pub fn apply_accumulated_interests(
             )?;
         }
         market.indexes_last_updated = current_timestamp;
+        market.interests_compounding_count += 1;
+
+        // Stable borrowers can pay more than the variable rate, so the liquidity index is
+        // checked against a debt index growing at the overall borrow rate over the same period
+        let debt_growth_index = calculate_applied_linear_interest_rate(
+            previous_liquidity_index,
+            overall_borrow_rate,
+            time_elapsed,
+        )?;
+        if debt_growth_index < market.liquidity_index {
+            return Err(ContractError::BorrowIndexBelowLiquidityIndex {
+                borrow_index: debt_growth_index,
+                liquidity_index: market.liquidity_index,
+            });
+        }
     }
 
     // Compute accrued protocol rewards
@@ -71,7 +123,7 @@ pub fn apply_accumulated_interests(
         ScalingOperation::Ceil,
     )?;
 
-    let borrow_interest_accrued = if new_debt_total > previous_debt_total {
+    let variable_interest_accrued = if new_debt_total > previous_debt_total {
         // debt stays constant between the application of the interest rate
         // so the difference between debt at the start and the end is the
         // total borrow interest accrued
@@ -79,8 +131,32 @@ pub fn apply_accumulated_interests(
     } else {
         Uint128::zero()
     };
+    let borrow_interest_accrued = variable_interest_accrued.checked_add(stable_interest_accrued)?;
+
+    let mut accrued_protocol_rewards = borrow_interest_accrued * market.reserve_factor;
+
+    // Depositors earnings above the model liquidity rate are paid from protocol rewards
+    if !liquidity_subsidy_accrued.is_zero() || !market.pending_liquidity_subsidy.is_zero() {
+        market.total_liquidity_subsidy = market
+            .total_liquidity_subsidy
+            .checked_add(liquidity_subsidy_accrued)?;
+        let subsidy_owed = market
+            .pending_liquidity_subsidy
+            .checked_add(liquidity_subsidy_accrued)?;
+        let subsidy_paid = subsidy_owed.min(accrued_protocol_rewards);
+        market.pending_liquidity_subsidy = subsidy_owed - subsidy_paid;
+        accrued_protocol_rewards -= subsidy_paid;
+    }
 
-    let accrued_protocol_rewards = borrow_interest_accrued * market.reserve_factor;
+    // Interest waived by borrow rate discounts was already paid to depositors so it is taken
+    // from protocol rewards
+    if !market.pending_borrow_rate_discount.is_zero() {
+        let discount_paid = market
+            .pending_borrow_rate_discount
+            .min(accrued_protocol_rewards);
+        market.pending_borrow_rate_discount -= discount_paid;
+        accrued_protocol_rewards -= discount_paid;
+    }
 
     if accrued_protocol_rewards > Uint128::zero() {
         let mint_amount = compute_scaled_amount(
@@ -88,6 +164,7 @@ pub fn apply_accumulated_interests(
             market.liquidity_index,
             ScalingOperation::Truncate,
         )?;
+        market.total_deposits_scaled = market.total_deposits_scaled.checked_add(mint_amount)?;
         response = response.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: market.ma_token_address.clone().into(),
             msg: to_binary(&Cw20ExecuteMsg::Mint {
@@ -105,11 +182,35 @@ pub fn calculate_applied_linear_interest_rate(
     rate: Decimal,
     time_elapsed: u64,
 ) -> StdResult<Decimal> {
-    let rate_factor = rate.checked_mul(Decimal::from_ratio(
-        Uint128::from(time_elapsed),
-        Uint128::from(SECONDS_PER_YEAR),
-    ))?;
-    index.checked_mul(Decimal::one() + rate_factor)
+    let rate_factor = decimal_multiplication(
+        rate,
+        Decimal::from_ratio(Uint128::from(time_elapsed), Uint128::from(SECONDS_PER_YEAR)),
+    )?;
+    decimal_multiplication(index, Decimal::one() + rate_factor)
+}
+
+/// Average number of index updates (each of them compounding interests) per year since the
+/// market was initialized
+pub fn get_compoundings_per_year(market: &Market, timestamp: u64) -> u64 {
+    if timestamp <= market.created_at {
+        return 0;
+    }
+    let seconds_since_creation = (timestamp - market.created_at) as u128;
+    let compoundings_per_year = market.interests_compounding_count as u128
+        * SECONDS_PER_YEAR as u128
+        / seconds_since_creation;
+    compoundings_per_year as u64
+}
+
+/// Effective annual rate of an annual rate compounded `compoundings_per_year` times per year:
+/// (1 + rate / n)^n - 1. Returns the rate itself if there is no compounding
+pub fn get_compounded_rate(rate: Decimal, compoundings_per_year: u64) -> StdResult<Decimal> {
+    if compoundings_per_year <= 1 {
+        return Ok(rate);
+    }
+    let period_rate = rate.checked_div(Decimal::from_ratio(compoundings_per_year, 1u128))?;
+    let compounded = (Decimal::one() + period_rate).checked_pow(compoundings_per_year)?;
+    Ok(compounded - Decimal::one())
 }
 
 /// Get scaled liquidity amount from an underlying amount, a Market and timestamp in seconds
@@ -186,6 +287,232 @@ pub fn get_underlying_debt_amount(
     )
 }
 
+/// Waives the discounted part of the interest accrued by the debt since the discount was last
+/// applied, reducing the debt scaled amount and the market total debt accordingly. The waived
+/// interest is added to the market pending discount to be paid from protocol rewards.
+/// NOTE: function should be called before changing the debt amount, as the discount is applied
+/// to the current amount for the whole period since it was last applied
+pub fn apply_debt_discount(debt: &mut Debt, market: &mut Market, timestamp: u64) -> StdResult<()> {
+    let borrow_index = get_updated_borrow_index(market, timestamp)?;
+
+    let (rebate, rebate_scaled) = compute_debt_discount_rebate(debt, borrow_index)?;
+    if !rebate_scaled.is_zero() {
+        debt.amount_scaled = debt.amount_scaled.checked_sub(rebate_scaled)?;
+        market.debt_total_scaled = market.debt_total_scaled.checked_sub(rebate_scaled)?;
+        market.pending_borrow_rate_discount =
+            market.pending_borrow_rate_discount.checked_add(rebate)?;
+    }
+
+    debt.discount_borrow_index = borrow_index;
+    Ok(())
+}
+
+/// Underlying and scaled amounts of the interest waived for the debt since the discount was
+/// last applied up to the given borrow index.
+/// The rebate is truncated so rounding errors accumulate in favor of the protocol
+fn compute_debt_discount_rebate(
+    debt: &Debt,
+    borrow_index: Decimal,
+) -> StdResult<(Uint128, Uint128)> {
+    // Stable rate debts don't follow the borrow rate so the discount does not apply to them
+    if debt.rate_mode != BorrowRateMode::Variable
+        || debt.borrow_rate_discount.is_zero()
+        || debt.amount_scaled.is_zero()
+        || borrow_index <= debt.discount_borrow_index
+    {
+        return Ok((Uint128::zero(), Uint128::zero()));
+    }
+
+    let debt_amount =
+        compute_underlying_amount(debt.amount_scaled, borrow_index, ScalingOperation::Ceil)?;
+    let debt_amount_at_last_discount = compute_underlying_amount(
+        debt.amount_scaled,
+        debt.discount_borrow_index,
+        ScalingOperation::Ceil,
+    )?;
+    let accrued_interest = debt_amount.saturating_sub(debt_amount_at_last_discount);
+    let rebate = accrued_interest * debt.borrow_rate_discount;
+    let rebate_scaled = compute_scaled_amount(rebate, borrow_index, ScalingOperation::Truncate)?;
+    Ok((rebate, rebate_scaled))
+}
+
+/// Get underlying amount owed by a debt at a timestamp in seconds, taking into account its
+/// borrow rate mode and the borrow rate discount not applied yet.
+/// Stable rate debt is the amount owed when stable rate interests were last applied plus
+/// linear interests at the stable rate since then.
+/// NOTE: As for `get_underlying_debt_amount`, market interests should not be up to date with
+/// the current block if the index is not
+pub fn get_debt_underlying_amount(
+    debt: &Debt,
+    market: &Market,
+    timestamp: u64,
+) -> StdResult<Uint128> {
+    match debt.rate_mode {
+        BorrowRateMode::Variable => {
+            let borrow_index = get_updated_borrow_index(market, timestamp)?;
+            let (_, rebate_scaled) = compute_debt_discount_rebate(debt, borrow_index)?;
+            compute_underlying_amount(
+                debt.amount_scaled.checked_sub(rebate_scaled)?,
+                borrow_index,
+                ScalingOperation::Ceil,
+            )
+        }
+        BorrowRateMode::Stable => {
+            let debt_amount_at_last_update = compute_underlying_amount(
+                debt.amount_scaled,
+                debt.stable_rate_borrow_index,
+                ScalingOperation::Ceil,
+            )?;
+            let stable_rate_factor = calculate_applied_linear_interest_rate(
+                Decimal::one(),
+                debt.stable_borrow_rate,
+                timestamp.saturating_sub(debt.stable_rate_last_updated),
+            )?;
+            Decimal::multiply_uint128_by_decimal_and_ceil(
+                debt_amount_at_last_update,
+                stable_rate_factor,
+            )
+        }
+    }
+}
+
+/// Stable borrow rate offered by the market: the current borrow rate plus the market stable
+/// borrow rate premium
+pub fn get_market_stable_borrow_rate(market: &Market) -> Decimal {
+    market.borrow_rate + market.stable_borrow_rate_premium
+}
+
+/// Applies the stable rate interests accrued by the debt since they were last applied, by
+/// resetting the debt scaled amount to the stable rate debt amount at the current market
+/// borrow index, so the debt amount can be computed like a variable one until it changes.
+/// Stable debts are not part of the market `debt_total_scaled`: the market tracks them in
+/// `stable_debt_total`, which already accrues the debt interests.
+/// NOTE: function should be called before changing the debt amount or its stable rate
+pub fn apply_stable_borrow_rate(debt: &mut Debt, market: &Market, timestamp: u64) -> StdResult<()> {
+    let borrow_index = get_updated_borrow_index(market, timestamp)?;
+
+    if debt.rate_mode == BorrowRateMode::Stable && !debt.amount_scaled.is_zero() {
+        let debt_amount = get_debt_underlying_amount(debt, market, timestamp)?;
+        debt.amount_scaled =
+            compute_scaled_amount(debt_amount, borrow_index, ScalingOperation::Ceil)?;
+    }
+
+    debt.stable_rate_borrow_index = borrow_index;
+    debt.stable_rate_last_updated = timestamp;
+    Ok(())
+}
+
+/// Underlying stable rate debt of the market at a timestamp in seconds: the stable debt when the
+/// indexes were last updated plus linear interests at the average stable borrow rate since then.
+/// Truncated so protocol rewards are never computed on interests that are not owed
+pub fn get_stable_debt_total(market: &Market, timestamp: u64) -> StdResult<Uint128> {
+    if market.stable_debt_total.is_zero() || market.indexes_last_updated >= timestamp {
+        return Ok(market.stable_debt_total);
+    }
+
+    let stable_rate_factor = calculate_applied_linear_interest_rate(
+        Decimal::one(),
+        market.average_stable_borrow_rate,
+        timestamp - market.indexes_last_updated,
+    )?;
+    Decimal::multiply_uint128_by_decimal(market.stable_debt_total, stable_rate_factor)
+}
+
+/// Underlying debt of the market at a timestamp in seconds, at both variable and stable rates
+pub fn get_underlying_market_debt_total(market: &Market, timestamp: u64) -> StdResult<Uint128> {
+    get_underlying_debt_amount(market.debt_total_scaled, market, timestamp)?
+        .checked_add(get_stable_debt_total(market, timestamp)?)
+        .map_err(StdError::from)
+}
+
+/// Adds an amount borrowed at a stable rate to the market stable debt, updating the average
+/// stable borrow rate.
+/// NOTE: market interests should be up to date with the current block
+pub fn increase_stable_debt(
+    market: &mut Market,
+    amount: Uint128,
+    stable_borrow_rate: Decimal,
+) -> StdResult<()> {
+    let stable_debt_total = market.stable_debt_total.checked_add(amount)?;
+    if stable_debt_total.is_zero() {
+        return Ok(());
+    }
+
+    market.average_stable_borrow_rate =
+        Decimal::from_ratio(market.stable_debt_total, stable_debt_total)
+            .checked_mul(market.average_stable_borrow_rate)?
+            + Decimal::from_ratio(amount, stable_debt_total).checked_mul(stable_borrow_rate)?;
+    market.stable_debt_total = stable_debt_total;
+    Ok(())
+}
+
+/// Removes an amount owed at a stable rate from the market stable debt, updating the average
+/// stable borrow rate. As the market total is truncated, it can end up lower than the sum of
+/// the debts, so it is floored at zero.
+/// NOTE: market interests should be up to date with the current block
+pub fn decrease_stable_debt(
+    market: &mut Market,
+    amount: Uint128,
+    stable_borrow_rate: Decimal,
+) -> StdResult<()> {
+    if amount >= market.stable_debt_total {
+        market.stable_debt_total = Uint128::zero();
+        market.average_stable_borrow_rate = Decimal::zero();
+        return Ok(());
+    }
+
+    let stable_debt_total = market.stable_debt_total - amount;
+    let total_rate = Decimal::from_ratio(market.stable_debt_total, stable_debt_total)
+        .checked_mul(market.average_stable_borrow_rate)?;
+    let removed_rate =
+        Decimal::from_ratio(amount, stable_debt_total).checked_mul(stable_borrow_rate)?;
+    market.average_stable_borrow_rate = if total_rate > removed_rate {
+        total_rate - removed_rate
+    } else {
+        Decimal::zero()
+    };
+    market.stable_debt_total = stable_debt_total;
+    Ok(())
+}
+
+/// Removes a repaid amount from the market total debt of the debt rate mode: the scaled amount
+/// for a variable debt and the underlying amount for a stable one.
+/// NOTE: market interests should be up to date with the current block
+pub fn decrease_market_debt(
+    market: &mut Market,
+    debt: &Debt,
+    amount: Uint128,
+    amount_scaled: Uint128,
+) -> StdResult<()> {
+    match debt.rate_mode {
+        BorrowRateMode::Variable => {
+            market.debt_total_scaled = market.debt_total_scaled.checked_sub(amount_scaled)?;
+            Ok(())
+        }
+        BorrowRateMode::Stable => decrease_stable_debt(market, amount, debt.stable_borrow_rate),
+    }
+}
+
+/// Borrow rate paid on average by the market borrowers, weighting the variable borrow rate
+/// and the average stable borrow rate by the variable and stable debts
+pub fn get_overall_borrow_rate(market: &Market, timestamp: u64) -> StdResult<Decimal> {
+    let variable_debt_total =
+        get_underlying_debt_amount(market.debt_total_scaled, market, timestamp)?;
+    let stable_debt_total = get_stable_debt_total(market, timestamp)?;
+    let debt_total = variable_debt_total.checked_add(stable_debt_total)?;
+    if debt_total.is_zero() {
+        return Ok(market.borrow_rate);
+    }
+
+    Ok(decimal_multiplication(
+        Decimal::from_ratio(variable_debt_total, debt_total),
+        market.borrow_rate,
+    )? + decimal_multiplication(
+        Decimal::from_ratio(stable_debt_total, debt_total),
+        market.average_stable_borrow_rate,
+    )?)
+}
+
 pub enum ScalingOperation {
     Truncate,
     Ceil,
@@ -202,11 +529,13 @@ pub fn compute_scaled_amount(
     index: Decimal,
     scaling_operation: ScalingOperation,
 ) -> StdResult<Uint128> {
-    // Scale by SCALING_FACTOR to have better precision
-    let scaled_amount = amount.checked_mul(SCALING_FACTOR)?;
     match scaling_operation {
-        ScalingOperation::Truncate => Decimal::divide_uint128_by_decimal(scaled_amount, index),
-        ScalingOperation::Ceil => Decimal::divide_uint128_by_decimal_and_ceil(scaled_amount, index),
+        ScalingOperation::Truncate => Ok(ScaledAmount::from_underlying(amount, index)?.amount()),
+        ScalingOperation::Ceil => {
+            // Scale by SCALING_FACTOR to have better precision
+            let scaled_amount = amount.checked_mul(SCALING_FACTOR)?;
+            Decimal::divide_uint128_by_decimal_and_ceil(scaled_amount, index)
+        }
     }
 }
 
@@ -217,13 +546,13 @@ pub fn compute_underlying_amount(
     index: Decimal,
     scaling_operation: ScalingOperation,
 ) -> StdResult<Uint128> {
-    // Multiply scaled amount by decimal (index)
-    let before_scaling_factor = scaled_amount * index;
-
-    // Descale by SCALING_FACTOR which is introduced when scaling the amount
     match scaling_operation {
-        ScalingOperation::Truncate => Ok(before_scaling_factor.checked_div(SCALING_FACTOR)?),
+        ScalingOperation::Truncate => ScaledAmount::new(scaled_amount).to_underlying(index),
         ScalingOperation::Ceil => {
+            // Multiply scaled amount by decimal (index)
+            let before_scaling_factor = Decimal::multiply_uint128_by_decimal(scaled_amount, index)?;
+
+            // Descale by SCALING_FACTOR which is introduced when scaling the amount
             uint128_checked_div_with_ceil(before_scaling_factor, SCALING_FACTOR)
         }
     }
@@ -275,6 +604,37 @@ pub fn get_updated_liquidity_index(market: &Market, timestamp: u64) -> StdResult
     Ok(market.liquidity_index)
 }
 
+/// Spreads a loss of underlying among the market depositors by lowering the liquidity index
+/// in the same proportion as the loss is of the total deposits. The liquidity index is expected
+/// to be up to date. The loss needs to be lower than the total deposits so the index stays
+/// above zero
+pub fn socialize_loss(market: &mut Market, loss_amount: Uint128) -> Result<(), ContractError> {
+    let deposits_amount = compute_underlying_amount(
+        market.total_deposits_scaled,
+        market.liquidity_index,
+        ScalingOperation::Truncate,
+    )?;
+    if loss_amount >= deposits_amount {
+        return Err(ContractError::LossExceedsDeposits {
+            loss_amount,
+            deposits_amount,
+        });
+    }
+
+    // Truncating the new index rounds the loss up, in favor of the protocol
+    let remaining_ratio = Decimal::from_ratio(deposits_amount - loss_amount, deposits_amount);
+    let liquidity_index = market.liquidity_index.checked_mul(remaining_ratio)?;
+    if liquidity_index.is_zero() {
+        return Err(ContractError::LossExceedsDeposits {
+            loss_amount,
+            deposits_amount,
+        });
+    }
+    market.liquidity_index = liquidity_index;
+
+    Ok(())
+}
+
 /// Update interest rates for current liquidity and debt levels
 /// Note it does not save the market to the store (that is left to the caller)
 /// Returns response with appended interest rates updated event
@@ -288,9 +648,118 @@ pub fn update_interest_rates(
     asset_label: &str,
     mut response: Response,
 ) -> Result<Response, ContractError> {
-    // compute utilization rate
+    apply_interest_rate_strategy(deps.as_ref().storage, env, market)?;
+
+    let current_utilization_rate =
+        get_utilization_rate(deps.as_ref(), env, market, liquidity_taken, asset_label)?;
+
+    match market.deprecated_at {
+        // The borrow rate stays frozen during the deprecation grace period
+        Some(deprecated_at)
+            if env.block.time.seconds()
+                < deprecated_at.saturating_add(market.deprecation_grace_period_seconds) => {}
+
+        Some(_) => {
+            update_market_interest_rates_with_model(env, market, current_utilization_rate)?;
+            if market.borrow_rate < market.deprecation_penalty_borrow_rate {
+                market.borrow_rate = market.deprecation_penalty_borrow_rate;
+            }
+        }
+
+        None => {
+            update_market_interest_rates_with_model(env, market, current_utilization_rate)?;
+        }
+    }
+
+    // The liquidity rate always follows the utilization and what variable and stable borrowers
+    // actually pay, so depositors don't earn more than borrowers pay
+    let overall_borrow_rate = get_overall_borrow_rate(market, env.block.time.seconds())?;
+    market.liquidity_rate = get_liquidity_rate(
+        overall_borrow_rate,
+        current_utilization_rate,
+        market.reserve_factor,
+    )?;
+    apply_min_liquidity_rate(market, overall_borrow_rate, current_utilization_rate)?;
+
+    response = response.add_event(build_interests_updated_event(asset_label, market));
+    Ok(response)
+}
+
+/// Sets the params of the shared interest rate strategy the market follows (if any) on its
+/// interest rate model. The state of a dynamic model is kept when the strategy is dynamic too
+fn apply_interest_rate_strategy(
+    storage: &dyn Storage,
+    env: &Env,
+    market: &mut Market,
+) -> Result<(), ContractError> {
+    if market.interest_rate_strategy_id == 0 {
+        return Ok(());
+    }
+
+    let strategy_params = INTEREST_RATE_STRATEGIES
+        .may_load(storage, U32Key::new(market.interest_rate_strategy_id))?
+        .ok_or(ContractError::InterestRateStrategyNotFound {
+            strategy_id: market.interest_rate_strategy_id,
+        })?;
+
+    market.interest_rate_model = match (&market.interest_rate_model, strategy_params) {
+        (
+            InterestRateModel::Dynamic { state, .. },
+            InterestRateModelParams::Dynamic(dynamic_params),
+        ) => InterestRateModel::Dynamic {
+            params: dynamic_params,
+            state: state.clone(),
+        },
+        (_, strategy_params) => {
+            init_interest_rate_model(strategy_params, env.block.time.seconds())?
+        }
+    };
+
+    Ok(())
+}
+
+/// Raises the liquidity rate to the market's `min_liquidity_rate` and records the subsidized
+/// part of the rate. The floor is capped at the borrow rate, and the subsidy at the current
+/// reserve income (overall borrow rate * utilization * reserve factor) so protocol rewards
+/// can always pay for it
+fn apply_min_liquidity_rate(
+    market: &mut Market,
+    overall_borrow_rate: Decimal,
+    current_utilization_rate: Decimal,
+) -> StdResult<()> {
+    let reserve_income_rate = overall_borrow_rate
+        .checked_mul(current_utilization_rate)?
+        .checked_mul(market.reserve_factor)?;
+    let max_floor = market.liquidity_rate + reserve_income_rate;
+    let mut floor = if market.min_liquidity_rate < market.borrow_rate {
+        market.min_liquidity_rate
+    } else {
+        market.borrow_rate
+    };
+    if floor > max_floor {
+        floor = max_floor;
+    }
+
+    if market.liquidity_rate < floor {
+        market.liquidity_rate_subsidy = floor - market.liquidity_rate;
+        market.liquidity_rate = floor;
+    } else {
+        market.liquidity_rate_subsidy = Decimal::zero();
+    }
+    Ok(())
+}
+
+/// Market utilization rate (total debt over total debt plus available liquidity) once
+/// `liquidity_taken` is taken out of the contract balance
+pub fn get_utilization_rate(
+    deps: Deps,
+    env: &Env,
+    market: &Market,
+    liquidity_taken: Uint128,
+    asset_label: &str,
+) -> Result<Decimal, ContractError> {
     let contract_current_balance = get_asset_balance(
-        deps.as_ref(),
+        deps,
         env.contract.address.clone(),
         asset_label.to_string(),
         market.asset_type,
@@ -299,19 +768,15 @@ pub fn update_interest_rates(
         return Err(ContractError::OperationExceedsAvailableLiquidity {});
     }
     let available_liquidity = contract_current_balance - liquidity_taken;
-    let total_debt =
-        get_underlying_debt_amount(market.debt_total_scaled, market, env.block.time.seconds())?;
-    let current_utilization_rate = if total_debt > Uint128::zero() {
+    let total_debt = get_underlying_market_debt_total(market, env.block.time.seconds())?;
+    let utilization_rate = if total_debt > Uint128::zero() {
         let liquidity_and_debt = available_liquidity.checked_add(total_debt)?;
         Decimal::from_ratio(total_debt, liquidity_and_debt)
     } else {
         Decimal::zero()
     };
 
-    update_market_interest_rates_with_model(env, market, current_utilization_rate)?;
-
-    response = response.add_event(build_interests_updated_event(asset_label, market));
-    Ok(response)
+    Ok(utilization_rate)
 }
 
 pub fn build_interests_updated_event(label: &str, market: &Market) -> Event {
@@ -325,13 +790,21 @@ pub fn build_interests_updated_event(label: &str, market: &Market) -> Event {
 
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::Uint128;
+    use cosmwasm_std::{coin, Addr, Response, Uint128};
     use mars_core::math::decimal::Decimal;
+    use mars_core::red_bank::interest_rate_models::{
+        InterestRateModel, LinearInterestRateModelParams,
+    };
     use mars_core::red_bank::Market;
+    use mars_core::testing::{mock_dependencies, mock_env_at_block_time};
 
+    use crate::error::ContractError;
     use crate::interest_rates::{
-        calculate_applied_linear_interest_rate, get_scaled_debt_amount,
-        get_scaled_liquidity_amount, get_underlying_debt_amount, get_underlying_liquidity_amount,
+        apply_accumulated_interests, calculate_applied_linear_interest_rate, decrease_stable_debt,
+        get_compounded_rate, get_compoundings_per_year, get_overall_borrow_rate,
+        get_scaled_debt_amount, get_scaled_liquidity_amount, get_underlying_debt_amount,
+        get_underlying_liquidity_amount, get_underlying_market_debt_total, increase_stable_debt,
+        socialize_loss, update_interest_rates, SCALING_FACTOR,
     };
 
     #[test]
@@ -345,6 +818,84 @@ mod tests {
         assert_eq!(accumulated, Decimal::from_ratio(11u128, 100u128));
     }
 
+    #[test]
+    fn test_interests_compounding() {
+        let mut market = Market {
+            borrow_rate: Decimal::percent(10),
+            liquidity_rate: Decimal::percent(10),
+            created_at: 1,
+            indexes_last_updated: 1,
+            ..Default::default()
+        };
+        let mut yearly_market = market.clone();
+
+        // Interests are simple between index updates and compound on each one of them
+        for block_time in &[1 + 15768000, 1 + 31536000] {
+            apply_accumulated_interests(
+                &mock_env_at_block_time(*block_time),
+                Addr::unchecked("protocol_rewards_collector"),
+                &mut market,
+                Response::new(),
+            )
+            .unwrap();
+        }
+        apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut yearly_market,
+            Response::new(),
+        )
+        .unwrap();
+
+        assert_eq!(market.interests_compounding_count, 2);
+        assert_eq!(
+            market.liquidity_index,
+            Decimal::from_ratio(11025u128, 10000u128)
+        );
+        assert_eq!(
+            market.borrow_index,
+            Decimal::from_ratio(11025u128, 10000u128)
+        );
+        assert_eq!(yearly_market.interests_compounding_count, 1);
+        assert_eq!(
+            yearly_market.liquidity_index,
+            Decimal::from_ratio(11u128, 10u128)
+        );
+
+        // Reported effective rates match the realized index growth
+        let compoundings_per_year = get_compoundings_per_year(&market, 1 + 31536000);
+        assert_eq!(compoundings_per_year, 2);
+        assert_eq!(
+            Decimal::one()
+                + get_compounded_rate(market.liquidity_rate, compoundings_per_year).unwrap(),
+            market.liquidity_index
+        );
+        let compoundings_per_year = get_compoundings_per_year(&yearly_market, 1 + 31536000);
+        assert_eq!(compoundings_per_year, 1);
+        assert_eq!(
+            Decimal::one()
+                + get_compounded_rate(yearly_market.liquidity_rate, compoundings_per_year).unwrap(),
+            yearly_market.liquidity_index
+        );
+
+        // Indexes are not updated (nor interests compounded) more than once per block
+        apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(market.interests_compounding_count, 2);
+
+        // No compounding history
+        assert_eq!(get_compoundings_per_year(&market, 1), 0);
+        assert_eq!(
+            get_compounded_rate(Decimal::percent(10), 0).unwrap(),
+            Decimal::percent(10)
+        );
+    }
+
     #[test]
     fn test_liquidity_and_debt_rounding() {
         let start = Uint128::from(100_000_000_000_u128);
@@ -374,4 +925,547 @@ mod tests {
         );
         assert_eq!(Uint128::from(100_000_000_001_u128), back_to_underlying_debt);
     }
+
+    #[test]
+    fn test_borrow_index_below_liquidity_index() {
+        // Pathological model paying depositors more than what borrowers pay
+        let mut market = Market {
+            liquidity_index: Decimal::one(),
+            borrow_index: Decimal::one(),
+            liquidity_rate: Decimal::from_ratio(2_u128, 10_u128),
+            borrow_rate: Decimal::from_ratio(1_u128, 10_u128),
+            indexes_last_updated: 1,
+            ..Default::default()
+        };
+
+        let env = mock_env_at_block_time(1 + 31536000);
+        let error_res = apply_accumulated_interests(
+            &env,
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error_res,
+            ContractError::BorrowIndexBelowLiquidityIndex {
+                borrow_index: Decimal::from_ratio(11_u128, 10_u128),
+                liquidity_index: Decimal::from_ratio(12_u128, 10_u128),
+            }
+        );
+
+        // Accrual succeeds when borrowers pay at least what depositors earn
+        let mut market = Market {
+            liquidity_index: Decimal::one(),
+            borrow_index: Decimal::one(),
+            liquidity_rate: Decimal::from_ratio(1_u128, 10_u128),
+            borrow_rate: Decimal::from_ratio(1_u128, 10_u128),
+            indexes_last_updated: 1,
+            ..Default::default()
+        };
+        apply_accumulated_interests(
+            &env,
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(market.borrow_index, Decimal::from_ratio(11_u128, 10_u128));
+        assert_eq!(
+            market.liquidity_index,
+            Decimal::from_ratio(11_u128, 10_u128)
+        );
+    }
+
+    #[test]
+    fn test_liquidity_index_above_borrow_index_with_stable_debt() {
+        // Stable debt paying more than the variable rate lets depositors earn more than what the
+        // borrow index grows by
+        let mut market = Market {
+            liquidity_index: Decimal::one(),
+            borrow_index: Decimal::one(),
+            borrow_rate: Decimal::permille(25),
+            liquidity_rate: Decimal::permille(71),
+            debt_total_scaled: Uint128::new(100_000) * SCALING_FACTOR,
+            stable_debt_total: Uint128::new(100_000),
+            average_stable_borrow_rate: Decimal::percent(71),
+            total_deposits_scaled: Uint128::new(1_000_000) * SCALING_FACTOR,
+            indexes_last_updated: 1,
+            ..Default::default()
+        };
+        apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            market.borrow_index,
+            Decimal::from_ratio(1025_u128, 1000_u128)
+        );
+        assert_eq!(
+            market.liquidity_index,
+            Decimal::from_ratio(1071_u128, 1000_u128)
+        );
+
+        // Accruals keep working after the stable debt is paid off
+        decrease_stable_debt(&mut market, Uint128::new(171_000), Decimal::percent(71)).unwrap();
+        market.liquidity_rate = Decimal::zero();
+        market.borrow_rate = Decimal::zero();
+        apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 2 * 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+
+        // Depositors earning more than the overall borrow rate still trips the check
+        market.borrow_rate = Decimal::percent(10);
+        market.liquidity_rate = Decimal::percent(20);
+        let error_res = apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 3 * 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error_res,
+            ContractError::BorrowIndexBelowLiquidityIndex {
+                borrow_index: Decimal::from_ratio(11781_u128, 10000_u128),
+                liquidity_index: Decimal::from_ratio(12852_u128, 10000_u128),
+            }
+        );
+    }
+
+    #[test]
+    fn test_min_liquidity_rate() {
+        // 100_000 of debt over 10_000_000 of liquidity and debt: 1% utilization
+        let mut deps = mock_dependencies(&[coin(9_900_000, "uusd")]);
+        let mut market = Market {
+            reserve_factor: Decimal::percent(20),
+            debt_total_scaled: Uint128::new(100_000) * SCALING_FACTOR,
+            indexes_last_updated: 1,
+            interest_rate_model: InterestRateModel::Linear {
+                params: LinearInterestRateModelParams {
+                    optimal_utilization_rate: Decimal::one(),
+                    base: Decimal::zero(),
+                    slope_1: Decimal::one(),
+                    slope_2: Decimal::zero(),
+                },
+            },
+            min_liquidity_rate: Decimal::percent(5),
+            ..Default::default()
+        };
+        let env = mock_env_at_block_time(1);
+
+        // Model liquidity rate is 1% (borrow rate) * 1% (utilization) * 80% = 0.008%. The
+        // subsidy is capped at the reserve income, 1% * 1% * 20% = 0.002%
+        update_interest_rates(
+            &deps.as_mut(),
+            &env,
+            &mut market,
+            Uint128::zero(),
+            "uusd",
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(market.borrow_rate, Decimal::percent(1));
+        assert_eq!(
+            market.liquidity_rate,
+            Decimal::from_ratio(1u128, 10_000u128)
+        );
+        assert_eq!(
+            market.liquidity_rate_subsidy,
+            Decimal::from_ratio(2u128, 100_000u128)
+        );
+
+        // Floor binds below the cap
+        market.min_liquidity_rate = Decimal::from_ratio(9u128, 100_000u128);
+        update_interest_rates(
+            &deps.as_mut(),
+            &env,
+            &mut market,
+            Uint128::zero(),
+            "uusd",
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            market.liquidity_rate,
+            Decimal::from_ratio(9u128, 100_000u128)
+        );
+        assert_eq!(
+            market.liquidity_rate_subsidy,
+            Decimal::from_ratio(1u128, 100_000u128)
+        );
+
+        // No subsidy when the model rate is above the floor
+        market.min_liquidity_rate = Decimal::from_ratio(5u128, 100_000u128);
+        update_interest_rates(
+            &deps.as_mut(),
+            &env,
+            &mut market,
+            Uint128::zero(),
+            "uusd",
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            market.liquidity_rate,
+            Decimal::from_ratio(8u128, 100_000u128)
+        );
+        assert_eq!(market.liquidity_rate_subsidy, Decimal::zero());
+    }
+
+    #[test]
+    fn test_liquidity_subsidy_accrual() {
+        // 1% of model liquidity rate raised to 5% by the floor
+        let mut market = Market {
+            ma_token_address: Addr::unchecked("ma_token"),
+            borrow_rate: Decimal::percent(10),
+            liquidity_rate: Decimal::percent(5),
+            liquidity_rate_subsidy: Decimal::percent(4),
+            reserve_factor: Decimal::percent(20),
+            debt_total_scaled: Uint128::new(100_000) * SCALING_FACTOR,
+            total_deposits_scaled: Uint128::new(1_000_000) * SCALING_FACTOR,
+            indexes_last_updated: 1,
+            ..Default::default()
+        };
+
+        // 10_000 of borrow interests give 2_000 of protocol rewards, not enough to cover the
+        // 40_000 subsidy earned by depositors
+        let res = apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            market.liquidity_index,
+            Decimal::from_ratio(105u128, 100u128)
+        );
+        assert_eq!(market.total_liquidity_subsidy, Uint128::new(40_000));
+        assert_eq!(market.pending_liquidity_subsidy, Uint128::new(38_000));
+
+        // Once the floor does not bind anymore, protocol rewards keep paying the pending subsidy
+        market.liquidity_rate = Decimal::percent(1);
+        market.liquidity_rate_subsidy = Decimal::zero();
+        let res = apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 2 * 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        // 100_000 * 1.1 * 10% * 20% = 2_200 of protocol rewards
+        assert!(res.messages.is_empty());
+        assert_eq!(market.total_liquidity_subsidy, Uint128::new(40_000));
+        assert_eq!(market.pending_liquidity_subsidy, Uint128::new(35_800));
+
+        // Protocol rewards are minted again once the pending subsidy is paid off
+        market.pending_liquidity_subsidy = Uint128::new(1_000);
+        let res = apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 3 * 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(market.pending_liquidity_subsidy, Uint128::zero());
+    }
+
+    #[test]
+    fn test_stable_debt_accrual() {
+        // 100_000 of variable debt at 10% and 100_000 of stable debt at 20%
+        let mut market = Market {
+            ma_token_address: Addr::unchecked("ma_token"),
+            borrow_rate: Decimal::percent(10),
+            liquidity_rate: Decimal::percent(3),
+            reserve_factor: Decimal::percent(10),
+            debt_total_scaled: Uint128::new(100_000) * SCALING_FACTOR,
+            total_deposits_scaled: Uint128::new(1_000_000) * SCALING_FACTOR,
+            stable_debt_total: Uint128::new(100_000),
+            average_stable_borrow_rate: Decimal::percent(20),
+            indexes_last_updated: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            get_overall_borrow_rate(&market, 1).unwrap(),
+            Decimal::percent(15)
+        );
+
+        // stable debt accrues at the average stable rate and its interests give protocol
+        // rewards: (10_000 + 20_000) * 10% = 3_000
+        let total_deposits_scaled_before = market.total_deposits_scaled;
+        let res = apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(market.stable_debt_total, Uint128::new(120_000));
+        assert_eq!(
+            market.total_deposits_scaled - total_deposits_scaled_before,
+            get_scaled_liquidity_amount(Uint128::new(3_000), &market, 1 + 31536000).unwrap()
+        );
+
+        // total debt includes both variable and stable debts
+        assert_eq!(
+            get_underlying_market_debt_total(&market, 1 + 31536000).unwrap(),
+            Uint128::new(230_000)
+        );
+
+        // average stable rate follows the stable debt amounts and rates
+        increase_stable_debt(&mut market, Uint128::new(120_000), Decimal::percent(10)).unwrap();
+        assert_eq!(market.stable_debt_total, Uint128::new(240_000));
+        assert_eq!(market.average_stable_borrow_rate, Decimal::percent(15));
+        decrease_stable_debt(&mut market, Uint128::new(120_000), Decimal::percent(20)).unwrap();
+        assert_eq!(market.stable_debt_total, Uint128::new(120_000));
+        assert_eq!(market.average_stable_borrow_rate, Decimal::percent(10));
+        decrease_stable_debt(&mut market, Uint128::new(120_001), Decimal::percent(10)).unwrap();
+        assert_eq!(market.stable_debt_total, Uint128::zero());
+        assert_eq!(market.average_stable_borrow_rate, Decimal::zero());
+    }
+
+    #[test]
+    fn test_borrow_rate_discount_paid_from_protocol_rewards() {
+        let mut market = Market {
+            ma_token_address: Addr::unchecked("ma_token"),
+            borrow_rate: Decimal::percent(10),
+            reserve_factor: Decimal::percent(20),
+            debt_total_scaled: Uint128::new(100_000) * SCALING_FACTOR,
+            total_deposits_scaled: Uint128::new(1_000_000) * SCALING_FACTOR,
+            pending_borrow_rate_discount: Uint128::new(2_500),
+            indexes_last_updated: 1,
+            ..Default::default()
+        };
+
+        // 10_000 of borrow interests give 2_000 of protocol rewards, all used for the discount
+        let res = apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(market.pending_borrow_rate_discount, Uint128::new(500));
+
+        // 100_000 * 1.1 * 10% * 20% = 2_200 of protocol rewards, 1_700 are minted
+        let total_deposits_scaled_before = market.total_deposits_scaled;
+        let res = apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 2 * 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(market.pending_borrow_rate_discount, Uint128::zero());
+        assert_eq!(
+            market.total_deposits_scaled - total_deposits_scaled_before,
+            get_scaled_liquidity_amount(Uint128::new(1_700), &market, 1 + 2 * 31536000).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_liquidity_rate_reserve_factor_identity() {
+        let total_liquidity = 10_000_000u128;
+        let reserve_factors = [0u64, 1, 10, 20, 50, 99, 100];
+
+        for utilization_percent in (0u128..=100).step_by(5) {
+            for reserve_factor_percent in reserve_factors {
+                // deprecated markets in their grace period keep the borrow rate frozen
+                for deprecated_at in [None, Some(1)] {
+                    let debt = total_liquidity * utilization_percent / 100;
+                    let mut deps = mock_dependencies(&[coin(total_liquidity - debt, "uusd")]);
+                    let reserve_factor = Decimal::percent(reserve_factor_percent);
+                    let mut market = Market {
+                        ma_token_address: Addr::unchecked("mauusd"),
+                        reserve_factor,
+                        borrow_index: Decimal::one(),
+                        liquidity_index: Decimal::one(),
+                        borrow_rate: Decimal::percent(30),
+                        liquidity_rate: Decimal::percent(25),
+                        debt_total_scaled: Uint128::new(debt) * SCALING_FACTOR,
+                        total_deposits_scaled: Uint128::new(total_liquidity) * SCALING_FACTOR,
+                        indexes_last_updated: 1,
+                        interest_rate_model: InterestRateModel::Linear {
+                            params: LinearInterestRateModelParams {
+                                optimal_utilization_rate: Decimal::percent(80),
+                                base: Decimal::percent(2),
+                                slope_1: Decimal::percent(20),
+                                slope_2: Decimal::one(),
+                            },
+                        },
+                        deprecated_at,
+                        deprecation_grace_period_seconds: 1_000,
+                        ..Default::default()
+                    };
+
+                    update_interest_rates(
+                        &deps.as_mut(),
+                        &mock_env_at_block_time(1),
+                        &mut market,
+                        Uint128::zero(),
+                        "uusd",
+                        Response::new(),
+                    )
+                    .unwrap();
+
+                    // liquidity_rate = borrow_rate * utilization * (1 - reserve_factor)
+                    let utilization_rate = Decimal::from_ratio(debt, total_liquidity);
+                    let expected_liquidity_rate = market
+                        .borrow_rate
+                        .checked_mul(utilization_rate)
+                        .unwrap()
+                        .checked_mul(Decimal::one() - reserve_factor)
+                        .unwrap();
+                    assert_eq!(
+                        market.liquidity_rate, expected_liquidity_rate,
+                        "utilization: {}%, reserve factor: {}%, deprecated: {:?}",
+                        utilization_percent, reserve_factor_percent, deprecated_at
+                    );
+
+                    // After a year, depositors and the protocol together earn what borrowers
+                    // pay, never more
+                    let deposits_scaled_before = market.total_deposits_scaled;
+                    let block_time = 1 + 31536000;
+                    apply_accumulated_interests(
+                        &mock_env_at_block_time(block_time),
+                        Addr::unchecked("protocol_rewards_collector"),
+                        &mut market,
+                        Response::new(),
+                    )
+                    .unwrap();
+
+                    let borrow_interest =
+                        get_underlying_debt_amount(market.debt_total_scaled, &market, block_time)
+                            .unwrap()
+                            - Uint128::new(debt);
+                    let deposit_interest = get_underlying_liquidity_amount(
+                        deposits_scaled_before,
+                        &market,
+                        block_time,
+                    )
+                    .unwrap()
+                        - Uint128::new(total_liquidity);
+                    let protocol_rewards = get_underlying_liquidity_amount(
+                        market.total_deposits_scaled - deposits_scaled_before,
+                        &market,
+                        block_time,
+                    )
+                    .unwrap();
+
+                    let earned = deposit_interest + protocol_rewards;
+                    assert!(
+                        earned <= borrow_interest && borrow_interest - earned <= Uint128::new(2),
+                        "utilization: {}%, reserve factor: {}%, deprecated: {:?}, \
+                        borrow interest: {}, earned: {}",
+                        utilization_percent,
+                        reserve_factor_percent,
+                        deprecated_at,
+                        borrow_interest,
+                        earned
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deprecation_grace_period_does_not_overflow() {
+        let mut deps = mock_dependencies(&[coin(1_000_000, "uusd")]);
+        let mut market = Market {
+            ma_token_address: Addr::unchecked("mauusd"),
+            borrow_index: Decimal::one(),
+            liquidity_index: Decimal::one(),
+            borrow_rate: Decimal::percent(30),
+            debt_total_scaled: Uint128::new(500_000) * SCALING_FACTOR,
+            total_deposits_scaled: Uint128::new(1_500_000) * SCALING_FACTOR,
+            indexes_last_updated: 100,
+            deprecated_at: Some(100),
+            deprecation_grace_period_seconds: u64::MAX,
+            deprecation_penalty_borrow_rate: Decimal::percent(90),
+            ..Default::default()
+        };
+
+        update_interest_rates(
+            &deps.as_mut(),
+            &mock_env_at_block_time(200),
+            &mut market,
+            Uint128::zero(),
+            "uusd",
+            Response::new(),
+        )
+        .unwrap();
+
+        // still in the grace period, so the borrow rate stays frozen
+        assert_eq!(market.borrow_rate, Decimal::percent(30));
+    }
+
+    #[test]
+    fn test_socialize_loss() {
+        let mut market = Market {
+            liquidity_index: Decimal::from_ratio(2u128, 1u128),
+            borrow_index: Decimal::from_ratio(2u128, 1u128),
+            borrow_rate: Decimal::percent(10),
+            liquidity_rate: Decimal::percent(10),
+            total_deposits_scaled: Uint128::new(1_000) * SCALING_FACTOR,
+            indexes_last_updated: 1,
+            ..Default::default()
+        };
+        let balance_scaled = Uint128::new(100) * SCALING_FACTOR;
+
+        // loss can't wipe out all the deposits
+        let error_res = socialize_loss(&mut market, Uint128::new(2_000)).unwrap_err();
+        assert_eq!(
+            error_res,
+            ContractError::LossExceedsDeposits {
+                loss_amount: Uint128::new(2_000),
+                deposits_amount: Uint128::new(2_000),
+            }
+        );
+
+        // 500 out of 2_000 deposited are lost: every depositor loses 25%
+        socialize_loss(&mut market, Uint128::new(500)).unwrap();
+        assert_eq!(market.liquidity_index, Decimal::from_ratio(15u128, 10u128));
+        assert_eq!(
+            get_underlying_liquidity_amount(market.total_deposits_scaled, &market, 1).unwrap(),
+            Uint128::new(1_500)
+        );
+        assert_eq!(
+            get_underlying_liquidity_amount(balance_scaled, &market, 1).unwrap(),
+            Uint128::new(150)
+        );
+
+        // scaling amounts keeps working with the lower index
+        let scaled_amount = get_scaled_liquidity_amount(Uint128::new(150), &market, 1).unwrap();
+        assert_eq!(scaled_amount, balance_scaled);
+
+        // and interests keep accruing from the lower index
+        apply_accumulated_interests(
+            &mock_env_at_block_time(1 + 31536000),
+            Addr::unchecked("protocol_rewards_collector"),
+            &mut market,
+            Response::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            market.liquidity_index,
+            Decimal::from_ratio(165u128, 100u128)
+        );
+        assert_eq!(
+            get_underlying_liquidity_amount(balance_scaled, &market, 1 + 31536000).unwrap(),
+            Uint128::new(165)
+        );
+    }
 }
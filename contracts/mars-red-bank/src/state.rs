@@ -1,17 +1,37 @@
 use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::{Item, Map, U32Key};
 
-use crate::{Config, Debt, GlobalState, Market, User};
+use crate::interest_rate_models::InterestRateModelParams;
+use crate::{
+    AutoRepayAuthorization, Config, Debt, EModeCategory, GlobalState, LiquidationRoute, Market,
+    PriceSnapshot, User,
+};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const GLOBAL_STATE: Item<GlobalState> = Item::new("global_state");
+pub const CONFIG_LAST_UPDATE_TIME: Item<u64> = Item::new("config_last_update_time");
 
 pub const USERS: Map<&Addr, User> = Map::new("users");
 
 pub const MARKETS: Map<&[u8], Market> = Map::new("markets");
 pub const MARKET_REFERENCES_BY_INDEX: Map<U32Key, Vec<u8>> = Map::new("market_refs_by_index");
 pub const MARKET_REFERENCES_BY_MA_TOKEN: Map<&Addr, Vec<u8>> = Map::new("market_refs_by_ma_token");
+pub const MARKET_LAST_UPDATE_TIMES: Map<&[u8], u64> = Map::new("market_last_update_times");
+pub const LIQUIDATION_ROUTES: Map<&[u8], LiquidationRoute> = Map::new("liquidation_routes");
 
 pub const DEBTS: Map<(&[u8], &Addr), Debt> = Map::new("debts");
 pub const UNCOLLATERALIZED_LOAN_LIMITS: Map<(&[u8], &Addr), Uint128> =
     Map::new("uncollateralized_loan_limits");
+pub const USER_LAST_BORROW_TIMES: Map<(&[u8], &Addr), u64> = Map::new("user_last_borrow_times");
+pub const AUTO_REPAY_AUTHORIZATIONS: Map<&Addr, AutoRepayAuthorization> =
+    Map::new("auto_repay_authorizations");
+
+pub const PRICE_SNAPSHOTS: Map<&[u8], PriceSnapshot> = Map::new("price_snapshots");
+
+pub const EMODE_CATEGORIES: Map<U32Key, EModeCategory> = Map::new("emode_categories");
+
+pub const INTEREST_RATE_STRATEGIES: Map<U32Key, InterestRateModelParams> =
+    Map::new("interest_rate_strategies");
+pub const USER_EMODE_CATEGORIES: Map<&Addr, u32> = Map::new("user_emode_categories");
+/// Number of users opted in to each e-mode category
+pub const EMODE_CATEGORY_USER_COUNTS: Map<U32Key, u64> = Map::new("emode_category_user_counts");
@@ -21,7 +21,9 @@ use mars_core::address_provider::{self, MarsContract};
 use crate::error::ContractError;
 use crate::msg::{CreateOrUpdateConfig, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg};
 use crate::state::{CLAIMS, CONFIG, GLOBAL_STATE, SLASH_EVENTS};
-use crate::{Claim, ClaimResponse, Config, GlobalState, SlashEvent};
+use crate::{
+    Claim, ClaimResponse, Config, GlobalState, PendingClaim, PendingClaimsResponse, SlashEvent,
+};
 
 // INSTANTIATE
 
@@ -452,6 +454,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::XMarsPerMars {} => to_binary(&query_xmars_per_mars(deps, env)?),
         QueryMsg::MarsPerXMars {} => to_binary(&query_mars_per_xmars(deps, env)?),
         QueryMsg::Claim { user_address } => to_binary(&query_claim(deps, env, user_address)?),
+        QueryMsg::PendingClaims { user_address } => {
+            to_binary(&query_pending_claims(deps, env, user_address)?)
+        }
     }
 }
 
@@ -499,6 +504,28 @@ fn query_claim(deps: Deps, _env: Env, user_address_unchecked: String) -> StdResu
     }
 }
 
+fn query_pending_claims(
+    deps: Deps,
+    env: Env,
+    user_address_unchecked: String,
+) -> StdResult<PendingClaimsResponse> {
+    let user_address = deps.api.addr_validate(&user_address_unchecked)?;
+    let block_time = env.block.time.seconds();
+
+    // Only one open claim per address is allowed so there is at most one pending claim
+    let mut claims = vec![];
+    if let Some(mut claim) = CLAIMS.may_load(deps.storage, &user_address)? {
+        apply_slash_events_to_claim(deps.storage, &mut claim)?;
+        claims.push(PendingClaim {
+            cooldown_end_timestamp: claim.cooldown_end_timestamp,
+            seconds_until_unlocked: claim.cooldown_end_timestamp.saturating_sub(block_time),
+            amount: claim.amount,
+        });
+    }
+
+    Ok(PendingClaimsResponse { claims })
+}
+
 // HELPERS
 
 /// Gets mars and xmars token addresses from address provider and returns them in a tuple.
@@ -1250,6 +1277,114 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_query_pending_claims() {
+        let mut deps = th_setup(&[]);
+        let claimer_address = Addr::unchecked("claimer");
+        GLOBAL_STATE
+            .save(
+                &mut deps.storage,
+                &GlobalState {
+                    total_mars_for_claimers: Uint128::new(5_000_000_000),
+                },
+            )
+            .unwrap();
+
+        // No claims
+        let res = query_pending_claims(
+            deps.as_ref(),
+            mock_env_at_block_time(1),
+            "claimer".to_string(),
+        )
+        .unwrap();
+        assert_eq!(res.claims, vec![]);
+
+        let first_claim = Claim {
+            amount: Uint128::new(2_000_000_000),
+            created_at_block: 100_u64,
+            cooldown_end_timestamp: 1_000_000_u64,
+        };
+        CLAIMS
+            .save(&mut deps.storage, &claimer_address, &first_claim)
+            .unwrap();
+
+        // Countdown goes down to zero when the cooldown ends
+        let res = query_pending_claims(
+            deps.as_ref(),
+            mock_env_at_block_time(999_000),
+            "claimer".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.claims,
+            vec![PendingClaim {
+                cooldown_end_timestamp: 1_000_000,
+                seconds_until_unlocked: 1_000,
+                amount: Uint128::new(2_000_000_000),
+            }]
+        );
+        let res = query_pending_claims(
+            deps.as_ref(),
+            mock_env_at_block_time(1_000_500),
+            "claimer".to_string(),
+        )
+        .unwrap();
+        assert_eq!(res.claims[0].seconds_until_unlocked, 0);
+
+        // Claiming the first claim allows unstaking again with a later cooldown end
+        let info = mock_info("claimer", &[]);
+        let env = mock_env_at_block_time(1_000_500);
+        execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::Claim { recipient: None },
+        )
+        .unwrap();
+
+        let res = query_pending_claims(
+            deps.as_ref(),
+            mock_env_at_block_time(1_000_500),
+            "claimer".to_string(),
+        )
+        .unwrap();
+        assert_eq!(res.claims, vec![]);
+
+        let second_claim = Claim {
+            amount: Uint128::new(3_000_000_000),
+            created_at_block: 200_u64,
+            cooldown_end_timestamp: 1_002_000_u64,
+        };
+        CLAIMS
+            .save(&mut deps.storage, &claimer_address, &second_claim)
+            .unwrap();
+        SLASH_EVENTS
+            .save(
+                &mut deps.storage,
+                U64Key::new(250),
+                &SlashEvent {
+                    slash_percentage: Decimal::percent(10),
+                },
+            )
+            .unwrap();
+
+        // Slash events are applied to the amount
+        let res = query_pending_claims(
+            deps.as_ref(),
+            mock_env_at_block_time(1_001_000),
+            "claimer".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.claims,
+            vec![PendingClaim {
+                cooldown_end_timestamp: 1_002_000,
+                seconds_until_unlocked: 1_000,
+                amount: Uint128::new(2_700_000_000),
+            }]
+        );
+    }
+
     #[test]
     fn test_claim_with_slash() {
         let mut deps = th_setup(&[]);
@@ -4,7 +4,7 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use mars_treasury::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use mars_treasury::Config;
+use mars_treasury::{Config, PendingConfigUpdate, SpendingLimitResponse};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -17,4 +17,6 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
 
     export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(SpendingLimitResponse), &out_dir);
+    export_schema(&schema_for!(PendingConfigUpdate), &out_dir);
 }
@@ -1,15 +1,21 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    from_binary, to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdResult, Storage, Uint128, WasmMsg,
 };
+use cw20::Cw20ExecuteMsg;
 
+use mars_core::asset::Asset;
 use mars_core::error::MarsError;
 use mars_core::helpers::option_string_to_addr;
 
+use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::CONFIG;
-use crate::Config;
+use crate::state::{CONFIG, PENDING_CONFIG_UPDATE, PENDING_SPENDING_LIMITS, SPENDING_LIMITS};
+use crate::{
+    Config, PendingConfigUpdate, PendingSpendingLimit, SpendingLimit, SpendingLimitResponse,
+};
 
 // INIT
 
@@ -19,10 +25,14 @@ pub fn instantiate(
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
+    validate_spending_limit_epoch_blocks(msg.spending_limit_epoch_blocks)?;
+
     // initialize Config
     let config = Config {
         owner: deps.api.addr_validate(&msg.owner)?,
+        spending_limit_epoch_blocks: msg.spending_limit_epoch_blocks,
+        spending_limit_update_delay_blocks: msg.spending_limit_update_delay_blocks,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -38,27 +48,51 @@ pub fn execute(
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
-) -> Result<Response, MarsError> {
+) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::ExecuteCosmosMsg(cosmos_msg) => {
             handle_execute_cosmos_msg(deps, env, info, cosmos_msg)
         }
 
-        ExecuteMsg::UpdateConfig { owner } => execute_update_config(deps, env, info, owner),
+        ExecuteMsg::UpdateConfig {
+            owner,
+            spending_limit_epoch_blocks,
+            spending_limit_update_delay_blocks,
+        } => execute_update_config(
+            deps,
+            env,
+            info,
+            owner,
+            spending_limit_epoch_blocks,
+            spending_limit_update_delay_blocks,
+        ),
+
+        ExecuteMsg::ApplyConfigUpdate {} => execute_apply_config_update(deps, env),
+
+        ExecuteMsg::SetSpendingLimit { asset, limit } => {
+            execute_set_spending_limit(deps, env, info, asset, limit)
+        }
+
+        ExecuteMsg::ApplySpendingLimit { asset } => execute_apply_spending_limit(deps, env, asset),
     }
 }
 
 /// Execute Cosmos message
 pub fn handle_execute_cosmos_msg(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: CosmosMsg,
-) -> Result<Response, MarsError> {
+) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     if info.sender != config.owner {
-        return Err(MarsError::Unauthorized {});
+        return Err(MarsError::Unauthorized {}.into());
+    }
+
+    let epoch = env.block.height / config.spending_limit_epoch_blocks;
+    for (asset, amount) in get_spent_assets(&msg)? {
+        spend(deps.storage, epoch, &asset, amount)?;
     }
 
     let res = Response::new()
@@ -67,33 +101,300 @@ pub fn handle_execute_cosmos_msg(
     Ok(res)
 }
 
+/// Update config. The owner is updated right away while spending limit epoch changes and update
+/// delay decreases (which could be used to loosen spending limits) are scheduled to be applied
+/// once the current update delay has passed
 pub fn execute_update_config(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     owner: Option<String>,
-) -> Result<Response, MarsError> {
+    spending_limit_epoch_blocks: Option<u64>,
+    spending_limit_update_delay_blocks: Option<u64>,
+) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
 
     if info.sender != config.owner {
-        return Err(MarsError::Unauthorized {});
+        return Err(MarsError::Unauthorized {}.into());
     };
 
     config.owner = option_string_to_addr(deps.api, owner, config.owner)?;
 
+    let new_spending_limit_epoch_blocks =
+        spending_limit_epoch_blocks.unwrap_or(config.spending_limit_epoch_blocks);
+    let new_spending_limit_update_delay_blocks =
+        spending_limit_update_delay_blocks.unwrap_or(config.spending_limit_update_delay_blocks);
+
+    validate_spending_limit_epoch_blocks(new_spending_limit_epoch_blocks)?;
+
+    let mut response = Response::new().add_attribute("action", "update_config");
+
+    if new_spending_limit_epoch_blocks == config.spending_limit_epoch_blocks
+        && new_spending_limit_update_delay_blocks >= config.spending_limit_update_delay_blocks
+    {
+        config.spending_limit_update_delay_blocks = new_spending_limit_update_delay_blocks;
+        PENDING_CONFIG_UPDATE.remove(deps.storage);
+    } else {
+        let applicable_at_block = env.block.height + config.spending_limit_update_delay_blocks;
+        PENDING_CONFIG_UPDATE.save(
+            deps.storage,
+            &PendingConfigUpdate {
+                spending_limit_epoch_blocks: new_spending_limit_epoch_blocks,
+                spending_limit_update_delay_blocks: new_spending_limit_update_delay_blocks,
+                applicable_at_block,
+            },
+        )?;
+        response = response.add_attribute("applicable_at_block", applicable_at_block.to_string());
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
-    let response = Response::new().add_attribute("action", "update_config");
+    Ok(response)
+}
+
+/// Apply the scheduled config update once its delay has passed (callable by anyone)
+pub fn execute_apply_config_update(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending_update = PENDING_CONFIG_UPDATE
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingUpdate {})?;
+    if env.block.height < pending_update.applicable_at_block {
+        return Err(ContractError::UpdateDelayNotPassed {
+            applicable_at_block: pending_update.applicable_at_block,
+        });
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.spending_limit_epoch_blocks = pending_update.spending_limit_epoch_blocks;
+    config.spending_limit_update_delay_blocks = pending_update.spending_limit_update_delay_blocks;
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_CONFIG_UPDATE.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "apply_config_update"))
+}
+
+/// Set (or remove if `limit` is None) the spending limit of an asset. Tighter limits are applied
+/// right away and cancel any pending loosening, while raising or removing a limit is scheduled to
+/// be applied once the update delay has passed
+pub fn execute_set_spending_limit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: Asset,
+    limit: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(MarsError::Unauthorized {}.into());
+    };
+
+    let asset_reference = asset.get_reference();
+    let current_limit = SPENDING_LIMITS
+        .may_load(deps.storage, asset_reference.as_slice())?
+        .map(|spending_limit| spending_limit.limit);
+
+    let is_tightening = match (current_limit, limit) {
+        (_, None) => current_limit.is_none(),
+        (None, Some(_)) => true,
+        (Some(current_limit), Some(limit)) => limit <= current_limit,
+    };
+
+    let mut response = Response::new()
+        .add_attribute("action", "set_spending_limit")
+        .add_attribute("asset", asset.get_canonical_label())
+        .add_attribute(
+            "limit",
+            limit.map_or_else(|| "none".to_string(), |limit| limit.to_string()),
+        );
+
+    if is_tightening {
+        save_spending_limit(deps.storage, &config, &env, &asset_reference, limit)?;
+        PENDING_SPENDING_LIMITS.remove(deps.storage, asset_reference.as_slice());
+    } else {
+        let applicable_at_block = env.block.height + config.spending_limit_update_delay_blocks;
+        PENDING_SPENDING_LIMITS.save(
+            deps.storage,
+            asset_reference.as_slice(),
+            &PendingSpendingLimit {
+                limit,
+                applicable_at_block,
+            },
+        )?;
+        response = response.add_attribute("applicable_at_block", applicable_at_block.to_string());
+    }
 
     Ok(response)
 }
 
+/// Apply the scheduled loosening of an asset spending limit once its delay has passed (callable
+/// by anyone)
+pub fn execute_apply_spending_limit(
+    deps: DepsMut,
+    env: Env,
+    asset: Asset,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let asset_reference = asset.get_reference();
+    let pending_update = PENDING_SPENDING_LIMITS
+        .may_load(deps.storage, asset_reference.as_slice())?
+        .ok_or(ContractError::NoPendingUpdate {})?;
+    if env.block.height < pending_update.applicable_at_block {
+        return Err(ContractError::UpdateDelayNotPassed {
+            applicable_at_block: pending_update.applicable_at_block,
+        });
+    }
+
+    save_spending_limit(
+        deps.storage,
+        &config,
+        &env,
+        &asset_reference,
+        pending_update.limit,
+    )?;
+    PENDING_SPENDING_LIMITS.remove(deps.storage, asset_reference.as_slice());
+
+    Ok(Response::new()
+        .add_attribute("action", "apply_spending_limit")
+        .add_attribute("asset", asset.get_canonical_label()))
+}
+
+/// Save (or remove if `limit` is None) the spending limit of an asset. Amount spent on the
+/// current epoch is kept
+fn save_spending_limit(
+    storage: &mut dyn Storage,
+    config: &Config,
+    env: &Env,
+    asset_reference: &[u8],
+    limit: Option<Uint128>,
+) -> StdResult<()> {
+    match limit {
+        Some(limit) => {
+            let epoch = env.block.height / config.spending_limit_epoch_blocks;
+            let spent = SPENDING_LIMITS
+                .may_load(storage, asset_reference)?
+                .map(|spending_limit| spending_limit.spent_at(epoch))
+                .unwrap_or_else(Uint128::zero);
+            SPENDING_LIMITS.save(
+                storage,
+                asset_reference,
+                &SpendingLimit {
+                    limit,
+                    spent,
+                    epoch,
+                },
+            )?;
+        }
+        None => SPENDING_LIMITS.remove(storage, asset_reference),
+    }
+    Ok(())
+}
+
+fn validate_spending_limit_epoch_blocks(spending_limit_epoch_blocks: u64) -> Result<(), MarsError> {
+    if spending_limit_epoch_blocks == 0 {
+        return Err(MarsError::InvalidParam {
+            param_name: "spending_limit_epoch_blocks".to_string(),
+            invalid_value: spending_limit_epoch_blocks.to_string(),
+            predicate: "> 0".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the assets (and amounts) sent out of the treasury by a message: coins sent with
+/// bank messages or attached to wasm messages, and cw20 tokens transferred, sent, burned or
+/// approved to be spent by others. Any other message could move funds without being tracked
+/// (e.g: withdrawing from another contract to a third party, or migrating a contract the
+/// treasury is admin of) so it is rejected
+fn get_spent_assets(msg: &CosmosMsg) -> Result<Vec<(Asset, Uint128)>, ContractError> {
+    let coins_to_assets = |coins: &[Coin]| -> Vec<(Asset, Uint128)> {
+        coins
+            .iter()
+            .map(|coin| {
+                (
+                    Asset::Native {
+                        denom: coin.denom.clone(),
+                    },
+                    coin.amount,
+                )
+            })
+            .collect()
+    };
+
+    let spent_assets = match msg {
+        CosmosMsg::Bank(BankMsg::Send { amount, .. })
+        | CosmosMsg::Bank(BankMsg::Burn { amount }) => coins_to_assets(amount),
+
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds,
+        }) => {
+            let mut spent_assets = coins_to_assets(funds);
+            let cw20_amount = match from_binary(msg) {
+                Ok(Cw20ExecuteMsg::Transfer { amount, .. })
+                | Ok(Cw20ExecuteMsg::Send { amount, .. })
+                | Ok(Cw20ExecuteMsg::Burn { amount })
+                | Ok(Cw20ExecuteMsg::IncreaseAllowance { amount, .. }) => amount,
+                Ok(Cw20ExecuteMsg::DecreaseAllowance { .. }) => Uint128::zero(),
+                _ => return Err(ContractError::UnsupportedCosmosMsg {}),
+            };
+            spent_assets.push((
+                Asset::Cw20 {
+                    contract_addr: contract_addr.clone(),
+                },
+                cw20_amount,
+            ));
+            spent_assets
+        }
+
+        CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. }) => coins_to_assets(funds),
+
+        _ => return Err(ContractError::UnsupportedCosmosMsg {}),
+    };
+
+    Ok(spent_assets)
+}
+
+/// Adds the amount to the spent amount of the asset on the epoch, failing if it goes over the
+/// asset spending limit. Assets without a spending limit are not tracked
+fn spend(
+    storage: &mut dyn Storage,
+    epoch: u64,
+    asset: &Asset,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let (asset_label, asset_reference, _) = asset.get_attributes();
+    let mut spending_limit = match SPENDING_LIMITS.may_load(storage, asset_reference.as_slice())? {
+        Some(spending_limit) => spending_limit,
+        None => return Ok(()),
+    };
+
+    let spent = spending_limit.spent_at(epoch);
+    let remaining = spending_limit.limit.saturating_sub(spent);
+    if amount > remaining {
+        return Err(ContractError::SpendingLimitExceeded {
+            asset: asset_label,
+            remaining,
+            amount,
+        });
+    }
+
+    spending_limit.spent = spent + amount;
+    spending_limit.epoch = epoch;
+    SPENDING_LIMITS.save(storage, asset_reference.as_slice(), &spending_limit)?;
+
+    Ok(())
+}
+
 // QUERIES
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::SpendingLimit { asset } => to_binary(&query_spending_limit(deps, env, asset)?),
+        QueryMsg::PendingConfigUpdate {} => to_binary(&query_pending_config_update(deps)?),
     }
 }
 
@@ -102,6 +403,27 @@ fn query_config(deps: Deps) -> StdResult<Config> {
     Ok(config)
 }
 
+fn query_spending_limit(deps: Deps, env: Env, asset: Asset) -> StdResult<SpendingLimitResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let epoch = env.block.height / config.spending_limit_epoch_blocks;
+    let asset_reference = asset.get_reference();
+    let spending_limit = SPENDING_LIMITS.load(deps.storage, asset_reference.as_slice())?;
+    let spent = spending_limit.spent_at(epoch);
+
+    Ok(SpendingLimitResponse {
+        limit: spending_limit.limit,
+        spent,
+        remaining: spending_limit.limit.saturating_sub(spent),
+        epoch,
+        pending_update: PENDING_SPENDING_LIMITS
+            .may_load(deps.storage, asset_reference.as_slice())?,
+    })
+}
+
+fn query_pending_config_update(deps: Deps) -> StdResult<Option<PendingConfigUpdate>> {
+    PENDING_CONFIG_UPDATE.may_load(deps.storage)
+}
+
 // TESTS
 
 #[cfg(test)]
@@ -109,7 +431,7 @@ mod tests {
     use super::*;
 
     use cosmwasm_std::testing::mock_info;
-    use cosmwasm_std::{attr, Addr, BankMsg, Coin, CosmosMsg, SubMsg, Uint128};
+    use cosmwasm_std::{attr, Addr, Empty, SubMsg};
 
     use mars_core::testing::{mock_dependencies, mock_env, MockEnvParams};
 
@@ -119,6 +441,8 @@ mod tests {
 
         let msg = InstantiateMsg {
             owner: String::from("owner"),
+            spending_limit_epoch_blocks: 100,
+            spending_limit_update_delay_blocks: 50,
         };
         let info = mock_info("owner", &[]);
 
@@ -129,6 +453,55 @@ mod tests {
 
         let config = CONFIG.load(&deps.storage).unwrap();
         assert_eq!(Addr::unchecked("owner"), config.owner);
+        assert_eq!(100, config.spending_limit_epoch_blocks);
+    }
+
+    #[test]
+    fn test_invalid_spending_limit_epoch_blocks() {
+        let mut deps = mock_dependencies(&[]);
+        let expected_error: ContractError = MarsError::InvalidParam {
+            param_name: "spending_limit_epoch_blocks".to_string(),
+            invalid_value: "0".to_string(),
+            predicate: "> 0".to_string(),
+        }
+        .into();
+
+        let msg = InstantiateMsg {
+            owner: String::from("owner"),
+            spending_limit_epoch_blocks: 0,
+            spending_limit_update_delay_blocks: 50,
+        };
+        let info = mock_info("owner", &[]);
+        let error_res = instantiate(
+            deps.as_mut(),
+            mock_env(MockEnvParams::default()),
+            info.clone(),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(error_res, expected_error);
+
+        let msg = InstantiateMsg {
+            owner: String::from("owner"),
+            spending_limit_epoch_blocks: 100,
+            spending_limit_update_delay_blocks: 50,
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(MockEnvParams::default()),
+            info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig {
+            owner: None,
+            spending_limit_epoch_blocks: Some(0),
+            spending_limit_update_delay_blocks: None,
+        };
+        let error_res =
+            execute(deps.as_mut(), mock_env(MockEnvParams::default()), info, msg).unwrap_err();
+        assert_eq!(error_res, expected_error);
     }
 
     #[test]
@@ -140,6 +513,8 @@ mod tests {
         // *
         let msg = InstantiateMsg {
             owner: String::from("owner"),
+            spending_limit_epoch_blocks: 100,
+            spending_limit_update_delay_blocks: 50,
         };
         let info = mock_info("owner", &[]);
         let _res =
@@ -148,27 +523,112 @@ mod tests {
         // *
         // non owner is not authorized
         // *
-        let msg = ExecuteMsg::UpdateConfig { owner: None };
+        let msg = ExecuteMsg::UpdateConfig {
+            owner: None,
+            spending_limit_epoch_blocks: None,
+            spending_limit_update_delay_blocks: None,
+        };
         let info = mock_info("somebody", &[]);
         let error_res =
             execute(deps.as_mut(), mock_env(MockEnvParams::default()), info, msg).unwrap_err();
-        assert_eq!(error_res, MarsError::Unauthorized {});
+        assert_eq!(error_res, MarsError::Unauthorized {}.into());
 
         // *
         // update config with all new params
         // *
         let msg = ExecuteMsg::UpdateConfig {
             owner: Some(String::from("new_owner")),
+            spending_limit_epoch_blocks: Some(200),
+            spending_limit_update_delay_blocks: Some(10),
         };
         let info = mock_info("owner", &[]);
+        let env_at = |block_height: u64| {
+            mock_env(MockEnvParams {
+                block_height,
+                ..Default::default()
+            })
+        };
         // we can just call .unwrap() to assert this was a success
-        let res = execute(deps.as_mut(), mock_env(MockEnvParams::default()), info, msg).unwrap();
+        let res = execute(deps.as_mut(), env_at(1_000), info, msg).unwrap();
         assert_eq!(0, res.messages.len());
 
-        // Read config from state
+        // owner is updated right away while the spending limit params wait for the delay
         let new_config = CONFIG.load(&deps.storage).unwrap();
-
         assert_eq!(new_config.owner, Addr::unchecked("new_owner"));
+        assert_eq!(new_config.spending_limit_epoch_blocks, 100);
+        assert_eq!(new_config.spending_limit_update_delay_blocks, 50);
+
+        let pending_update: Option<PendingConfigUpdate> = from_binary(
+            &query(
+                deps.as_ref(),
+                env_at(1_000),
+                QueryMsg::PendingConfigUpdate {},
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            pending_update,
+            Some(PendingConfigUpdate {
+                spending_limit_epoch_blocks: 200,
+                spending_limit_update_delay_blocks: 10,
+                applicable_at_block: 1_050,
+            })
+        );
+
+        let error_res = execute(
+            deps.as_mut(),
+            env_at(1_049),
+            mock_info("somebody", &[]),
+            ExecuteMsg::ApplyConfigUpdate {},
+        )
+        .unwrap_err();
+        assert_eq!(
+            error_res,
+            ContractError::UpdateDelayNotPassed {
+                applicable_at_block: 1_050
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            env_at(1_050),
+            mock_info("somebody", &[]),
+            ExecuteMsg::ApplyConfigUpdate {},
+        )
+        .unwrap();
+        let new_config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(new_config.spending_limit_epoch_blocks, 200);
+        assert_eq!(new_config.spending_limit_update_delay_blocks, 10);
+        assert!(PENDING_CONFIG_UPDATE
+            .may_load(&deps.storage)
+            .unwrap()
+            .is_none());
+
+        let error_res = execute(
+            deps.as_mut(),
+            env_at(1_050),
+            mock_info("somebody", &[]),
+            ExecuteMsg::ApplyConfigUpdate {},
+        )
+        .unwrap_err();
+        assert_eq!(error_res, ContractError::NoPendingUpdate {});
+
+        // increasing the delay is applied right away
+        let msg = ExecuteMsg::UpdateConfig {
+            owner: None,
+            spending_limit_epoch_blocks: None,
+            spending_limit_update_delay_blocks: Some(20),
+        };
+        execute(
+            deps.as_mut(),
+            env_at(1_100),
+            mock_info("new_owner", &[]),
+            msg,
+        )
+        .unwrap();
+        let new_config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(new_config.spending_limit_update_delay_blocks, 20);
     }
 
     #[test]
@@ -177,6 +637,8 @@ mod tests {
 
         let msg = InstantiateMsg {
             owner: String::from("owner"),
+            spending_limit_epoch_blocks: 100,
+            spending_limit_update_delay_blocks: 50,
         };
         let info = mock_info("owner", &[]);
         let _res =
@@ -203,15 +665,371 @@ mod tests {
             msg.clone(),
         )
         .unwrap_err();
-        assert_eq!(error_res, MarsError::Unauthorized {});
+        assert_eq!(error_res, MarsError::Unauthorized {}.into());
 
         // *
         // can execute Cosmos msg
         // *
         let info = mock_info("owner", &[]);
-        let res = execute(deps.as_mut(), mock_env(MockEnvParams::default()), info, msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(MockEnvParams::default()),
+            info.clone(),
+            msg,
+        )
+        .unwrap();
         assert_eq!(res.messages, vec![SubMsg::new(cosmos_msg)]);
         let expected_attr = vec![attr("action", "execute_cosmos_msg")];
         assert_eq!(res.attributes, expected_attr);
+
+        // *
+        // msgs that could move funds without being tracked are rejected
+        // *
+        let untracked_msgs = vec![
+            CosmosMsg::Custom(Empty {}),
+            // red bank withdraw to a third party
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "red_bank".to_string(),
+                msg: Binary::from(
+                    br#"{"withdraw":{"asset":{"native":{"denom":"uusd"}},"amount":null,"recipient":"somebody"}}"#,
+                ),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr: "some_contract".to_string(),
+                new_code_id: 2,
+                msg: Binary::from(br#"{}"#),
+            }),
+            CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+                contract_addr: "some_contract".to_string(),
+                admin: "somebody".to_string(),
+            }),
+        ];
+        for untracked_msg in untracked_msgs {
+            let error_res = execute(
+                deps.as_mut(),
+                mock_env(MockEnvParams::default()),
+                info.clone(),
+                ExecuteMsg::ExecuteCosmosMsg(untracked_msg),
+            )
+            .unwrap_err();
+            assert_eq!(error_res, ContractError::UnsupportedCosmosMsg {});
+        }
+    }
+
+    #[test]
+    fn test_spending_limits() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            owner: String::from("owner"),
+            spending_limit_epoch_blocks: 100,
+            spending_limit_update_delay_blocks: 50,
+        };
+        let info = mock_info("owner", &[]);
+        instantiate(
+            deps.as_mut(),
+            mock_env(MockEnvParams::default()),
+            info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        let uluna = Asset::Native {
+            denom: "uluna".to_string(),
+        };
+        let token = Asset::Cw20 {
+            contract_addr: "token".to_string(),
+        };
+
+        // non owner is not authorized to set spending limits
+        let msg = ExecuteMsg::SetSpendingLimit {
+            asset: uluna.clone(),
+            limit: Some(Uint128::new(1_000)),
+        };
+        let error_res = execute(
+            deps.as_mut(),
+            mock_env(MockEnvParams::default()),
+            mock_info("somebody", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(error_res, MarsError::Unauthorized {}.into());
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(MockEnvParams::default()),
+            info.clone(),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "set_spending_limit"),
+                attr("asset", "native:uluna"),
+                attr("limit", "1000"),
+            ]
+        );
+        let msg = ExecuteMsg::SetSpendingLimit {
+            asset: token.clone(),
+            limit: Some(Uint128::new(500)),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(MockEnvParams::default()),
+            info.clone(),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1], attr("asset", "cw20:token"));
+
+        let bank_send = |amount: u128| {
+            ExecuteMsg::ExecuteCosmosMsg(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "destination".to_string(),
+                amount: vec![Coin {
+                    denom: "uluna".to_string(),
+                    amount: Uint128::new(amount),
+                }],
+            }))
+        };
+        let cw20_transfer = |amount: u128| {
+            ExecuteMsg::ExecuteCosmosMsg(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "destination".to_string(),
+                    amount: Uint128::new(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            }))
+        };
+        let env_at = |block_height: u64| {
+            mock_env(MockEnvParams {
+                block_height,
+                ..Default::default()
+            })
+        };
+
+        // spend part of the limits
+        execute(deps.as_mut(), env_at(150), info.clone(), bank_send(600)).unwrap();
+        execute(deps.as_mut(), env_at(160), info.clone(), cw20_transfer(500)).unwrap();
+
+        let res: SpendingLimitResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env_at(170),
+                QueryMsg::SpendingLimit {
+                    asset: uluna.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            SpendingLimitResponse {
+                limit: Uint128::new(1_000),
+                spent: Uint128::new(600),
+                remaining: Uint128::new(400),
+                epoch: 1,
+                pending_update: None,
+            }
+        );
+
+        // spending over the remaining allowance fails
+        let error_res =
+            execute(deps.as_mut(), env_at(180), info.clone(), bank_send(401)).unwrap_err();
+        assert_eq!(
+            error_res,
+            ContractError::SpendingLimitExceeded {
+                asset: "uluna".to_string(),
+                remaining: Uint128::new(400),
+                amount: Uint128::new(401),
+            }
+        );
+        let error_res =
+            execute(deps.as_mut(), env_at(180), info.clone(), cw20_transfer(1)).unwrap_err();
+        assert_eq!(
+            error_res,
+            ContractError::SpendingLimitExceeded {
+                asset: "token".to_string(),
+                remaining: Uint128::zero(),
+                amount: Uint128::new(1),
+            }
+        );
+
+        // coins attached to wasm messages also count towards the limit
+        let msg = ExecuteMsg::ExecuteCosmosMsg(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "other_token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "destination".to_string(),
+                amount: Uint128::zero(),
+            })
+            .unwrap(),
+            funds: vec![Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(500),
+            }],
+        }));
+        let error_res = execute(deps.as_mut(), env_at(180), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            error_res,
+            ContractError::SpendingLimitExceeded {
+                asset: "uluna".to_string(),
+                remaining: Uint128::new(400),
+                amount: Uint128::new(500),
+            }
+        );
+
+        // exhaust the limit
+        execute(deps.as_mut(), env_at(199), info.clone(), bank_send(400)).unwrap();
+        execute(deps.as_mut(), env_at(199), info.clone(), bank_send(1)).unwrap_err();
+
+        // assets without a limit are not restricted
+        let msg = ExecuteMsg::ExecuteCosmosMsg(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "destination".to_string(),
+            amount: vec![Coin {
+                denom: "uusd".to_string(),
+                amount: Uint128::new(1_000_000),
+            }],
+        }));
+        execute(deps.as_mut(), env_at(199), info.clone(), msg).unwrap();
+
+        // limits are reset after the epoch boundary
+        let res: SpendingLimitResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env_at(200),
+                QueryMsg::SpendingLimit {
+                    asset: uluna.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            SpendingLimitResponse {
+                limit: Uint128::new(1_000),
+                spent: Uint128::zero(),
+                remaining: Uint128::new(1_000),
+                epoch: 2,
+                pending_update: None,
+            }
+        );
+        execute(deps.as_mut(), env_at(200), info.clone(), bank_send(1_000)).unwrap();
+        execute(deps.as_mut(), env_at(200), info.clone(), cw20_transfer(500)).unwrap();
+
+        // raising the limit has to wait for the update delay
+        let msg = ExecuteMsg::SetSpendingLimit {
+            asset: uluna.clone(),
+            limit: Some(Uint128::new(2_000)),
+        };
+        let res = execute(deps.as_mut(), env_at(201), info.clone(), msg).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "set_spending_limit"),
+                attr("asset", "native:uluna"),
+                attr("limit", "2000"),
+                attr("applicable_at_block", "251"),
+            ]
+        );
+        execute(deps.as_mut(), env_at(201), info.clone(), bank_send(1)).unwrap_err();
+        let error_res = execute(
+            deps.as_mut(),
+            env_at(250),
+            mock_info("somebody", &[]),
+            ExecuteMsg::ApplySpendingLimit {
+                asset: uluna.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            error_res,
+            ContractError::UpdateDelayNotPassed {
+                applicable_at_block: 251
+            }
+        );
+
+        // tightening the limit is applied right away and cancels the pending loosening
+        let msg = ExecuteMsg::SetSpendingLimit {
+            asset: uluna.clone(),
+            limit: Some(Uint128::new(800)),
+        };
+        execute(deps.as_mut(), env_at(210), info.clone(), msg).unwrap();
+        let res: SpendingLimitResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env_at(210),
+                QueryMsg::SpendingLimit {
+                    asset: uluna.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            SpendingLimitResponse {
+                limit: Uint128::new(800),
+                spent: Uint128::new(1_000),
+                remaining: Uint128::zero(),
+                epoch: 2,
+                pending_update: None,
+            }
+        );
+        let error_res = execute(
+            deps.as_mut(),
+            env_at(260),
+            mock_info("somebody", &[]),
+            ExecuteMsg::ApplySpendingLimit {
+                asset: uluna.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error_res, ContractError::NoPendingUpdate {});
+
+        // removing the limit allows spending again once the delay has passed
+        let msg = ExecuteMsg::SetSpendingLimit {
+            asset: uluna.clone(),
+            limit: None,
+        };
+        execute(deps.as_mut(), env_at(301), info.clone(), msg).unwrap();
+        let res: SpendingLimitResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                env_at(301),
+                QueryMsg::SpendingLimit {
+                    asset: uluna.clone(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.pending_update,
+            Some(PendingSpendingLimit {
+                limit: None,
+                applicable_at_block: 351,
+            })
+        );
+        execute(
+            deps.as_mut(),
+            env_at(351),
+            mock_info("somebody", &[]),
+            ExecuteMsg::ApplySpendingLimit {
+                asset: uluna.clone(),
+            },
+        )
+        .unwrap();
+        execute(deps.as_mut(), env_at(351), info, bank_send(5_000)).unwrap();
+        query(
+            deps.as_ref(),
+            env_at(351),
+            QueryMsg::SpendingLimit { asset: uluna },
+        )
+        .unwrap_err();
     }
 }
@@ -1,6 +1,13 @@
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 
-use crate::Config;
+use crate::{Config, PendingConfigUpdate, PendingSpendingLimit, SpendingLimit};
 
 /// Stores config at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
+/// Spending limits by asset reference
+pub const SPENDING_LIMITS: Map<&[u8], SpendingLimit> = Map::new("spending_limits");
+/// Spending limit loosenings waiting for the update delay to pass, by asset reference
+pub const PENDING_SPENDING_LIMITS: Map<&[u8], PendingSpendingLimit> =
+    Map::new("pending_spending_limits");
+/// Spending limit config update waiting for the update delay to pass
+pub const PENDING_CONFIG_UPDATE: Item<PendingConfigUpdate> = Item::new("pending_config_update");
@@ -41,6 +41,15 @@ impl Asset {
             Asset::Cw20 { contract_addr } => contract_addr.to_lowercase().as_bytes().to_vec(),
         }
     }
+
+    /// Return label prefixed by the asset type (e.g: `native:uusd` or `cw20:terra1...`)
+    /// so native and cw20 assets can be told apart when emitted in attributes
+    pub fn get_canonical_label(&self) -> String {
+        match &self {
+            Asset::Native { denom } => format!("native:{}", denom),
+            Asset::Cw20 { contract_addr } => format!("cw20:{}", contract_addr.to_lowercase()),
+        }
+    }
 }
 
 // Cast astroport::asset::AssetInfo into mars_core::asset::Asset so that they can be compared
@@ -135,3 +144,21 @@ pub fn get_asset_balance(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_canonical_label() {
+        let native_asset = Asset::Native {
+            denom: "uluna".to_string(),
+        };
+        assert_eq!(native_asset.get_canonical_label(), "native:uluna");
+
+        let cw20_asset = Asset::Cw20 {
+            contract_addr: "Terra1CW20Address".to_string(),
+        };
+        assert_eq!(cw20_asset.get_canonical_label(), "cw20:terra1cw20address");
+    }
+}
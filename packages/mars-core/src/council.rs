@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, CosmosMsg, Uint128};
+use cosmwasm_std::{Addr, Binary, CosmosMsg, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +18,10 @@ pub struct Config {
     pub address_provider_address: Addr,
     /// Blocks during which a proposal is active since being submitted
     pub proposal_voting_period: u64,
+    /// Minimum blocks a proposal can override its voting period to
+    pub proposal_min_voting_period: u64,
+    /// Maximum blocks a proposal can override its voting period to
+    pub proposal_max_voting_period: u64,
     /// Blocks that need to pass since a proposal succeeds in order for it to be available to be
     /// executed
     pub proposal_effective_delay: u64,
@@ -30,33 +34,145 @@ pub struct Config {
     pub proposal_required_quorum: Decimal,
     /// % of for votes required in order to consider the proposal successful
     pub proposal_required_threshold: Decimal,
+    /// Blocks that need to pass since an address submitted a proposal for it to be able to
+    /// submit a new one (0 means no limit)
+    pub proposal_submission_cooldown: u64,
+    /// Blocks between a proposal submission and the start of its voting period, during which
+    /// the submitter can amend it (0 means voting starts on submission)
+    pub proposal_voting_delay: u64,
+    /// Max % of the total voting power a single voter can contribute to a proposal. Voting
+    /// power above it is not counted (1 means no cap)
+    pub proposal_max_voter_power_share: Decimal,
+    /// Quorum and threshold used instead of the default ones for proposals of some kinds
+    pub proposal_kind_requirements: Vec<ProposalKindRequirements>,
 }
 
 impl Config {
     pub fn validate(&self) -> Result<(), ContractError> {
-        decimal_param_le_one(&self.proposal_required_quorum, "proposal_required_quorum")?;
-
-        let minimum_proposal_required_threshold =
-            Decimal::percent(MINIMUM_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE);
-        let maximum_proposal_required_threshold =
-            Decimal::percent(MAXIMUM_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE);
+        if self.proposal_min_voting_period > self.proposal_max_voting_period {
+            return Err(MarsError::InvalidParam {
+                param_name: "proposal_min_voting_period".to_string(),
+                invalid_value: self.proposal_min_voting_period.to_string(),
+                predicate: format!("<= {}", self.proposal_max_voting_period),
+            }
+            .into());
+        }
 
-        if !(self.proposal_required_threshold >= minimum_proposal_required_threshold
-            && self.proposal_required_threshold <= maximum_proposal_required_threshold)
+        if !(self.proposal_voting_period >= self.proposal_min_voting_period
+            && self.proposal_voting_period <= self.proposal_max_voting_period)
         {
             return Err(MarsError::InvalidParam {
-                param_name: "proposal_required_threshold".to_string(),
-                invalid_value: self.proposal_required_threshold.to_string(),
+                param_name: "proposal_voting_period".to_string(),
+                invalid_value: self.proposal_voting_period.to_string(),
                 predicate: format!(
                     ">= {} and <= {}",
-                    minimum_proposal_required_threshold, maximum_proposal_required_threshold
+                    self.proposal_min_voting_period, self.proposal_max_voting_period
                 ),
             }
             .into());
         }
 
+        if self.proposal_max_voter_power_share.is_zero() {
+            return Err(MarsError::InvalidParam {
+                param_name: "proposal_max_voter_power_share".to_string(),
+                invalid_value: self.proposal_max_voter_power_share.to_string(),
+                predicate: "> 0".to_string(),
+            }
+            .into());
+        }
+        decimal_param_le_one(
+            &self.proposal_max_voter_power_share,
+            "proposal_max_voter_power_share",
+        )?;
+
+        validate_quorum_and_threshold(
+            &self.proposal_required_quorum,
+            "proposal_required_quorum",
+            &self.proposal_required_threshold,
+            "proposal_required_threshold",
+        )?;
+
+        for (i, requirements) in self.proposal_kind_requirements.iter().enumerate() {
+            if self.proposal_kind_requirements[..i]
+                .iter()
+                .any(|other| other.kind == requirements.kind)
+            {
+                return Err(MarsError::InvalidParam {
+                    param_name: "proposal_kind_requirements".to_string(),
+                    invalid_value: format!("{:?}", requirements.kind),
+                    predicate: "one entry per proposal kind".to_string(),
+                }
+                .into());
+            }
+
+            validate_quorum_and_threshold(
+                &requirements.required_quorum,
+                "required_quorum",
+                &requirements.required_threshold,
+                "required_threshold",
+            )?;
+        }
+
         Ok(())
     }
+
+    /// Quorum and threshold a proposal of the given kind needs to pass
+    pub fn required_quorum_and_threshold(&self, kind: &ProposalKind) -> (Decimal, Decimal) {
+        match self
+            .proposal_kind_requirements
+            .iter()
+            .find(|requirements| &requirements.kind == kind)
+        {
+            Some(requirements) => (
+                requirements.required_quorum,
+                requirements.required_threshold,
+            ),
+            None => (
+                self.proposal_required_quorum,
+                self.proposal_required_threshold,
+            ),
+        }
+    }
+}
+
+fn validate_quorum_and_threshold(
+    quorum: &Decimal,
+    quorum_param_name: &str,
+    threshold: &Decimal,
+    threshold_param_name: &str,
+) -> Result<(), ContractError> {
+    decimal_param_le_one(quorum, quorum_param_name)?;
+
+    let minimum_proposal_required_threshold =
+        Decimal::percent(MINIMUM_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE);
+    let maximum_proposal_required_threshold =
+        Decimal::percent(MAXIMUM_PROPOSAL_REQUIRED_THRESHOLD_PERCENTAGE);
+
+    if !(*threshold >= minimum_proposal_required_threshold
+        && *threshold <= maximum_proposal_required_threshold)
+    {
+        return Err(MarsError::InvalidParam {
+            param_name: threshold_param_name.to_string(),
+            invalid_value: threshold.to_string(),
+            predicate: format!(
+                ">= {} and <= {}",
+                minimum_proposal_required_threshold, maximum_proposal_required_threshold
+            ),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Quorum and threshold required for proposals of a given kind
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalKindRequirements {
+    pub kind: ProposalKind,
+    /// % of total voting power required to participate in the proposal
+    pub required_quorum: Decimal,
+    /// % of for votes required in order to consider the proposal successful
+    pub required_threshold: Decimal,
 }
 
 /// Global state
@@ -74,6 +190,8 @@ pub struct Proposal {
     pub submitter_address: Addr,
     /// Wether the proposal is Active, Passed, Rejected or Executed
     pub status: ProposalStatus,
+    /// Category of the proposal, used by UIs to group proposals
+    pub kind: ProposalKind,
     /// Number of for votes
     pub for_votes: Uint128,
     /// Number of against votes
@@ -119,6 +237,43 @@ pub enum ProposalStatus {
     Executed,
 }
 
+/// What happened to the deposit of a proposal
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalDepositStatus {
+    /// Proposal is being voted on, deposit is held by the council
+    Locked,
+    /// Quorum was met, deposit was returned to the submitter
+    Refunded,
+    /// Quorum was not met, deposit was sent to the staking contract
+    Slashed,
+}
+
+impl std::fmt::Display for ProposalDepositStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let display_str = match self {
+            ProposalDepositStatus::Locked => "locked",
+            ProposalDepositStatus::Refunded => "refunded",
+            ProposalDepositStatus::Slashed => "slashed",
+        };
+        write!(f, "{}", display_str)
+    }
+}
+
+/// Proposal Kind
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalKind {
+    /// Proposal updating protocol parameters
+    ParameterChange,
+    /// Proposal migrating one or more contracts
+    ContractUpgrade,
+    /// Proposal spending funds from the treasury
+    TreasurySpend,
+    /// Signaling proposal, has no messages to be executed
+    TextOnly,
+}
+
 /// Single vote made by an address
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ProposalVote {
@@ -153,6 +308,47 @@ pub struct ProposalsListResponse {
     pub proposal_list: Vec<Proposal>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalDepositsResponse {
+    /// List of proposal deposits (paginated by query)
+    pub deposits: Vec<ProposalDepositResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalDepositResponse {
+    pub proposal_id: u64,
+    pub status: ProposalDepositStatus,
+    /// MARS tokens deposited on the proposal submission
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalVotingPowerResponse {
+    pub height: u64,
+    /// Total supply of xMars token at the height
+    pub free: Uint128,
+    /// Total amount of Mars locked in the vesting contract at the height
+    pub locked: Uint128,
+    /// Sum of free and locked voting power, used as denominator for the proposals quorum
+    pub total: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalResultResponse {
+    pub proposal_id: u64,
+    /// % of total voting power that participated in the proposal
+    pub quorum: Decimal,
+    /// % of for votes over total votes
+    pub threshold: Decimal,
+    /// Whether quorum meets the required quorum
+    pub quorum_met: bool,
+    /// Whether threshold exceeds the required threshold
+    pub threshold_met: bool,
+    /// Status the proposal would have if voting ended now. Current status if proposal is
+    /// not active anymore
+    pub projected_status: ProposalStatus,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ProposalVotesResponse {
     pub proposal_id: u64,
@@ -166,6 +362,61 @@ pub struct ProposalVoteResponse {
     pub power: Uint128,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalMessagesSummaryResponse {
+    pub proposal_id: u64,
+    pub messages: Vec<ProposalMessageSummary>,
+}
+
+/// Human readable summary of a proposal message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalMessageSummary {
+    pub execution_order: u64,
+    /// Description of what the message does. None if the message type is not known
+    pub description: Option<String>,
+    /// Parameters set by the message
+    pub params: Vec<ProposalMessageParam>,
+    /// Base64 encoded message, only set if the message type is not known
+    pub raw: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalMessageParam {
+    pub name: String,
+    pub value: String,
+}
+
+/// Keys and values of the attributes emitted by the council handlers, so off-chain indexers
+/// can rely on the same names across all governance actions
+pub mod attributes {
+    pub const ACTION: &str = "action";
+
+    pub const ACTION_SUBMIT_PROPOSAL: &str = "submit_proposal";
+    pub const ACTION_AMEND_PROPOSAL: &str = "amend_proposal";
+    pub const ACTION_CAST_VOTE: &str = "cast_vote";
+    pub const ACTION_END_PROPOSAL: &str = "end_proposal";
+    pub const ACTION_EXECUTE_PROPOSAL: &str = "execute_proposal";
+    pub const ACTION_DELEGATE_VOTING_POWER: &str = "delegate_voting_power";
+    pub const ACTION_ACCEPT_DELEGATION: &str = "accept_delegation";
+    pub const ACTION_REVOKE_DELEGATION: &str = "revoke_delegation";
+    pub const ACTION_UPDATE_CONFIG: &str = "update_config";
+
+    pub const PROPOSAL_ID: &str = "proposal_id";
+    pub const SUBMITTER: &str = "submitter";
+    pub const PROPOSAL_START_HEIGHT: &str = "proposal_start_height";
+    pub const PROPOSAL_END_HEIGHT: &str = "proposal_end_height";
+    pub const PROPOSAL_RESULT: &str = "proposal_result";
+    pub const DEPOSIT: &str = "deposit";
+    pub const DEPOSIT_AMOUNT: &str = "deposit_amount";
+    pub const FOR_VOTES: &str = "for_votes";
+    pub const AGAINST_VOTES: &str = "against_votes";
+    pub const VOTER: &str = "voter";
+    pub const VOTE: &str = "vote";
+    pub const VOTING_POWER: &str = "voting_power";
+    pub const DELEGATOR: &str = "delegator";
+    pub const DELEGATE: &str = "delegate";
+}
+
 pub mod msg {
     use cosmwasm_std::Uint128;
     use cw20::Cw20ReceiveMsg;
@@ -174,7 +425,7 @@ pub mod msg {
 
     use crate::math::decimal::Decimal;
 
-    use super::{ProposalMessage, ProposalVoteOption};
+    use super::{ProposalKind, ProposalKindRequirements, ProposalMessage, ProposalVoteOption};
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     pub struct InstantiateMsg {
@@ -186,11 +437,22 @@ pub mod msg {
         pub address_provider_address: Option<String>,
 
         pub proposal_voting_period: Option<u64>,
+        pub proposal_min_voting_period: Option<u64>,
+        pub proposal_max_voting_period: Option<u64>,
         pub proposal_effective_delay: Option<u64>,
         pub proposal_expiration_period: Option<u64>,
         pub proposal_required_deposit: Option<Uint128>,
         pub proposal_required_quorum: Option<Decimal>,
         pub proposal_required_threshold: Option<Decimal>,
+        /// Defaults to 0 (no limit) when instantiating
+        pub proposal_submission_cooldown: Option<u64>,
+        /// Defaults to 0 (voting starts on submission) when instantiating
+        pub proposal_voting_delay: Option<u64>,
+        /// Defaults to 1 (no cap) when instantiating
+        pub proposal_max_voter_power_share: Option<Decimal>,
+        /// Replaces all the per kind requirements when set. Defaults to none (all kinds use
+        /// the required quorum and threshold) when instantiating
+        pub proposal_kind_requirements: Option<Vec<ProposalKindRequirements>>,
     }
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -211,8 +473,31 @@ pub mod msg {
         /// Execute a successful proposal
         ExecuteProposal { proposal_id: u64 },
 
+        /// Amend the title, description, link or messages of a proposal before its voting
+        /// period starts (only callable by the proposal submitter). Fields left as `None`
+        /// are kept unchanged
+        AmendProposal {
+            proposal_id: u64,
+            title: Option<String>,
+            description: Option<String>,
+            link: Option<String>,
+            messages: Option<Vec<ProposalMessage>>,
+        },
+
         /// Update config
         UpdateConfig { config: CreateOrUpdateConfig },
+
+        /// Request to delegate sender's voting power to another address. Once the delegate
+        /// accepts, it votes on behalf of the sender on proposals the sender has not voted on
+        /// yet. Replaces any previous delegation
+        DelegateVotingPower { delegate: String },
+
+        /// Accept the pending voting power delegation of a delegator to the sender. A delegate
+        /// can have up to 20 delegators
+        AcceptDelegation { delegator: String },
+
+        /// Revoke sender's voting power delegation, pending or accepted
+        RevokeDelegation {},
     }
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -224,7 +509,11 @@ pub mod msg {
             title: String,
             description: String,
             link: Option<String>,
+            kind: ProposalKind,
             messages: Option<Vec<ProposalMessage>>,
+            /// Voting period to use instead of the configured one (e.g: for urgent proposals).
+            /// Must be within the configured min and max voting periods
+            voting_period_override: Option<u64>,
         },
     }
 
@@ -239,11 +528,33 @@ pub mod msg {
         Proposal {
             proposal_id: u64,
         },
+        /// Get quorum and threshold of a proposal with the current votes and the status it
+        /// would have if voting ended now. Returns ProposalResultResponse
+        ProposalResult {
+            proposal_id: u64,
+        },
+        /// Get whether the deposit of each proposal is locked, was refunded or was slashed,
+        /// along with its amount. Returns ProposalDepositsResponse
+        ProposalDeposits {
+            start: Option<u64>,
+            limit: Option<u32>,
+        },
         ProposalVotes {
             proposal_id: u64,
             start_after: Option<String>,
             limit: Option<u32>,
         },
+        /// Get a human readable summary of each message of a proposal. Known messages (Red Bank
+        /// and council admin calls, contract migrations and bank sends) are decoded, the rest
+        /// are returned as raw base64. Returns ProposalMessagesSummaryResponse
+        ProposalMessagesSummary {
+            proposal_id: u64,
+        },
+        /// Get total voting power at a given height (current height if not provided).
+        /// Returns TotalVotingPowerResponse
+        TotalVotingPower {
+            height: Option<u64>,
+        },
     }
 }
 
@@ -264,8 +575,13 @@ pub mod error {
         #[error("Invalid Proposal: {error:?}")]
         InvalidProposal { error: String },
 
+        #[error("Address can't submit a new proposal until block {next_submission_height}")]
+        ProposalSubmissionCooldown { next_submission_height: u64 },
+
         #[error("Proposal is not active")]
         ProposalNotActive {},
+        #[error("Proposal can only be amended before its voting period starts")]
+        ProposalAmendmentNotAllowed {},
 
         #[error("User has already voted on this proposal")]
         VoteUserAlreadyVoted {},
@@ -273,6 +589,8 @@ pub mod error {
         VoteNoVotingPower { block: u64 },
         #[error("Voting period has ended")]
         VoteVotingPeriodEnded {},
+        #[error("Voting period has not started")]
+        VoteVotingPeriodNotStarted {},
 
         #[error("Voting period has not ended")]
         EndProposalVotingPeriodNotEnded {},
@@ -283,6 +601,13 @@ pub mod error {
         ExecuteProposalDelayNotEnded {},
         #[error("Proposal has expired")]
         ExecuteProposalExpired {},
+
+        #[error("Cannot delegate voting power to self")]
+        DelegateToSelf {},
+        #[error("No voting power delegation found")]
+        DelegationNotFound {},
+        #[error("Delegate cannot have more than {max_delegators} delegators")]
+        DelegateMaxDelegatorsReached { max_delegators: usize },
     }
 
     impl ContractError {
@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    to_binary, Addr, Api, QuerierWrapper, QueryRequest, StdError, StdResult, Uint128, WasmQuery,
+    to_binary, Addr, Api, CanonicalAddr, QuerierWrapper, QueryRequest, StdError, StdResult,
+    Uint128, WasmQuery,
 };
 
 use crate::{error::MarsError, math::decimal::Decimal};
@@ -77,6 +78,19 @@ pub fn option_string_to_addr(
     }
 }
 
+/// Converts a validated address into its canonical form (e.g: to store it or compare it with
+/// canonical addresses)
+pub fn addr_to_canonical(api: &dyn Api, addr: &Addr) -> StdResult<CanonicalAddr> {
+    api.addr_canonicalize(addr.as_str())
+}
+
+/// Converts a canonical address into a human readable address, validating the result so it
+/// can be compared with the senders of messages
+pub fn canonical_to_addr(api: &dyn Api, canonical_addr: &CanonicalAddr) -> StdResult<Addr> {
+    let addr = api.addr_humanize(canonical_addr)?;
+    api.addr_validate(addr.as_str())
+}
+
 pub fn decimal_param_le_one(param_value: &Decimal, param_name: &str) -> Result<(), MarsError> {
     if !param_value.le(&Decimal::one()) {
         Err(MarsError::InvalidParam {
@@ -92,3 +106,26 @@ pub fn decimal_param_le_one(param_value: &Decimal, param_name: &str) -> Result<(
 pub fn zero_address() -> Addr {
     Addr::unchecked("")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cosmwasm_std::testing::MockApi;
+
+    #[test]
+    fn test_canonical_addr_round_trip() {
+        let api = MockApi::default();
+        let addr = api.addr_validate("someaddress").unwrap();
+
+        let canonical_addr = addr_to_canonical(&api, &addr).unwrap();
+        assert_eq!(
+            canonical_addr,
+            api.addr_canonicalize("someaddress").unwrap()
+        );
+        assert_eq!(canonical_to_addr(&api, &canonical_addr).unwrap(), addr);
+
+        // Invalid canonical addresses can't be converted
+        canonical_to_addr(&api, &CanonicalAddr::from(vec![])).unwrap_err();
+    }
+}
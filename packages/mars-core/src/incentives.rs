@@ -3,8 +3,14 @@ use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{Addr, Uint128};
 
+use crate::error::MarsError;
+use crate::helpers::decimal_param_le_one;
 use crate::math::decimal::Decimal;
 
+/// Max number of decay periods an incentive schedule can span, so computing its emission
+/// over any time range stays bounded
+pub const MAX_DECAY_PERIODS: u64 = 1_000;
+
 /// Global configuration
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -23,6 +29,81 @@ pub struct AssetIncentive {
     pub index: Decimal,
     /// Last time (in seconds) index was updated
     pub last_updated: u64,
+    /// Time bounded emission replacing `emission_per_second` while set
+    pub schedule: Option<IncentiveSchedule>,
+}
+
+/// Response to UserUnclaimedRewardsBreakdown query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserUnclaimedRewardsBreakdownResponse {
+    /// Rewards recorded on previous balance changes, which are not tracked per maToken
+    pub recorded_rewards: Uint128,
+    /// Rewards accrued on each maToken since the user's last balance change
+    pub assets: Vec<AssetUnclaimedRewards>,
+    /// Total unclaimed rewards (same as the UserUnclaimedRewards query)
+    pub total_rewards: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetUnclaimedRewards {
+    /// maToken address associated with the incentives
+    pub ma_token_address: Addr,
+    /// Rewards accrued since the user's last balance change
+    pub rewards: Uint128,
+}
+
+/// Time bounded emission of MARS to the holders of a maToken
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IncentiveSchedule {
+    /// Time (in seconds) from which MARS is emitted
+    pub start_time: u64,
+    /// Time (in seconds) at which emissions stop
+    pub end_time: u64,
+    /// How much MARS per second is emitted at the start of the schedule
+    pub emission_per_second: Uint128,
+    /// Decrease of the emission per second over the schedule, if any
+    pub decay: Option<EmissionDecay>,
+}
+
+/// Emission decrease applied at the end of each period since the start of a schedule
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmissionDecay {
+    /// Portion of the emission per second removed at the end of each period
+    pub rate: Decimal,
+    /// Length (in seconds) of a period
+    pub period_seconds: u64,
+}
+
+impl IncentiveSchedule {
+    pub fn validate(&self) -> Result<(), MarsError> {
+        if self.end_time <= self.start_time {
+            return Err(MarsError::InvalidParam {
+                param_name: "end_time".to_string(),
+                invalid_value: self.end_time.to_string(),
+                predicate: format!("> {}", self.start_time),
+            });
+        }
+        if let Some(decay) = &self.decay {
+            decimal_param_le_one(&decay.rate, "decay_rate")?;
+            if decay.period_seconds == 0 {
+                return Err(MarsError::InvalidParam {
+                    param_name: "decay_period_seconds".to_string(),
+                    invalid_value: decay.period_seconds.to_string(),
+                    predicate: "> 0".to_string(),
+                });
+            }
+            let duration = self.end_time - self.start_time;
+            let min_period_seconds = duration.div_ceil(MAX_DECAY_PERIODS);
+            if decay.period_seconds < min_period_seconds {
+                return Err(MarsError::InvalidParam {
+                    param_name: "decay_period_seconds".to_string(),
+                    invalid_value: decay.period_seconds.to_string(),
+                    predicate: format!(">= {}", min_period_seconds),
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Response to AssetIncentive query
@@ -37,6 +118,8 @@ pub mod msg {
     use schemars::JsonSchema;
     use serde::{Deserialize, Serialize};
 
+    use super::IncentiveSchedule;
+
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     pub struct InstantiateMsg {
         /// Contract owner
@@ -57,6 +140,14 @@ pub mod msg {
             emission_per_second: Uint128,
         },
 
+        /// Set a time bounded emission schedule for an asset, replacing its emission per
+        /// second while set. A `None` schedule removes it (only callable by owner)
+        SetAssetIncentiveSchedule {
+            /// maToken address associated with the incentives
+            ma_token_address: String,
+            schedule: Option<IncentiveSchedule>,
+        },
+
         /// Handle balance change updating user and asset rewards.
         /// Sent from an external contract, triggered on user balance changes.
         /// Will return an empty response if no incentive is applied for the asset
@@ -72,7 +163,11 @@ pub mod msg {
 
         /// Claim rewards. MARS rewards accrued by the user will be staked into xMARS before
         /// being sent.
-        ClaimRewards {},
+        ClaimRewards {
+            /// maTokens to claim rewards on. Rewards already recorded for the user are always
+            /// included. If None, rewards are claimed across all maTokens (default: None)
+            ma_token_addresses: Option<Vec<String>>,
+        },
 
         /// Update contract config (only callable by owner)
         UpdateConfig {
@@ -95,5 +190,9 @@ pub mod msg {
 
         /// Query user current unclaimed rewards
         UserUnclaimedRewards { user_address: String },
+
+        /// Query user current unclaimed rewards itemized by maToken.
+        /// Returns UserUnclaimedRewardsBreakdownResponse
+        UserUnclaimedRewardsBreakdown { user_address: String },
     }
 }
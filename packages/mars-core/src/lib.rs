@@ -20,6 +20,7 @@ pub mod math;
 // Helpers
 pub mod error;
 pub mod helpers;
+pub mod storage;
 pub mod swapping;
 pub mod tax;
 
@@ -90,26 +90,48 @@ impl Decimal {
         Ok(Decimal(result))
     }
 
+    /// Raise 'self' to the power of 'exp' (exponentiation by squaring, each product being
+    /// truncated).
+    /// Function can return errors such as:
+    /// - OverflowError from multiplication,
+    /// - ConversionOverflowError during Uint256 to Uint128 conversion.
+    pub fn checked_pow(self, mut exp: u64) -> StdResult<Self> {
+        let mut base = self;
+        let mut result = Decimal::one();
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = result.checked_mul(base)?;
+            }
+            exp /= 2;
+            if exp > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        Ok(result)
+    }
+
     /// Divide Uint128 by Decimal.
     /// (Uint128 / numerator / denominator) is equal to (Uint128 * denominator / numerator).
+    /// Returns DivideByZeroError if 'b' is equal to 0.
     pub fn divide_uint128_by_decimal(a: Uint128, b: Decimal) -> StdResult<Uint128> {
         // (Uint128 / numerator / denominator) is equal to (Uint128 * denominator / numerator).
         let numerator_u256 = a.full_mul(b.denominator());
         let denominator_u256 = Uint256::from(b.numerator());
 
-        let result_u256 = numerator_u256 / denominator_u256;
+        let result_u256 = numerator_u256.checked_div(denominator_u256)?;
 
         let result = result_u256.try_into()?;
         Ok(result)
     }
 
     /// Divide Uint128 by Decimal, rounding up to the nearest integer.
+    /// Returns DivideByZeroError if 'b' is equal to 0.
     pub fn divide_uint128_by_decimal_and_ceil(a: Uint128, b: Decimal) -> StdResult<Uint128> {
         // (Uint128 / numerator / denominator) is equal to (Uint128 * denominator / numerator).
         let numerator_u256 = a.full_mul(b.denominator());
         let denominator_u256 = Uint256::from(b.numerator());
 
-        let mut result_u256 = numerator_u256 / denominator_u256;
+        let mut result_u256 = numerator_u256.checked_div(denominator_u256)?;
 
         if numerator_u256.checked_rem(denominator_u256)? > Uint256::zero() {
             result_u256 += Uint256::from(1_u32);
@@ -119,6 +141,19 @@ impl Decimal {
         Ok(result)
     }
 
+    /// Multiply Uint128 by Decimal, truncating the result.
+    /// Same as `a * b` but returns ConversionOverflowError instead of panicking
+    /// if the result doesn't fit in a Uint128.
+    pub fn multiply_uint128_by_decimal(a: Uint128, b: Decimal) -> StdResult<Uint128> {
+        let numerator_u256 = a.full_mul(b.numerator());
+        let denominator_u256 = Uint256::from(b.denominator());
+
+        let result_u256 = numerator_u256 / denominator_u256;
+
+        let result = result_u256.try_into()?;
+        Ok(result)
+    }
+
     /// Multiply Uint128 by Decimal, rounding up to the nearest integer.
     pub fn multiply_uint128_by_decimal_and_ceil(a: Uint128, b: Decimal) -> StdResult<Uint128> {
         let numerator_u256 = a.full_mul(b.numerator());
@@ -675,6 +710,19 @@ mod tests {
         assert_eq!(left * right, Uint128::new(0));
     }
 
+    #[test]
+    fn test_multiply_uint128_by_decimal() {
+        let a = Uint128::new(301);
+        let b = Decimal::percent(50);
+        let c = Decimal::multiply_uint128_by_decimal(a, b).unwrap();
+        assert_eq!(c, Uint128::new(150));
+
+        // Overflow
+        let a = Uint128::MAX;
+        let b = Decimal::from_ratio(2u128, 1u128);
+        Decimal::multiply_uint128_by_decimal(a, b).unwrap_err();
+    }
+
     #[test]
     fn decimal_uint128_division() {
         // a/b
@@ -748,6 +796,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn checked_decimal_pow() {
+        let a = Decimal::from_ratio(105u128, 100u128);
+        assert_eq!(a.checked_pow(0).unwrap(), Decimal::one());
+        assert_eq!(a.checked_pow(1).unwrap(), a);
+        assert_eq!(
+            a.checked_pow(2).unwrap(),
+            Decimal::from_str("1.1025").unwrap()
+        );
+        assert_eq!(
+            a.checked_pow(5).unwrap(),
+            Decimal::from_str("1.2762815625").unwrap()
+        );
+
+        let a = Decimal::from_ratio(1u128, 2u128);
+        assert_eq!(
+            a.checked_pow(10).unwrap(),
+            Decimal::from_str("0.0009765625").unwrap()
+        );
+
+        let a = Decimal::from_ratio(340282366920u128, 1u128);
+        a.checked_pow(2).unwrap_err();
+    }
+
     #[test]
     fn checked_decimal_division() {
         let a = Decimal::from_ratio(99988u128, 100u128);
@@ -825,6 +897,11 @@ mod tests {
         let c = Decimal::divide_uint128_by_decimal(a, b).unwrap();
         assert_eq!(c, Uint128::new(0));
 
+        // Division by zero
+        let a = Uint128::new(100);
+        let b = Decimal::zero();
+        Decimal::divide_uint128_by_decimal(a, b).unwrap_err();
+
         // Overflow
         let a = Uint128::MAX;
         let b = Decimal::from_ratio(1_u128, 10_u128);
@@ -881,6 +958,11 @@ mod tests {
         let c = Decimal::divide_uint128_by_decimal_and_ceil(a, b).unwrap();
         assert_eq!(c, Uint128::new(1));
 
+        // Division by zero
+        let a = Uint128::new(100);
+        let b = Decimal::zero();
+        Decimal::divide_uint128_by_decimal_and_ceil(a, b).unwrap_err();
+
         // Overflow
         let a = Uint128::MAX;
         let b = Decimal::from_ratio(1_u128, 10_u128);
@@ -1,6 +1,10 @@
 pub mod decimal;
 
-use cosmwasm_std::{StdResult, Uint128};
+use std::convert::TryInto;
+
+use cosmwasm_std::{Fraction, StdError, StdResult, Uint128, Uint256};
+
+use self::decimal::Decimal;
 
 pub fn uint128_checked_div_with_ceil(
     numerator: Uint128,
@@ -15,9 +19,96 @@ pub fn uint128_checked_div_with_ceil(
     Ok(result)
 }
 
+/// Returns 10^decimals
+fn decimals_factor(decimals: u8) -> StdResult<Uint256> {
+    10u128
+        .checked_pow(decimals.into())
+        .map(Uint256::from)
+        .ok_or_else(|| StdError::generic_err(format!("decimals too large: {}", decimals)))
+}
+
+/// Value in base units of the price asset of `amount` base units of an asset with
+/// `asset_decimals`, given its `price` (in whole price asset units with `price_decimals` per
+/// whole asset unit). Computed with full precision and truncated
+pub fn compute_asset_value(
+    amount: Uint128,
+    asset_decimals: u8,
+    price: Decimal,
+    price_decimals: u8,
+) -> StdResult<Uint128> {
+    // amount * price * 10^price_decimals / 10^asset_decimals
+    let numerator = amount
+        .full_mul(price.numerator())
+        .checked_mul(decimals_factor(price_decimals)?)?;
+    let denominator =
+        Uint256::from(price.denominator()).checked_mul(decimals_factor(asset_decimals)?)?;
+    Ok(numerator.checked_div(denominator)?.try_into()?)
+}
+
+/// Amount in base units of an asset with `asset_decimals` worth `value` base units of the
+/// price asset, given its `price` (in whole price asset units with `price_decimals` per whole
+/// asset unit). Inverse of `compute_asset_value`, truncated.
+/// Returns DivideByZeroError if `price` is zero
+pub fn compute_asset_amount(
+    value: Uint128,
+    asset_decimals: u8,
+    price: Decimal,
+    price_decimals: u8,
+) -> StdResult<Uint128> {
+    // value * 10^asset_decimals / (price * 10^price_decimals)
+    let numerator = value
+        .full_mul(price.denominator())
+        .checked_mul(decimals_factor(asset_decimals)?)?;
+    let denominator =
+        Uint256::from(price.numerator()).checked_mul(decimals_factor(price_decimals)?)?;
+    Ok(numerator.checked_div(denominator)?.try_into()?)
+}
+
+/// Price (in whole price asset units with `price_decimals` per whole asset unit) at which
+/// `amount` base units of an asset with `asset_decimals` are worth `value` base units of the
+/// price asset.
+/// Returns DivideByZeroError if `amount` is zero
+pub fn compute_asset_price(
+    amount: Uint128,
+    asset_decimals: u8,
+    value: Uint128,
+    price_decimals: u8,
+) -> StdResult<Decimal> {
+    // value * 10^asset_decimals / (amount * 10^price_decimals)
+    let numerator = value
+        .full_mul(Decimal::one().denominator())
+        .checked_mul(decimals_factor(asset_decimals)?)?;
+    let denominator = Uint256::from(amount).checked_mul(decimals_factor(price_decimals)?)?;
+    let price_numerator: Uint128 = numerator.checked_div(denominator)?.try_into()?;
+    Ok(Decimal::from_ratio(
+        price_numerator,
+        Decimal::one().denominator(),
+    ))
+}
+
+/// Returns a * b, truncated.
+/// Returns OverflowError if the result does not fit in a Decimal
+pub fn decimal_multiplication(a: Decimal, b: Decimal) -> StdResult<Decimal> {
+    a.checked_mul(b)
+}
+
+/// Returns a / b, truncated.
+/// Returns DivideByZeroError if `b` is zero and OverflowError if the result does not fit in a
+/// Decimal
+pub fn decimal_division(a: Decimal, b: Decimal) -> StdResult<Decimal> {
+    a.checked_div(b)
+}
+
+/// Returns 1 / decimal, truncated.
+/// Returns DivideByZeroError if `decimal` is zero
+pub fn reverse_decimal(decimal: Decimal) -> StdResult<Decimal> {
+    decimal_division(Decimal::one(), decimal)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::str::FromStr;
     #[test]
     fn test_uint128_checked_div_with_ceil() {
         let a = Uint128::new(120u128);
@@ -49,4 +140,127 @@ mod tests {
         let c = uint128_checked_div_with_ceil(a, b).unwrap();
         assert_eq!(c, Uint128::zero());
     }
+
+    #[test]
+    fn test_compute_asset_value() {
+        // (amount, asset_decimals, price, price_decimals, expected value)
+        let cases = [
+            // same decimals: value is amount times price
+            (2_000_000u128, 6u8, "1.5", 6u8, 3_000_000u128),
+            // 1 whole unit of an 8 decimals asset worth 30_000 whole units of a 6 decimals
+            (100_000_000, 8, "30000", 6, 30_000_000_000),
+            // 2.5 whole units of an 18 decimals asset worth 3_000.25 whole units each
+            (2_500_000_000_000_000_000, 18, "3000.25", 6, 7_500_625_000),
+            // 1 base unit of an 18 decimals asset is worth less than one base unit
+            (1, 18, "3000", 6, 0),
+            // 6 decimals asset priced in an 18 decimals asset
+            (1_000_000, 6, "0.000000000000000001", 18, 1),
+            // asset without decimals
+            (3, 0, "1.1", 6, 3_300_000),
+        ];
+        for (amount, asset_decimals, price, price_decimals, expected_value) in cases {
+            let price = Decimal::from_str(price).unwrap();
+            let value =
+                compute_asset_value(Uint128::new(amount), asset_decimals, price, price_decimals)
+                    .unwrap();
+            assert_eq!(value, Uint128::new(expected_value));
+        }
+
+        // 10^39 doesn't fit in a u128
+        compute_asset_value(Uint128::new(1), 39, Decimal::one(), 6).unwrap_err();
+    }
+
+    #[test]
+    fn test_compute_asset_amount() {
+        // (value, asset_decimals, price, price_decimals, expected amount)
+        let cases = [
+            (3_000_000u128, 6u8, "1.5", 6u8, 2_000_000u128),
+            (30_000_000_000, 8, "30000", 6, 100_000_000),
+            (7_500_625_000, 18, "3000.25", 6, 2_500_000_000_000_000_000),
+            // truncated to the asset base unit
+            (1, 6, "3", 6, 0),
+            (1, 18, "3000", 6, 333_333_333),
+            (3_300_000, 0, "1.1", 6, 3),
+        ];
+        for (value, asset_decimals, price, price_decimals, expected_amount) in cases {
+            let price = Decimal::from_str(price).unwrap();
+            let amount =
+                compute_asset_amount(Uint128::new(value), asset_decimals, price, price_decimals)
+                    .unwrap();
+            assert_eq!(amount, Uint128::new(expected_amount));
+        }
+
+        compute_asset_amount(Uint128::new(1), 6, Decimal::zero(), 6).unwrap_err();
+    }
+
+    #[test]
+    fn test_compute_asset_price() {
+        // (amount, asset_decimals, value, price_decimals, expected price)
+        let cases = [
+            (2_000_000u128, 6u8, 3_000_000u128, 6u8, "1.5"),
+            (100_000_000, 8, 30_000_000_000, 6, "30000"),
+            (2_500_000_000_000_000_000, 18, 7_500_625_000, 6, "3000.25"),
+            (3, 0, 3_300_000, 6, "1.1"),
+        ];
+        for (amount, asset_decimals, value, price_decimals, expected_price) in cases {
+            let price = compute_asset_price(
+                Uint128::new(amount),
+                asset_decimals,
+                Uint128::new(value),
+                price_decimals,
+            )
+            .unwrap();
+            assert_eq!(price, Decimal::from_str(expected_price).unwrap());
+        }
+
+        compute_asset_price(Uint128::zero(), 6, Uint128::new(1), 6).unwrap_err();
+    }
+
+    #[test]
+    fn test_decimal_multiplication() {
+        let a = Decimal::from_str("1.5").unwrap();
+        let b = Decimal::from_str("0.2").unwrap();
+        assert_eq!(
+            decimal_multiplication(a, b).unwrap(),
+            Decimal::from_str("0.3").unwrap()
+        );
+        assert_eq!(
+            decimal_multiplication(a, Decimal::zero()).unwrap(),
+            Decimal::zero()
+        );
+
+        let big = Decimal::from_str("100000000000").unwrap();
+        decimal_multiplication(big, big).unwrap_err();
+    }
+
+    #[test]
+    fn test_decimal_division() {
+        let a = Decimal::from_str("1.5").unwrap();
+        let b = Decimal::from_str("0.2").unwrap();
+        assert_eq!(
+            decimal_division(a, b).unwrap(),
+            Decimal::from_str("7.5").unwrap()
+        );
+
+        decimal_division(a, Decimal::zero()).unwrap_err();
+
+        let big = Decimal::from_str("100000000000").unwrap();
+        let tiny = Decimal::from_str("0.000000000000000001").unwrap();
+        decimal_division(big, tiny).unwrap_err();
+    }
+
+    #[test]
+    fn test_reverse_decimal() {
+        assert_eq!(reverse_decimal(Decimal::one()).unwrap(), Decimal::one());
+        assert_eq!(
+            reverse_decimal(Decimal::from_str("0.5").unwrap()).unwrap(),
+            Decimal::from_str("2").unwrap()
+        );
+        assert_eq!(
+            reverse_decimal(Decimal::from_str("3").unwrap()).unwrap(),
+            Decimal::from_str("0.333333333333333333").unwrap()
+        );
+
+        reverse_decimal(Decimal::zero()).unwrap_err();
+    }
 }
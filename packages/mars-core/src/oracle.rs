@@ -102,6 +102,8 @@ impl PriceSourceUnchecked {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: Addr,
+    /// Denom of the native asset prices are quoted in. Its price is always one
+    pub base_denom: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -122,6 +124,9 @@ pub mod msg {
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     pub struct InstantiateMsg {
         pub owner: String,
+        /// Denom of the native asset prices are quoted in (default: "uusd").
+        /// Only "uusd" is supported as protocol contracts value positions in it
+        pub base_denom: Option<String>,
     }
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
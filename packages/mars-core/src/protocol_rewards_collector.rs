@@ -2,7 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use cosmwasm_std::{Addr, Decimal as StdDecimal};
+use cosmwasm_std::{Addr, Decimal as StdDecimal, Uint128};
 
 use crate::error::MarsError;
 use crate::helpers::decimal_param_le_one;
@@ -15,10 +15,9 @@ pub struct Config {
     pub owner: Addr,
     /// Address provider returns addresses for all protocol contracts
     pub address_provider_address: Addr,
-    /// Percentage of fees that are sent to the safety fund
-    pub safety_fund_fee_share: Decimal,
-    /// Percentage of fees that are sent to the treasury
-    pub treasury_fee_share: Decimal,
+    /// Recipients of the protocol fees with the percentage each of them gets. Whatever is
+    /// left after paying all of them is sent to the staking contract
+    pub fee_recipients: Vec<FeeRecipient>,
     /// Astroport factory contract address
     pub astroport_factory_address: Addr,
     /// Astroport max spread
@@ -27,10 +26,12 @@ pub struct Config {
 
 impl Config {
     pub fn validate(&self) -> Result<(), ConfigError> {
-        decimal_param_le_one(&self.safety_fund_fee_share, "safety_fund_fee_share")?;
-        decimal_param_le_one(&self.treasury_fee_share, "treasury_fee_share")?;
+        let mut combined_fee_share = Decimal::zero();
+        for fee_recipient in &self.fee_recipients {
+            decimal_param_le_one(&fee_recipient.fee_share, "fee_share")?;
+            combined_fee_share = combined_fee_share + fee_recipient.fee_share;
+        }
 
-        let combined_fee_share = self.safety_fund_fee_share + self.treasury_fee_share;
         // Combined fee shares cannot exceed one
         if combined_fee_share > Decimal::one() {
             return Err(ConfigError::InvalidFeeShareAmounts {});
@@ -45,13 +46,27 @@ pub enum ConfigError {
     #[error("{0}")]
     Mars(#[from] MarsError),
 
-    #[error("Invalid fee share amounts. Sum of safety and treasury fee shares exceeds one")]
+    #[error("Invalid fee share amounts. Sum of fee recipient shares exceeds one")]
     InvalidFeeShareAmounts {},
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeRecipient {
+    /// Address the fees are sent to
+    pub address: Addr,
+    /// Percentage of fees that are sent to the address
+    pub fee_share: Decimal,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AssetConfig {
     pub enabled_for_distribution: bool,
+    /// Min amount to distribute, distributions of smaller amounts are skipped
+    /// (0 means no minimum)
+    pub min_distribution_amount: Uint128,
+    /// Min seconds between two distributions, distributions happening earlier are skipped
+    /// (0 means no minimum)
+    pub min_distribution_interval_seconds: u64,
 }
 
 #[allow(clippy::derivable_impls)]
@@ -59,10 +74,18 @@ impl Default for AssetConfig {
     fn default() -> Self {
         AssetConfig {
             enabled_for_distribution: false,
+            min_distribution_amount: Uint128::zero(),
+            min_distribution_interval_seconds: 0,
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapLeftoverResponse {
+    /// Amount of the asset that was left unswapped by the last partial fill swap
+    pub amount: Uint128,
+}
+
 pub mod msg {
     use schemars::JsonSchema;
     use serde::{Deserialize, Serialize};
@@ -73,6 +96,7 @@ pub mod msg {
 
     use crate::asset::Asset;
     use crate::math::decimal::Decimal;
+    use crate::swapping::SwapFillMode;
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     pub struct InstantiateMsg {
@@ -83,12 +107,17 @@ pub mod msg {
     pub struct CreateOrUpdateConfig {
         pub owner: Option<String>,
         pub address_provider_address: Option<String>,
-        pub safety_fund_fee_share: Option<Decimal>,
-        pub treasury_fee_share: Option<Decimal>,
+        pub fee_recipients: Option<Vec<FeeRecipientParams>>,
         pub astroport_factory_address: Option<String>,
         pub astroport_max_spread: Option<StdDecimal>,
     }
 
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct FeeRecipientParams {
+        pub address: String,
+        pub fee_share: Decimal,
+    }
+
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     #[serde(rename_all = "snake_case")]
     pub enum ExecuteMsg {
@@ -96,7 +125,15 @@ pub mod msg {
         UpdateConfig { config: CreateOrUpdateConfig },
 
         /// Update asset config
-        UpdateAssetConfig { asset: Asset, enabled: bool },
+        UpdateAssetConfig {
+            asset: Asset,
+            enabled: bool,
+            /// Min amount to distribute. Defaults to 0 (no minimum) on asset config creation
+            min_distribution_amount: Option<Uint128>,
+            /// Min seconds between two distributions. Defaults to 0 (no minimum) on asset
+            /// config creation
+            min_distribution_interval_seconds: Option<u64>,
+        },
 
         /// Withdraw maTokens from the red bank
         WithdrawFromRedBank {
@@ -104,9 +141,10 @@ pub mod msg {
             amount: Option<Uint128>,
         },
 
-        /// Distribute the accrued protocol income to the safety fund, treasury and staking contracts,
+        /// Distribute the accrued protocol income to the fee recipients and the staking contract,
         /// according to the split set in config.
-        /// Callable by any address.
+        /// Callable by any address. Distributions below the asset config min amount or before
+        /// its min interval are skipped.
         DistributeProtocolRewards {
             /// Asset market fees to distribute
             asset: Asset,
@@ -118,6 +156,9 @@ pub mod msg {
         SwapAssetToUusd {
             offer_asset_info: AssetInfo,
             amount: Option<Uint128>,
+            /// On partial fill mode only the amount that can be swapped within the max spread
+            /// is swapped and the rest is recorded as leftover. Defaults to full fill
+            fill_mode: Option<SwapFillMode>,
         },
 
         /// Execute Cosmos msg (only callable by owner)
@@ -131,5 +172,8 @@ pub mod msg {
         Config {},
         /// Get asset config parameters
         AssetConfig { asset: Asset },
+        /// Get the amount left unswapped by the last partial fill swap of an asset.
+        /// Returns SwapLeftoverResponse
+        SwapLeftover { offer_asset_info: AssetInfo },
     }
 }
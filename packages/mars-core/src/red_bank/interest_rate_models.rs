@@ -78,14 +78,21 @@ pub fn update_market_interest_rates_with_model(
     market: &mut Market,
     current_utilization_rate: Decimal,
 ) -> StdResult<()> {
+    // the borrow rate cap applies to the total move within the block, so several updates in
+    // the same block cannot add up
+    let current_block_time = env.block.time.seconds();
+    if market.borrow_rate_block_start_time != current_block_time {
+        market.borrow_rate_at_block_start = market.borrow_rate;
+        market.borrow_rate_block_start_time = current_block_time;
+    }
+    let previous_borrow_rate = market.borrow_rate_at_block_start;
+
     // update borrow rate
     match market.interest_rate_model {
         InterestRateModel::Dynamic {
             ref params,
             ref mut state,
         } => {
-            let current_block_time = env.block.time.seconds();
-
             // update tx count and determine if borrow rate should be updated
             state.txs_since_last_borrow_rate_update += 1;
             let seconds_since_last_borrow_rate_update =
@@ -114,6 +121,21 @@ pub fn update_market_interest_rates_with_model(
         }
     }
 
+    // cap the borrow rate move to smooth out utilization spikes
+    if !market.max_borrow_rate_change.is_zero() {
+        let max_borrow_rate = previous_borrow_rate + market.max_borrow_rate_change;
+        let min_borrow_rate = if previous_borrow_rate > market.max_borrow_rate_change {
+            previous_borrow_rate - market.max_borrow_rate_change
+        } else {
+            Decimal::zero()
+        };
+        if market.borrow_rate > max_borrow_rate {
+            market.borrow_rate = max_borrow_rate;
+        } else if market.borrow_rate < min_borrow_rate {
+            market.borrow_rate = min_borrow_rate;
+        }
+    }
+
     // update liquidity rate
     market.liquidity_rate = get_liquidity_rate(
         market.borrow_rate,
@@ -770,4 +792,93 @@ mod tests {
             assert_eq!(new_borrow_rate, expected_borrow_rate);
         }
     }
+
+    #[test]
+    fn test_max_borrow_rate_change() {
+        let interest_rate_model = init_interest_rate_model(
+            InterestRateModelParams::Linear(LinearInterestRateModelParams {
+                optimal_utilization_rate: Decimal::percent(80),
+                base: Decimal::zero(),
+                slope_1: Decimal::percent(7),
+                slope_2: Decimal::percent(45),
+            }),
+            123,
+        )
+        .unwrap();
+
+        let mut market = Market {
+            borrow_rate: Decimal::percent(10),
+            reserve_factor: Decimal::percent(20),
+            interest_rate_model,
+            max_borrow_rate_change: Decimal::percent(5),
+            ..Default::default()
+        };
+
+        // utilization spike only moves the borrow rate up by the configured step
+        update_market_interest_rates_with_model(
+            &mock_env_at_block_time(1234),
+            &mut market,
+            Decimal::one(),
+        )
+        .unwrap();
+        assert_eq!(market.borrow_rate, Decimal::percent(15));
+        assert_eq!(market.liquidity_rate, Decimal::percent(12));
+
+        // further updates in the same block cannot move it past the step
+        update_market_interest_rates_with_model(
+            &mock_env_at_block_time(1234),
+            &mut market,
+            Decimal::one(),
+        )
+        .unwrap();
+        assert_eq!(market.borrow_rate, Decimal::percent(15));
+
+        // a following spike keeps moving it by one step at a time
+        update_market_interest_rates_with_model(
+            &mock_env_at_block_time(1235),
+            &mut market,
+            Decimal::one(),
+        )
+        .unwrap();
+        assert_eq!(market.borrow_rate, Decimal::percent(20));
+
+        // utilization drop is capped the same way
+        update_market_interest_rates_with_model(
+            &mock_env_at_block_time(1236),
+            &mut market,
+            Decimal::zero(),
+        )
+        .unwrap();
+        assert_eq!(market.borrow_rate, Decimal::percent(15));
+
+        // borrow rate does not go below zero when capping
+        for block_time in 1237..1240 {
+            update_market_interest_rates_with_model(
+                &mock_env_at_block_time(block_time),
+                &mut market,
+                Decimal::zero(),
+            )
+            .unwrap();
+        }
+        assert_eq!(market.borrow_rate, Decimal::zero());
+
+        // moves smaller than the step are not affected
+        update_market_interest_rates_with_model(
+            &mock_env_at_block_time(1240),
+            &mut market,
+            Decimal::percent(40),
+        )
+        .unwrap();
+        assert_eq!(market.borrow_rate, Decimal::from_ratio(35u128, 1000u128));
+
+        // no cap when max borrow rate change is zero
+        market.max_borrow_rate_change = Decimal::zero();
+        update_market_interest_rates_with_model(
+            &mock_env_at_block_time(1241),
+            &mut market,
+            Decimal::one(),
+        )
+        .unwrap();
+        assert_eq!(market.borrow_rate, Decimal::percent(52));
+    }
 }
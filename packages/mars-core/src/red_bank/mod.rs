@@ -1,19 +1,29 @@
 pub mod interest_rate_models;
 pub mod msg;
+pub mod scaled_amount;
+
+use std::fmt;
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal as StdDecimal, StdResult, Uint128};
 
-use crate::asset::AssetType;
+use crate::asset::{Asset, AssetType};
 use crate::error::MarsError;
 use crate::helpers::decimal_param_le_one;
 use crate::math::decimal::Decimal;
+use crate::math::{compute_asset_amount, compute_asset_value};
 
 use self::interest_rate_models::InterestRateModel;
 
+pub const MINIMUM_LIQUIDATION_BONUS_PERCENTAGE: u64 = 1;
+pub const MAXIMUM_LIQUIDATION_BONUS_PERCENTAGE: u64 = 20;
+/// Decimals of uusd, used as default for the market asset and price decimals
+pub const DEFAULT_DECIMALS: u8 = 6;
+pub const MAX_DECIMALS: u8 = 18;
+
 /// Global configuration
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -25,11 +35,43 @@ pub struct Config {
     pub ma_token_code_id: u64,
     /// Maximum percentage of outstanding debt that can be covered by a liquidator
     pub close_factor: Decimal,
+    /// maToken balances worth less than this uusd amount are considered dust and can be swept
+    pub dust_threshold_in_uusd: Uint128,
+    /// Collateral positions worth less than this uusd amount don't count towards the user's
+    /// borrowing power
+    pub min_collateral_value_in_uusd: Uint128,
+    /// Margin above a health factor of 1 that a user position must keep after a new borrow
+    pub borrow_health_factor_buffer: Decimal,
+    /// Maximum number of markets a user can be using as collateral or borrowing at the same
+    /// time, to bound the gas cost of computing the user position (zero means no maximum)
+    pub max_markets_per_user: u32,
+    /// Maximum relative change between an asset price and the last price the red bank used
+    /// for it within `price_deviation_window`. Operations depending on the price of an asset
+    /// exceeding it are paused until the window elapses (zero disables the check)
+    pub max_price_deviation: Decimal,
+    /// Time window in seconds in which new prices are checked against the last used price
+    pub price_deviation_window: u64,
+    /// Astroport factory used to swap collateral when repaying debt with collateral
+    pub astroport_factory_address: Addr,
+    /// Minimum time in seconds between successive config updates, and between successive
+    /// updates of the same market (zero disables the cooldown)
+    pub update_cooldown_seconds: u64,
+    /// Address allowed to tighten market risk parameters without a governance proposal
+    /// (zero address means no guardian)
+    pub guardian_address: Addr,
+    /// Share of the collateral used on an auto-repayment that is paid (in maTokens) to the
+    /// keeper triggering it
+    pub auto_repay_keeper_fee: Decimal,
 }
 
 impl Config {
     pub fn validate(&self) -> Result<(), MarsError> {
         decimal_param_le_one(&self.close_factor, "close_factor")?;
+        decimal_param_le_one(
+            &self.borrow_health_factor_buffer,
+            "borrow_health_factor_buffer",
+        )?;
+        decimal_param_le_one(&self.auto_repay_keeper_fee, "auto_repay_keeper_fee")?;
 
         Ok(())
     }
@@ -61,6 +103,10 @@ pub struct Market {
     pub liquidation_bonus: Decimal,
     /// Portion of the borrow rate that is kept as protocol rewards
     pub reserve_factor: Decimal,
+    /// Portion of each deposit that is kept as protocol rewards
+    pub deposit_fee: Decimal,
+    /// Portion of each withdrawal that is kept as protocol rewards
+    pub withdraw_fee: Decimal,
 
     /// model (params + internal state) that defines how interest rate behaves
     pub interest_rate_model: InterestRateModel,
@@ -69,15 +115,27 @@ pub struct Market {
     pub borrow_index: Decimal,
     /// Liquidity index (Used to compute deposit interest)
     pub liquidity_index: Decimal,
-    /// Rate charged to borrowers
+    /// Annual rate charged to borrowers. Applied as simple interest between index updates,
+    /// so borrowers' interest compounds each time the indexes are updated
     pub borrow_rate: Decimal,
-    /// Rate paid to depositors
+    /// Annual rate paid to depositors. Applied as simple interest between index updates,
+    /// so depositors' interest compounds each time the indexes are updated
     pub liquidity_rate: Decimal,
     /// Timestamp (seconds) where indexes and where last updated
     pub indexes_last_updated: u64,
+    /// Timestamp (seconds) at which the market was initialized
+    pub created_at: u64,
+    /// Number of times the indexes were updated (i.e. interests were compounded) since the
+    /// market was initialized
+    pub interests_compounding_count: u64,
 
     /// Total debt scaled for the market's currency
     pub debt_total_scaled: Uint128,
+    /// Total deposits scaled for the market's currency (equal to the maToken supply)
+    pub total_deposits_scaled: Uint128,
+    /// Underlying amount of a native asset that is not attributable to any maToken holder
+    /// because of rounding when scaling amounts. Can be swept to the treasury by the owner
+    pub rounding_dust: Uint128,
 
     /// If false cannot do any action (deposit/withdraw/borrow/repay/liquidate)
     pub active: bool,
@@ -85,13 +143,131 @@ pub struct Market {
     pub deposit_enabled: bool,
     /// If false cannot borrow
     pub borrow_enabled: bool,
+    /// Seconds a user has to wait after borrowing the asset before borrowing it again
+    /// (0 means no cooldown)
+    pub borrow_cooldown_seconds: u64,
+    /// Max amount the borrow rate can move within a single block (0 means no limit)
+    pub max_borrow_rate_change: Decimal,
+    /// Borrow rate at the start of the block `max_borrow_rate_change` currently applies to
+    pub borrow_rate_at_block_start: Decimal,
+    /// Timestamp of the block `borrow_rate_at_block_start` was recorded on
+    pub borrow_rate_block_start_time: u64,
+    /// Fraction of the borrow rate waived for users holding at least
+    /// `borrow_rate_discount_min_xmars` xMars (0 means no discount), checked each time their
+    /// debt changes. The waived interest is paid from protocol rewards so it can be at most
+    /// the reserve factor
+    pub borrow_rate_discount: Decimal,
+    /// Min xMars balance a user needs to get the borrow rate discount
+    pub borrow_rate_discount_min_xmars: Uint128,
+    /// Interest waived by borrow rate discounts not yet covered by protocol rewards. Paid from
+    /// the next accrued protocol rewards before any is minted to the rewards collector
+    pub pending_borrow_rate_discount: Uint128,
+    /// Whether users can borrow the asset at a stable borrow rate
+    pub stable_borrow_enabled: bool,
+    /// Premium over the variable borrow rate charged to borrowers choosing a stable rate
+    pub stable_borrow_rate_premium: Decimal,
+    /// Utilization rate from which debts with a stable rate below the market stable rate can
+    /// be rebalanced (0 disables the condition)
+    pub stable_rate_rebalance_utilization_threshold: Decimal,
+    /// Difference between a debt stable rate and the market stable rate from which the debt
+    /// can be rebalanced, up or down (0 disables the condition)
+    pub stable_rate_rebalance_max_divergence: Decimal,
+    /// Underlying debt at a stable rate when the indexes were last updated. Stable debt is not
+    /// part of `debt_total_scaled` and accrues at `average_stable_borrow_rate`
+    pub stable_debt_total: Uint128,
+    /// Average stable rate of the stable debt, weighted by the debt amounts
+    pub average_stable_borrow_rate: Decimal,
+    /// Seconds after the market is deprecated during which the borrow rate stays frozen at its
+    /// value on deprecation so borrowers can repay
+    pub deprecation_grace_period_seconds: u64,
+    /// Min borrow rate charged once the deprecation grace period is over
+    /// (0 means the interest rate model rate applies)
+    pub deprecation_penalty_borrow_rate: Decimal,
+    /// Timestamp (seconds) at which the market was deprecated, if it was
+    pub deprecated_at: Option<u64>,
+    /// Min rate paid to depositors, capped at the borrow rate (0 means no floor). The part of
+    /// the liquidity rate above what the interest rate model gives is paid from protocol rewards,
+    /// so it is also capped at the current reserve income
+    /// (overall borrow rate * utilization * reserve factor)
+    pub min_liquidity_rate: Decimal,
+    /// Part of the current liquidity rate coming from the `min_liquidity_rate` floor
+    pub liquidity_rate_subsidy: Decimal,
+    /// Subsidy accrued to depositors not yet covered by protocol rewards. Paid from the next
+    /// accrued protocol rewards before any is minted to the rewards collector
+    pub pending_liquidity_subsidy: Uint128,
+    /// Total underlying amount accrued to depositors because of the `min_liquidity_rate` floor
+    pub total_liquidity_subsidy: Uint128,
+    /// E-mode category the asset belongs to (0 means none)
+    pub emode_category_id: u32,
+    /// Max underlying debt of the asset that can be repaid on a single liquidation, on top
+    /// of the close factor limit (0 means no limit)
+    pub max_liquidation_amount_per_tx: Uint128,
+    /// Shared interest rate strategy the market follows (0 means the market uses its own
+    /// interest rate model params)
+    pub interest_rate_strategy_id: u32,
+    /// Max uusd value of the total debt of the asset, computed with the oracle price on each
+    /// borrow (0 means no ceiling)
+    pub debt_ceiling_in_uusd: Uint128,
+    /// Portion of the liquidation bonus kept by the protocol as liquidation fees
+    pub liquidation_protocol_fee: Decimal,
+    /// Underlying amount of liquidation fees held by the contract that has not been claimed
+    /// to the treasury yet
+    pub liquidation_fees_accrued: Uint128,
+    /// Total underlying amount of liquidation fees taken by the market
+    pub total_liquidation_fees: Uint128,
+    /// Part of the rounding dust that is never swept, so the contract keeps a buffer over the
+    /// amounts attributable to users
+    pub rounding_reserve: Uint128,
+    /// Decimals of the asset
+    pub asset_decimals: u8,
+    /// Decimals of the asset the oracle price is quoted in. The price is taken as the value
+    /// of a whole unit of the asset in whole units of the quote asset, so it only matches the
+    /// base units ratio when both decimals are equal
+    pub price_decimals: u8,
 }
 
 impl Market {
     pub fn validate(&self) -> Result<(), MarketError> {
         decimal_param_le_one(&self.max_loan_to_value, "max_loan_to_value")?;
         decimal_param_le_one(&self.liquidation_threshold, "liquidation_threshold")?;
-        decimal_param_le_one(&self.liquidation_bonus, "liquidation_bonus")?;
+        decimal_param_le_one(&self.deposit_fee, "deposit_fee")?;
+        decimal_param_le_one(&self.withdraw_fee, "withdraw_fee")?;
+        decimal_param_le_one(&self.max_borrow_rate_change, "max_borrow_rate_change")?;
+        decimal_param_le_one(&self.borrow_rate_discount, "borrow_rate_discount")?;
+        decimal_param_le_one(
+            &self.stable_borrow_rate_premium,
+            "stable_borrow_rate_premium",
+        )?;
+        decimal_param_le_one(
+            &self.stable_rate_rebalance_utilization_threshold,
+            "stable_rate_rebalance_utilization_threshold",
+        )?;
+        decimal_param_le_one(
+            &self.deprecation_penalty_borrow_rate,
+            "deprecation_penalty_borrow_rate",
+        )?;
+        decimal_param_le_one(&self.min_liquidity_rate, "min_liquidity_rate")?;
+        decimal_param_le_one(&self.liquidation_protocol_fee, "liquidation_protocol_fee")?;
+        decimals_param_le_max(self.asset_decimals, "asset_decimals")?;
+        decimals_param_le_max(self.price_decimals, "price_decimals")?;
+
+        // liquidation_bonus should be high enough for liquidations to be profitable, but
+        // not so high that liquidators are over-rewarded at the expense of borrowers
+        let minimum_liquidation_bonus = Decimal::percent(MINIMUM_LIQUIDATION_BONUS_PERCENTAGE);
+        let maximum_liquidation_bonus = Decimal::percent(MAXIMUM_LIQUIDATION_BONUS_PERCENTAGE);
+        if !(self.liquidation_bonus >= minimum_liquidation_bonus
+            && self.liquidation_bonus <= maximum_liquidation_bonus)
+        {
+            return Err(MarsError::InvalidParam {
+                param_name: "liquidation_bonus".to_string(),
+                invalid_value: self.liquidation_bonus.to_string(),
+                predicate: format!(
+                    ">= {} and <= {}",
+                    minimum_liquidation_bonus, maximum_liquidation_bonus
+                ),
+            }
+            .into());
+        }
 
         // liquidation_threshold should be greater than max_loan_to_value
         if self.liquidation_threshold <= self.max_loan_to_value {
@@ -101,8 +277,39 @@ impl Market {
             });
         }
 
+        // borrow_rate_discount is paid from the protocol share of the interests
+        if self.borrow_rate_discount > self.reserve_factor {
+            return Err(MarsError::InvalidParam {
+                param_name: "borrow_rate_discount".to_string(),
+                invalid_value: self.borrow_rate_discount.to_string(),
+                predicate: format!("<= reserve_factor ({})", self.reserve_factor),
+            }
+            .into());
+        }
+
         Ok(())
     }
+
+    /// Value in uusd of an amount of the asset given its oracle price
+    pub fn compute_value(&self, amount: Uint128, price: Decimal) -> StdResult<Uint128> {
+        compute_asset_value(amount, self.asset_decimals, price, self.price_decimals)
+    }
+
+    /// Amount of the asset worth a value in uusd given its oracle price
+    pub fn compute_amount(&self, value: Uint128, price: Decimal) -> StdResult<Uint128> {
+        compute_asset_amount(value, self.asset_decimals, price, self.price_decimals)
+    }
+}
+
+fn decimals_param_le_max(decimals: u8, param_name: &str) -> Result<(), MarsError> {
+    if decimals > MAX_DECIMALS {
+        return Err(MarsError::InvalidParam {
+            param_name: param_name.to_string(),
+            invalid_value: decimals.to_string(),
+            predicate: format!("<= {}", MAX_DECIMALS),
+        });
+    }
+    Ok(())
 }
 
 impl Default for Market {
@@ -134,8 +341,14 @@ impl Default for Market {
             liquidity_rate: Default::default(),
             max_loan_to_value: Default::default(),
             reserve_factor: Default::default(),
+            deposit_fee: Decimal::zero(),
+            withdraw_fee: Decimal::zero(),
             indexes_last_updated: 0,
+            created_at: 0,
+            interests_compounding_count: 0,
             debt_total_scaled: Default::default(),
+            total_deposits_scaled: Default::default(),
+            rounding_dust: Uint128::zero(),
             asset_type: AssetType::Native,
             liquidation_threshold: Decimal::one(),
             liquidation_bonus: Decimal::zero(),
@@ -143,6 +356,36 @@ impl Default for Market {
             active: true,
             deposit_enabled: true,
             borrow_enabled: true,
+            borrow_cooldown_seconds: 0,
+            max_borrow_rate_change: Decimal::zero(),
+            borrow_rate_at_block_start: Decimal::zero(),
+            borrow_rate_block_start_time: 0,
+            borrow_rate_discount: Decimal::zero(),
+            borrow_rate_discount_min_xmars: Uint128::zero(),
+            pending_borrow_rate_discount: Uint128::zero(),
+            stable_borrow_enabled: false,
+            stable_borrow_rate_premium: Decimal::zero(),
+            stable_rate_rebalance_utilization_threshold: Decimal::zero(),
+            stable_rate_rebalance_max_divergence: Decimal::zero(),
+            stable_debt_total: Uint128::zero(),
+            average_stable_borrow_rate: Decimal::zero(),
+            deprecation_grace_period_seconds: 0,
+            deprecation_penalty_borrow_rate: Decimal::zero(),
+            deprecated_at: None,
+            min_liquidity_rate: Decimal::zero(),
+            liquidity_rate_subsidy: Decimal::zero(),
+            pending_liquidity_subsidy: Uint128::zero(),
+            total_liquidity_subsidy: Uint128::zero(),
+            emode_category_id: 0,
+            max_liquidation_amount_per_tx: Uint128::zero(),
+            interest_rate_strategy_id: 0,
+            debt_ceiling_in_uusd: Uint128::zero(),
+            liquidation_protocol_fee: Decimal::zero(),
+            liquidation_fees_accrued: Uint128::zero(),
+            total_liquidation_fees: Uint128::zero(),
+            rounding_reserve: Uint128::zero(),
+            asset_decimals: DEFAULT_DECIMALS,
+            price_decimals: DEFAULT_DECIMALS,
         }
     }
 }
@@ -157,6 +400,67 @@ pub enum MarketError {
         liquidation_threshold: Decimal,
         max_loan_to_value: Decimal,
     },
+
+    #[error("e-mode liquidation_threshold * (1 + liquidation_bonus) should be <= 1. liquidation_threshold: {liquidation_threshold:?}, liquidation_bonus: {liquidation_bonus:?}")]
+    InvalidEModeLiquidationThreshold {
+        liquidation_threshold: Decimal,
+        liquidation_bonus: Decimal,
+    },
+}
+
+/// Efficiency mode category: a group of correlated assets (e.g: stablecoins) whose collateral
+/// gets higher risk parameters for users opting in to the category. Users in e-mode can only
+/// borrow assets of their category
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EModeCategory {
+    /// Name of the category
+    pub label: String,
+    /// Max uusd that can be borrowed per uusd of collateral of an asset in the category
+    pub max_loan_to_value: Decimal,
+    /// Liquidation threshold for collateral of an asset in the category
+    pub liquidation_threshold: Decimal,
+}
+
+impl EModeCategory {
+    pub fn validate(&self) -> Result<(), MarketError> {
+        decimal_param_le_one(&self.max_loan_to_value, "max_loan_to_value")?;
+        decimal_param_le_one(&self.liquidation_threshold, "liquidation_threshold")?;
+
+        if self.liquidation_threshold <= self.max_loan_to_value {
+            return Err(MarketError::InvalidLiquidationThreshold {
+                liquidation_threshold: self.liquidation_threshold,
+                max_loan_to_value: self.max_loan_to_value,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Validates the category can be used by a market with the given liquidation bonus:
+    /// liquidating a position at the liquidation threshold should not take more collateral
+    /// than the position has
+    pub fn validate_liquidation_bonus(
+        &self,
+        liquidation_bonus: Decimal,
+    ) -> Result<(), MarketError> {
+        let max_seized_collateral_ratio = self
+            .liquidation_threshold
+            .checked_mul(Decimal::one() + liquidation_bonus)
+            .map_err(MarsError::from)?;
+        if max_seized_collateral_ratio > Decimal::one() {
+            return Err(MarketError::InvalidEModeLiquidationThreshold {
+                liquidation_threshold: self.liquidation_threshold,
+                liquidation_bonus,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserEModeCategoryResponse {
+    /// E-mode category the user opted in to (0 means none)
+    pub category_id: u32,
 }
 
 /// Data for individual users
@@ -179,6 +483,15 @@ impl Default for User {
     }
 }
 
+/// Last price used by the red bank for an asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceSnapshot {
+    /// Asset price in uusd
+    pub price: Decimal,
+    /// Timestamp (seconds) at which the price was used
+    pub timestamp: u64,
+}
+
 /// Debt for each asset and user
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Debt {
@@ -187,6 +500,42 @@ pub struct Debt {
 
     /// Marker for uncollateralized debt
     pub uncollateralized: bool,
+
+    /// Fraction of the borrow rate waived for the debt, recorded when borrowing
+    pub borrow_rate_discount: Decimal,
+    /// Market borrow index when the borrow rate discount was last applied to the debt
+    pub discount_borrow_index: Decimal,
+
+    /// Whether the debt accrues interests at the market variable borrow rate or at a fixed
+    /// stable rate
+    pub rate_mode: BorrowRateMode,
+    /// Fixed borrow rate of the debt (only used in stable rate mode)
+    pub stable_borrow_rate: Decimal,
+    /// Market borrow index when stable rate interests were last applied to the debt
+    pub stable_rate_borrow_index: Decimal,
+    /// Timestamp (seconds) when stable rate interests were last applied to the debt
+    pub stable_rate_last_updated: u64,
+}
+
+/// Borrow rate mode of a debt position
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BorrowRateMode {
+    /// Debt accrues interests at the market borrow rate, which changes with utilization
+    Variable,
+    /// Debt accrues interests at a rate fixed when borrowing, which can only be rebalanced
+    /// to the market stable rate under some market conditions
+    Stable,
+}
+
+impl fmt::Display for BorrowRateMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let display_str = match self {
+            BorrowRateMode::Variable => "variable",
+            BorrowRateMode::Stable => "stable",
+        };
+        write!(f, "{}", display_str)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -203,6 +552,71 @@ pub struct ConfigResponse {
     pub ma_token_code_id: u64,
     pub market_count: u32,
     pub close_factor: Decimal,
+    pub dust_threshold_in_uusd: Uint128,
+    pub min_collateral_value_in_uusd: Uint128,
+    pub borrow_health_factor_buffer: Decimal,
+    pub max_markets_per_user: u32,
+    pub max_price_deviation: Decimal,
+    pub price_deviation_window: u64,
+    pub astroport_factory_address: Addr,
+    pub update_cooldown_seconds: u64,
+    pub guardian_address: Addr,
+    pub auto_repay_keeper_fee: Decimal,
+    /// Whether the stored config passes validation
+    pub is_valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketResponse {
+    pub market: Market,
+    /// Timestamp (seconds) where interests (indexes and rates) were last updated
+    pub interests_last_updated: u64,
+    /// Current block timestamp (seconds), so staleness of stored rates can be computed
+    pub current_block_time: u64,
+    /// Average number of times per year interests have been compounded since the market was
+    /// initialized. Interests compound on every index update, which happens on each market
+    /// interaction rather than on a fixed schedule
+    pub compoundings_per_year: u64,
+    /// Effective annual borrow rate if the borrow rate was compounded `compoundings_per_year`
+    /// times per year (equal to the borrow rate when there is no compounding history yet)
+    pub borrow_apy: Decimal,
+    /// Effective annual liquidity rate if the liquidity rate was compounded
+    /// `compoundings_per_year` times per year (equal to the liquidity rate when there is no
+    /// compounding history yet)
+    pub liquidity_apy: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketsByAssetsResponse {
+    pub markets: Vec<MarketByAsset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetMarketInfoResponse {
+    /// Denom for native assets, contract address for cw20 assets
+    pub denom: String,
+    pub market_exists: bool,
+    /// None if there is no market for the denom
+    pub asset_type: Option<AssetType>,
+    /// None if there is no market for the denom or its maToken is not registered yet
+    pub ma_token_address: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketInitializedResponse {
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Whether the maToken of the market has been registered
+    pub initialized: bool,
+    /// None until the maToken is registered
+    pub ma_token_address: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketByAsset {
+    pub asset: Asset,
+    /// None if there is no market for the asset
+    pub market: Option<Market>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -210,6 +624,119 @@ pub struct MarketsListResponse {
     pub markets_list: Vec<MarketInfo>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketIndexesResponse {
+    /// Total markets initialized
+    pub market_count: u32,
+    /// List of market indexes and their assets (paginated by query)
+    pub market_indexes: Vec<MarketIndexInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketIndexInfo {
+    /// Index of the market, used as the bit position in the user bitmaps
+    pub index: u32,
+    /// Asset denom
+    pub denom: String,
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Bytes used as key on the kv store for data related to the asset
+    pub asset_reference: Vec<u8>,
+    /// Indicated whether the asset is native or a cw20 token
+    pub asset_type: AssetType,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnderlyingLiquidityResponse {
+    /// Underlying liquidity of the markets (paginated by query)
+    pub markets: Vec<MarketUnderlyingLiquidity>,
+    /// Sum of the liquidity value of the returned markets
+    pub total_liquidity_in_uusd: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketUnderlyingLiquidity {
+    /// Index of the market
+    pub index: u32,
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Indicated whether the asset is native or a cw20 token
+    pub asset_type: AssetType,
+    /// Underlying amount supplied to the market by maToken holders
+    pub liquidity_amount: Uint128,
+    /// Asset price in uusd
+    pub asset_price: Decimal,
+    /// Value of the supplied amount in uusd
+    pub liquidity_in_uusd: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SolvencyResponse {
+    /// Solvency status of every market
+    pub markets: Vec<MarketSolvency>,
+    /// Sum of the assets value of all markets
+    pub total_assets_in_uusd: Uint128,
+    /// Sum of the liabilities value of all markets
+    pub total_liabilities_in_uusd: Uint128,
+    /// True if the total assets value covers the total liabilities value
+    pub solvent: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketSolvency {
+    /// Index of the market
+    pub index: u32,
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Indicated whether the asset is native or a cw20 token
+    pub asset_type: AssetType,
+    /// Contract balance of the asset plus the underlying debt owed by borrowers
+    pub assets_amount: Uint128,
+    /// Underlying amount supplied to the market by maToken holders
+    pub liabilities_amount: Uint128,
+    /// Asset price in uusd
+    pub asset_price: Decimal,
+    /// Value of the assets in uusd
+    pub assets_in_uusd: Uint128,
+    /// Value of the liabilities in uusd
+    pub liabilities_in_uusd: Uint128,
+    /// True if the market assets cover its liabilities
+    pub solvent: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReserveBalanceResponse {
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Balance of the asset held by the contract
+    pub held_amount: Uint128,
+    /// Balance the contract should hold: underlying supplied (protocol rewards included)
+    /// minus underlying debt plus rounding dust
+    pub expected_amount: Uint128,
+    /// Held amount in excess of the expected amount (e.g. external donations)
+    pub surplus_amount: Uint128,
+    /// Expected amount missing from the held amount
+    pub deficit_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProtocolStatsResponse {
+    /// Number of markets in the protocol
+    pub market_count: u32,
+    /// Value of the underlying supplied to the returned markets
+    pub total_supplied_in_uusd: Uint128,
+    /// Value of the underlying debt owed to the returned markets
+    pub total_borrowed_in_uusd: Uint128,
+    /// Value of the underlying held as maTokens by the protocol rewards collector on the
+    /// returned markets
+    pub total_reserves_in_uusd: Uint128,
+    /// Total borrowed over total supplied value of the returned markets
+    pub utilization_rate: Decimal,
+    /// Index of the last returned market, to be used as `start_after` to get the next page.
+    /// None if there are no more markets
+    pub last_market_index: Option<u32>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MarketInfo {
     /// Asset denom
@@ -245,6 +772,53 @@ pub struct UserAssetDebtResponse {
     pub amount: Uint128,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserBorrowRateResponse {
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Rate mode of the user debt (variable if the user is not borrowing the asset)
+    pub rate_mode: BorrowRateMode,
+    /// Market variable borrow rate
+    pub market_borrow_rate: Decimal,
+    /// Discount on the variable borrow rate. Recorded on the user debt if any, otherwise the
+    /// one a new borrow would get given the user xMars balance
+    pub borrow_rate_discount: Decimal,
+    /// Borrow rate the user pays: the debt stable rate in stable mode, the discounted market
+    /// borrow rate otherwise
+    pub borrow_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DebtResponse {
+    /// Debt as stored in contract state
+    pub debt: Debt,
+    /// Underlying asset amount that is actually owed at the current block
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundingDustResponse {
+    /// Asset denom
+    pub denom: String,
+    /// Underlying amount not attributable to any maToken holder
+    pub amount: Uint128,
+    /// Part of the rounding dust that is kept in the market when sweeping
+    pub reserve_amount: Uint128,
+    /// Part of the rounding dust that can be swept to the treasury. Capped so the contract
+    /// keeps holding the reserve on top of what is owed to maToken holders
+    pub sweepable_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationFeesResponse {
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Underlying amount of liquidation fees that can be claimed to the treasury
+    pub accrued_amount: Uint128,
+    /// Total underlying amount of liquidation fees taken by the market
+    pub total_amount: Uint128,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct UserCollateralResponse {
     pub collateral: Vec<UserAssetCollateralResponse>,
@@ -264,6 +838,163 @@ pub struct UserAssetCollateralResponse {
     pub enabled: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidatablePositionsResponse {
+    pub positions: Vec<LiquidatablePosition>,
+    /// Last user scanned, to be used as `start_after` to get the next page. None if there are
+    /// no more users to scan
+    pub last_scanned_user: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidatablePosition {
+    pub user_address: Addr,
+    pub health_factor: Decimal,
+    pub total_collateralized_debt_in_uusd: Uint128,
+    /// Max debt that can be repaid in a single liquidation, when repaying the largest
+    /// collateralized debt of the user
+    pub max_repayable_debt_in_uusd: Uint128,
+    pub collateral: Vec<LiquidatableCollateral>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidatableCollateral {
+    /// Asset denom
+    pub denom: String,
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Indicated whether the asset is native or a cw20 token
+    pub asset_type: AssetType,
+    /// Underlying collateral amount
+    pub amount: Uint128,
+    /// Estimated collateral amount a liquidator gets (liquidation bonus included) when
+    /// repaying `max_repayable_debt_in_uusd`
+    pub seizable_amount: Uint128,
+    /// Route advised to sell the seized collateral, if one is set for the market
+    pub liquidation_route: Option<LiquidationRoute>,
+}
+
+/// User authorization for keepers to repay debt with the user's collateral once the user
+/// health factor drops below a threshold
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AutoRepayAuthorization {
+    /// Asset used as collateral to repay the debt with
+    pub collateral_asset: Asset,
+    /// Asset of the debt to repay
+    pub debt_asset: Asset,
+    /// Health factor under which keepers can trigger a repayment (must be greater than 1)
+    pub health_factor_threshold: Decimal,
+    /// Max amount of collateral used over all the repayments, keeper fee included. It
+    /// decreases with each repayment and the authorization is removed once it is used up
+    pub max_collateral_amount: Uint128,
+    /// Max spread for the Astroport swap when the assets differ
+    pub max_spread: Option<StdDecimal>,
+}
+
+/// Hint for liquidators on how to sell the collateral of a market that has little on-chain
+/// liquidity
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquidationRoute {
+    /// DEX to swap the collateral on (e.g: astroport)
+    pub dex: String,
+    /// Denom to swap the collateral to before swapping to the debt asset, if the collateral
+    /// has no direct pair
+    pub intermediate_denom: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserLiquidationThresholdsResponse {
+    pub health_status: UserHealthStatus,
+    pub collateral: Vec<UserAssetLiquidationThreshold>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserAssetLiquidationThreshold {
+    /// Asset denom
+    pub denom: String,
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Indicated whether the asset is native or a cw20 token
+    pub asset_type: AssetType,
+    /// Underlying collateral amount
+    pub amount: Uint128,
+    /// Current asset price in uusd
+    pub price: Decimal,
+    /// Liquidation threshold actually applied to the position (zero if its value is below
+    /// the configured min collateral value)
+    pub liquidation_threshold: Decimal,
+    /// Asset price in uusd under which the user can be liquidated if all other prices stay
+    /// the same. None if the user with the current debt can't be liquidated because of a
+    /// drop in this asset price
+    pub liquidation_price: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserPositionSimulationResponse {
+    /// Current user position
+    pub current: UserPositionResponse,
+    /// User position after the simulated action
+    pub simulated: UserPositionResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserBorrowLimitUsageResponse {
+    /// Total debt minus the uncollateralized debt
+    pub total_collateralized_debt_in_uusd: Uint128,
+    /// Collateral value weighted by each asset max loan to value
+    pub max_debt_in_uusd: Uint128,
+    /// Collateralized debt over max debt. Zero when not borrowing, one when the user has debt
+    /// but no borrowing power
+    pub borrow_limit_used: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserPositionFullResponse {
+    /// Raw bitmap of the markets the user is borrowing (bit at the market index)
+    pub borrowed_assets: Uint128,
+    /// Raw bitmap of the markets the user is using as collateral (bit at the market index)
+    pub collateral_assets: Uint128,
+    /// E-mode category the user opted in to (0 means none)
+    pub emode_category_id: u32,
+    /// Markets the user is borrowing or using as collateral
+    pub assets: Vec<UserAssetPositionResponse>,
+    /// User totals and health status
+    pub position: UserPositionResponse,
+    /// Collateralized debt over max debt. Zero when not borrowing, one when the user has debt
+    /// but no borrowing power
+    pub borrow_limit_used: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserAssetPositionResponse {
+    /// Either denom if native asset or contract address if cw20
+    pub asset_label: String,
+    /// Bytes used as key on the kv store for data related to the asset
+    pub asset_reference: Vec<u8>,
+    /// Indicated whether the asset is native or a cw20 token
+    pub asset_type: AssetType,
+    /// Market index, which is the bit used for the asset in the user bitmaps
+    pub market_index: u32,
+    /// Whether the user is using the asset as collateral
+    pub collateral_enabled: bool,
+    /// Whether the user is borrowing the asset
+    pub borrowing: bool,
+    /// Underlying collateral amount (zero if not used as collateral)
+    pub collateral_amount: Uint128,
+    /// Underlying debt amount (zero if not borrowing)
+    pub debt_amount: Uint128,
+    /// Whether the debt is uncollateralized
+    pub uncollateralized_debt: bool,
+    /// Max loan to value applied to the collateral, taking the user e-mode category into
+    /// account (zero if not used as collateral)
+    pub max_loan_to_value: Decimal,
+    /// Liquidation threshold applied to the collateral, taking the user e-mode category into
+    /// account (zero if not used as collateral)
+    pub liquidation_threshold: Decimal,
+    /// Asset price in uusd used to value the position
+    pub asset_price: Decimal,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct UserPositionResponse {
     pub total_collateral_in_uusd: Uint128,
@@ -1,14 +1,15 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal as StdDecimal, Uint128};
 
 use cw20::Cw20ReceiveMsg;
 
-use crate::asset::Asset;
+use crate::asset::{Asset, AssetType};
 use crate::math::decimal::Decimal;
 
 use super::interest_rate_models::InterestRateModelParams;
+use super::{AutoRepayAuthorization, EModeCategory, LiquidationRoute};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -40,6 +41,13 @@ pub enum ExecuteMsg {
         asset_symbol: Option<String>,
     },
 
+    /// Initialize several assets on the money market in a single transaction, each with its
+    /// own maToken (only owner can call). Markets are indexed in the order the assets are
+    /// given and maTokens use the default symbol of the asset (see `InitAsset`)
+    InitAssets {
+        assets: Vec<(Asset, InitOrUpdateAssetParams)>,
+    },
+
     /// Callback sent from maToken contract after instantiated
     InitAssetTokenCallback {
         /// Either the denom for a terra native asset or address for a cw20 token
@@ -68,12 +76,89 @@ pub enum ExecuteMsg {
         new_limit: Uint128,
     },
 
+    /// Deprecate a market: deposits and borrows get disabled and the borrow rate is frozen
+    /// during the market's deprecation grace period so borrowers can repay. Once the grace
+    /// period is over the borrow rate is at least the market's deprecation penalty borrow rate
+    /// (only owner can call)
+    DeprecateMarket {
+        /// Asset of the market to deprecate
+        asset: Asset,
+    },
+
+    /// Write off the debt of a user that has no collateral left, spreading the loss among the
+    /// market depositors by lowering the liquidity index (only owner can call)
+    SocializeBadDebt {
+        /// Asset of the debt to write off
+        asset: Asset,
+        /// User holding the bad debt
+        user_address: String,
+    },
+
+    /// Lower the max loan to value and/or the liquidation threshold of a market to de-risk it
+    /// without a governance proposal. Params can only be tightened, never relaxed
+    /// (only guardian can call)
+    TightenAssetParams {
+        /// Asset of the market to update
+        asset: Asset,
+        /// New max loan to value, must not be higher than the current one
+        max_loan_to_value: Option<Decimal>,
+        /// New liquidation threshold, must not be higher than the current one
+        liquidation_threshold: Option<Decimal>,
+    },
+
+    /// Lower the max loan to value and/or the liquidation threshold of an e-mode category to
+    /// de-risk it without a governance proposal. Params can only be tightened, never relaxed
+    /// (only guardian can call)
+    TightenEModeCategoryParams {
+        category_id: u32,
+        /// New max loan to value, must not be higher than the current one
+        max_loan_to_value: Option<Decimal>,
+        /// New liquidation threshold, must not be higher than the current one
+        liquidation_threshold: Option<Decimal>,
+    },
+
+    /// Set the DEX route liquidators are advised to use to sell the collateral of a market,
+    /// returned along liquidatable positions. A `None` route removes it (only owner can call)
+    SetLiquidationRoute {
+        /// Asset of the market to set the route for
+        asset: Asset,
+        route: Option<LiquidationRoute>,
+    },
+
     /// Deposit Terra native coins. Deposited coins must be sent in the transaction
     /// this call is made
     DepositNative {
         /// Denom used in Terra (e.g: uluna, uusd)
         denom: String,
-        /// Address that will receive the maTokens
+        /// Address that will receive the maTokens. With a markets limit set, they are only
+        /// used as collateral once the recipient enables them if it wasn't using the asset
+        on_behalf_of: Option<String>,
+    },
+
+    /// Deposit cw20 tokens the caller approved the contract to spend, for integrations that
+    /// cannot attach a hook message to a cw20 send. The tokens are pulled with a `TransferFrom`
+    /// and the deposit is credited once they are received
+    DepositCw20FromAllowance {
+        /// Cw20 token contract address
+        token_address: String,
+        /// Amount to deposit (must not exceed the allowance)
+        amount: Uint128,
+        /// Address that will receive the maTokens. With a markets limit set, they are only
+        /// used as collateral once the recipient enables them if it wasn't using the asset
+        on_behalf_of: Option<String>,
+    },
+
+    /// Credit a deposit from allowance once the tokens were transferred to the contract
+    /// (only callable by the contract itself)
+    DepositCw20FromAllowanceCallback {
+        /// Address the tokens were pulled from
+        depositor_address: String,
+        /// Cw20 token contract address
+        token_address: String,
+        /// Amount pulled
+        amount: Uint128,
+        /// Address that will receive the maTokens. With a markets limit set, they are only
+        /// used as collateral once the recipient enables them if it wasn't using the asset
         on_behalf_of: Option<String>,
     },
 
@@ -113,6 +198,79 @@ pub enum ExecuteMsg {
         on_behalf_of: Option<String>,
     },
 
+    /// Repay the caller's full debt on all the assets they borrowed so they can exit the
+    /// red bank. Coins used to repay must be sent in the transaction this call is made, one
+    /// per borrowed asset, and any amount exceeding a debt is refunded. Not available for
+    /// users with cw20 debts
+    RepayAll {},
+
+    /// Repay debt using the caller's own collateral (self-deleverage). An equivalent amount of
+    /// maTokens is burned and, if the collateral and debt assets differ, the underlying
+    /// collateral is swapped to the debt asset via Astroport before being repaid. The health
+    /// factor after the repayment must be at least one or not lower than before.
+    RepayWithCollateral {
+        /// Asset used as collateral to repay the debt with
+        collateral_asset: Asset,
+        /// Amount of collateral to use, withdraw fee included
+        collateral_amount: Uint128,
+        /// Asset of the debt to repay
+        debt_asset: Asset,
+        /// Max spread for the Astroport swap when the assets differ
+        max_spread: Option<StdDecimal>,
+    },
+
+    /// Authorize keepers to repay the caller's debt with their collateral once their health
+    /// factor drops below the authorization threshold. A `None` authorization removes it
+    SetAutoRepay {
+        authorization: Option<AutoRepayAuthorization>,
+    },
+
+    /// Repay a user's debt with their collateral as set in their auto-repay authorization.
+    /// Only allowed while the user health factor is below the authorization threshold. The
+    /// caller gets the config `auto_repay_keeper_fee` share of the collateral used, which is
+    /// deducted from the authorization `max_collateral_amount`
+    AutoRepay {
+        /// User whose debt is repaid
+        user_address: String,
+    },
+
+    /// Repay the proceeds of a repayment with collateral once they are available in the
+    /// contract (only callable by the contract itself)
+    RepayWithCollateralCallback {
+        /// User whose debt is repaid
+        user_address: String,
+        /// Asset of the debt to repay
+        debt_asset: Asset,
+        /// Amount known to be repaid before the callback is executed
+        repay_amount: Uint128,
+        /// Contract balance of the debt asset before the swap, any increase is also repaid
+        debt_asset_balance_before: Uint128,
+        /// User health factor before the repayment (None if not borrowing collateralized)
+        health_factor_before: Option<Decimal>,
+    },
+
+    /// Switch the caller's debt in the asset between the variable and the stable borrow rate.
+    /// When switching to stable, the rate is fixed to the current market borrow rate plus the
+    /// market stable borrow rate premium
+    SwapBorrowRateMode {
+        /// Asset of the debt
+        asset: Asset,
+    },
+
+    /// Reset the stable borrow rate of a user's debt to the current stable rate offered by the
+    /// market (anyone can call). Only allowed when either:
+    /// - the user's stable rate is below the market variable borrow rate,
+    /// - market utilization reached `stable_rate_rebalance_utilization_threshold` and the
+    ///   user's stable rate is below the market stable rate, or
+    /// - the user's stable rate differs from the market stable rate by more than
+    ///   `stable_rate_rebalance_max_divergence`
+    RebalanceStableBorrowRate {
+        /// Asset of the debt
+        asset: Asset,
+        /// User whose stable borrow rate is rebalanced
+        user_address: String,
+    },
+
     /// Liquidate under-collateralized native loans. Coins used to repay must be sent in the
     /// transaction this call is made.
     LiquidateNative {
@@ -135,6 +293,66 @@ pub enum ExecuteMsg {
         enable: bool,
     },
 
+    /// Sweep a maToken balance worth less than the dust threshold (only owner or the user can
+    /// call). The user must not be borrowing. If called by the user, the underlying asset is
+    /// withdrawn to them. If called by the owner, the maTokens are transferred to the protocol
+    /// rewards collector, where they get converted to uusd along with the rest of the
+    /// protocol rewards
+    SweepDust {
+        /// Asset to sweep the dust balance for
+        asset: Asset,
+        /// Address holding the dust balance
+        user_address: String,
+    },
+
+    /// Send the rounding dust accumulated by a native asset market to the treasury
+    /// (only owner can call)
+    SweepRoundingDust {
+        /// Denom of the native asset to sweep the rounding dust for
+        denom: String,
+    },
+
+    /// Send the liquidation fees accrued by a market to the treasury (only owner can call)
+    ClaimLiquidationFees {
+        /// Asset to claim the liquidation fees for
+        asset: Asset,
+    },
+
+    /// Correct the asset type of a market that was initialized with the wrong one. Only
+    /// allowed while the market has no deposits and no debt (only owner can call)
+    UpdateAssetType {
+        /// Asset of the market, with the asset type the market should have
+        asset: Asset,
+    },
+
+    /// Create or update an e-mode category. Category ids start at 1. The risk params of a
+    /// category can't be changed while users are opted in to it, and the category liquidation
+    /// threshold must satisfy `liquidation_threshold * (1 + liquidation_bonus) <= 1` for every
+    /// market in it (only owner can call)
+    UpdateEModeCategory {
+        category_id: u32,
+        category: EModeCategory,
+    },
+
+    /// Create or update an interest rate strategy shared by the markets referencing it.
+    /// Strategy ids start at 1 (only owner can call). Referencing markets use the new params
+    /// from their next interest rates update
+    UpdateInterestRateStrategy {
+        strategy_id: u32,
+        params: InterestRateModelParams,
+    },
+
+    /// Opt in to an e-mode category (0 to opt out). All the assets the caller is borrowing
+    /// must belong to the category and the health factor must stay above 1 after the change
+    SetUserEModeCategory { category_id: u32 },
+
+    /// Update indexes and interest rates of markets to the current block time
+    /// (only owner can call)
+    AccrueAllMarkets {
+        /// Markets to update. If not provided, all markets are updated
+        assets: Option<Vec<Asset>>,
+    },
+
     /// Called by liquidity token (maToken). Validate liquidity token transfer is valid
     /// and update collateral status
     FinalizeLiquidityTokenTransfer {
@@ -158,7 +376,8 @@ pub enum ExecuteMsg {
 pub enum ReceiveMsg {
     /// Deposit sent cw20 tokens
     DepositCw20 {
-        /// Deposit the funds for the user
+        /// Deposit the funds for the user. With a markets limit set, they are only used as
+        /// collateral once the user enables them if it wasn't using the asset
         on_behalf_of: Option<String>,
     },
     /// Repay sent cw20 tokens
@@ -178,21 +397,40 @@ pub enum ReceiveMsg {
     },
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct CreateOrUpdateConfig {
     pub owner: Option<String>,
     pub address_provider_address: Option<String>,
     pub ma_token_code_id: Option<u64>,
     pub close_factor: Option<Decimal>,
+    pub dust_threshold_in_uusd: Option<Uint128>,
+    pub min_collateral_value_in_uusd: Option<Uint128>,
+    pub borrow_health_factor_buffer: Option<Decimal>,
+    pub max_markets_per_user: Option<u32>,
+    pub max_price_deviation: Option<Decimal>,
+    pub price_deviation_window: Option<u64>,
+    pub astroport_factory_address: Option<String>,
+    /// Defaults to zero (no cooldown) on instantiation
+    pub update_cooldown_seconds: Option<u64>,
+    /// Defaults to no guardian on instantiation
+    pub guardian_address: Option<String>,
+    /// Defaults to zero on instantiation
+    pub auto_repay_keeper_fee: Option<Decimal>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 pub struct InitOrUpdateAssetParams {
     /// Initial borrow rate
     pub initial_borrow_rate: Option<Decimal>,
 
     /// Portion of the borrow rate that is kept as protocol rewards
     pub reserve_factor: Option<Decimal>,
+    /// Portion of each deposit that is kept as protocol rewards.
+    /// Defaults to 0 on market initialization
+    pub deposit_fee: Option<Decimal>,
+    /// Portion of each withdrawal that is kept as protocol rewards.
+    /// Defaults to 0 on market initialization
+    pub withdraw_fee: Option<Decimal>,
     /// Max uusd that can be borrowed per uusd of collateral when using the asset as collateral
     pub max_loan_to_value: Option<Decimal>,
     /// uusd amount in debt position per uusd of asset collateral that if surpassed makes the user's position liquidatable.
@@ -210,6 +448,65 @@ pub struct InitOrUpdateAssetParams {
     pub deposit_enabled: Option<bool>,
     /// If false cannot borrow
     pub borrow_enabled: Option<bool>,
+    /// Seconds a user has to wait after borrowing the asset before borrowing it again.
+    /// Defaults to 0 (no cooldown) on market initialization
+    pub borrow_cooldown_seconds: Option<u64>,
+    /// Max amount the borrow rate can move within a single block, at most 1.
+    /// Defaults to 0 (no limit) on market initialization
+    pub max_borrow_rate_change: Option<Decimal>,
+    /// Fraction of the borrow rate waived for users holding at least
+    /// `borrow_rate_discount_min_xmars` xMars, at most the reserve factor.
+    /// Defaults to 0 (no discount) on market initialization
+    pub borrow_rate_discount: Option<Decimal>,
+    /// Min xMars balance a user needs to get the borrow rate discount.
+    /// Defaults to 0 on market initialization
+    pub borrow_rate_discount_min_xmars: Option<Uint128>,
+    /// Whether users can borrow the asset at a stable borrow rate.
+    /// Defaults to false on market initialization
+    pub stable_borrow_enabled: Option<bool>,
+    /// Premium over the variable borrow rate charged to borrowers choosing a stable rate.
+    /// Defaults to 0 on market initialization
+    pub stable_borrow_rate_premium: Option<Decimal>,
+    /// Utilization rate from which debts with a stable rate below the market stable rate can
+    /// be rebalanced. Defaults to 0 (condition disabled) on market initialization
+    pub stable_rate_rebalance_utilization_threshold: Option<Decimal>,
+    /// Difference between a debt stable rate and the market stable rate from which the debt
+    /// can be rebalanced. Defaults to 0 (condition disabled) on market initialization
+    pub stable_rate_rebalance_max_divergence: Option<Decimal>,
+    /// Seconds after the market is deprecated during which the borrow rate stays frozen.
+    /// Defaults to 0 on market initialization
+    pub deprecation_grace_period_seconds: Option<u64>,
+    /// Min borrow rate charged once the deprecation grace period is over.
+    /// Defaults to 0 (model rate applies) on market initialization
+    pub deprecation_penalty_borrow_rate: Option<Decimal>,
+    /// Min rate paid to depositors, the difference with the model rate being paid from
+    /// protocol rewards, so it is capped at the reserve income.
+    /// Defaults to 0 (no floor) on market initialization
+    pub min_liquidity_rate: Option<Decimal>,
+    /// E-mode category the asset belongs to (0 means none). The category must exist.
+    /// Can't be changed while users are opted in to the previous or the new category.
+    /// Defaults to 0 on market initialization
+    pub emode_category_id: Option<u32>,
+    /// Max underlying debt of the asset that can be repaid on a single liquidation.
+    /// Defaults to 0 (no limit) on market initialization
+    pub max_liquidation_amount_per_tx: Option<Uint128>,
+    /// Shared interest rate strategy to follow instead of `interest_rate_model_params`
+    /// (0 means none). The strategy must exist. Defaults to 0 on market initialization
+    pub interest_rate_strategy_id: Option<u32>,
+    /// Max uusd value of the total debt of the asset, computed with the oracle price on each
+    /// borrow. Defaults to 0 (no ceiling) on market initialization
+    pub debt_ceiling_in_uusd: Option<Uint128>,
+    /// Portion of the liquidation bonus kept by the protocol as liquidation fees.
+    /// Defaults to 0 on market initialization
+    pub liquidation_protocol_fee: Option<Decimal>,
+    /// Part of the rounding dust of a native asset that is never swept.
+    /// Defaults to 0 on market initialization
+    pub rounding_reserve: Option<Uint128>,
+    /// Decimals of the asset. Defaults to 6 on market initialization
+    pub asset_decimals: Option<u8>,
+    /// Decimals of the asset the oracle price is quoted in. Defaults to 6 on market
+    /// initialization
+    pub price_decimals: Option<u8>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -218,11 +515,64 @@ pub enum QueryMsg {
     /// Get config
     Config {},
 
-    /// Get asset market
+    /// Get asset market. Returns MarketResponse
     Market { asset: Asset },
 
-    /// Get a list of all markets. Returns MarketsListResponse
-    MarketsList {},
+    /// Get the markets of the given assets, in the same order. Assets without a market are
+    /// returned with no market. Returns MarketsByAssetsResponse
+    MarketsByAssets { assets: Vec<Asset> },
+
+    /// Get whether a market exists for a denom (or cw20 contract address) and, if it does,
+    /// its asset type and maToken address. Returns AssetMarketInfoResponse
+    AssetMarketInfo { denom: String },
+
+    /// Get whether the maToken instantiated on market initialization has been registered,
+    /// which is needed for the market to be usable. Returns MarketInitializedResponse
+    MarketInitialized { asset: Asset },
+
+    /// Get asset market with interests accrued up to the current block time. Rates are the
+    /// ones stored in the market as they only change when liquidity or debt change
+    MarketAccrued { asset: Asset },
+
+    /// Get the asset market a maToken belongs to
+    MarketByMaToken { ma_token_address: String },
+
+    /// Get a list of all markets, or only the ones of the given asset type.
+    /// Returns MarketsListResponse
+    MarketsList { asset_type: Option<AssetType> },
+
+    /// Get the market count and the market index to asset mapping used in the user
+    /// collateral and debt bitmaps. Returns MarketIndexesResponse
+    MarketIndexes {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+
+    /// Get the underlying liquidity supplied to each market (with interests accrued up to the
+    /// current block time) and its value in uusd, paginated by market index.
+    /// Returns UnderlyingLiquidityResponse
+    SimulateUnderlyingLiquidity {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+
+    /// Get the assets held by each market (contract balance plus outstanding debt) against its
+    /// liabilities (underlying supplied by maToken holders), both with interests accrued up to
+    /// the current block time, and whether the protocol is solvent per market and in aggregate.
+    /// Returns SolvencyResponse
+    Solvency {},
+
+    /// Get the balance of an asset held by the contract against the balance expected from the
+    /// market accounting (with interests accrued up to the current block time), to detect
+    /// accounting drift or external donations. Returns ReserveBalanceResponse
+    ReserveBalance { asset: Asset },
+
+    /// Get protocol wide totals in uusd for a page of markets, paginated by market index.
+    /// Returns ProtocolStatsResponse
+    ProtocolStats {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
 
     /// Get uncollateralized limit for given asset and user.
     /// Returns UncollateralizedLoanLimitResponse
@@ -234,6 +584,14 @@ pub enum QueryMsg {
     /// Get user debt position for a specific asset. Returns UserAssetDebtResponse
     UserAssetDebt { user_address: String, asset: Asset },
 
+    /// Get the borrow rate a user pays on an asset once their borrow rate discount or stable
+    /// rate is taken into account. Returns UserBorrowRateResponse
+    UserBorrowRate { user_address: String, asset: Asset },
+
+    /// Get the raw debt entry stored for a user and asset. Fails if the user has no
+    /// debt entry for the asset. Returns DebtResponse
+    Debt { asset: Asset, user_address: String },
+
     /// Get info about whether or not user is using each asset as collateral.
     /// Returns UserCollateralResponse
     UserCollateral { user_address: String },
@@ -241,6 +599,57 @@ pub enum QueryMsg {
     /// Get user position. Returns UserPositionResponse
     UserPosition { user_address: String },
 
+    /// Get the share of the user borrowing power in use. Returns UserBorrowLimitUsageResponse
+    UserBorrowLimitUsage { user_address: String },
+
+    /// Get everything about a user position in a single query: markets the user is borrowing
+    /// or using as collateral with the underlying amounts, totals and health. Meant for support
+    /// and audit tooling. Returns UserPositionFullResponse
+    UserPositionFull { user_address: String },
+
+    /// Get user position before and after a hypothetical action, without executing it.
+    /// Returns UserPositionSimulationResponse
+    UserPositionSimulation {
+        user_address: String,
+        action: UserPositionAction,
+    },
+
+    /// Scan a page of users and get the ones that can be liquidated, meant to be used by
+    /// liquidation bots. `limit` bounds the number of users scanned, not the number of
+    /// positions returned. Returns LiquidatablePositionsResponse
+    LiquidatablePositions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Get the liquidation threshold applied to each of the user collateral positions and
+    /// the price under which the user becomes liquidatable.
+    /// Returns UserLiquidationThresholdsResponse
+    UserLiquidationThresholds { user_address: String },
+
+    /// Get an e-mode category. Returns EModeCategory
+    EModeCategory { category_id: u32 },
+
+    /// Get a shared interest rate strategy. Returns InterestRateModelParams
+    InterestRateStrategy { strategy_id: u32 },
+
+    /// Get all markets following a shared interest rate strategy (0 for markets using their
+    /// own interest rate model params). Returns MarketsListResponse
+    MarketsByInterestRateStrategy { strategy_id: u32 },
+
+    /// Get the e-mode category a user opted in to. Returns UserEModeCategoryResponse
+    UserEModeCategory { user_address: String },
+
+    /// Get the auto-repay authorization of a user. Returns Option<AutoRepayAuthorization>
+    UserAutoRepay { user_address: String },
+
+    /// Get the rounding dust accumulated by a native asset market that can be swept to the
+    /// treasury. Returns RoundingDustResponse
+    RoundingDust { denom: String },
+
+    /// Get the liquidation fees accrued by a market. Returns LiquidationFeesResponse
+    LiquidationFees { asset: Asset },
+
     /// Get liquidity scaled amount for a given underlying asset amount
     /// (i.e: how much maTokens will get minted if the given amount is deposited)
     ScaledLiquidityAmount { asset: Asset, amount: Uint128 },
@@ -263,3 +672,16 @@ pub enum QueryMsg {
         amount_scaled: Uint128,
     },
 }
+
+/// Action to simulate on a user position
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserPositionAction {
+    /// Deposit the amount of the asset (enables it as collateral)
+    Deposit { asset: Asset, amount: Uint128 },
+    /// Borrow the amount of the asset
+    Borrow { asset: Asset, amount: Uint128 },
+    /// Withdraw the amount of the asset (only affects the position if the asset is used
+    /// as collateral)
+    Withdraw { asset: Asset, amount: Uint128 },
+}
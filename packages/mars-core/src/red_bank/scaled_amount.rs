@@ -0,0 +1,171 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{StdResult, Uint128};
+
+use crate::math::decimal::Decimal;
+
+/// Scaling factor used to keep more precision during division / multiplication by index.
+pub const SCALING_FACTOR: Uint128 = Uint128::new(1_000_000);
+
+/// Amount scaled by a market index (e.g: maToken balances), as stored by the red bank
+#[derive(
+    Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
+pub struct ScaledAmount(Uint128);
+
+impl ScaledAmount {
+    pub const fn new(amount_scaled: Uint128) -> Self {
+        Self(amount_scaled)
+    }
+
+    /// Scales an underlying amount dividing it by the given index. Amount is multiplied by
+    /// SCALING_FACTOR before dividing for greater precision.
+    /// Result is truncated, so that rounding errors accumulate in favor of the protocol
+    /// (same rounding the red bank uses for liquidity amounts)
+    pub fn from_underlying(amount: Uint128, index: Decimal) -> StdResult<Self> {
+        let amount = amount.checked_mul(SCALING_FACTOR)?;
+        Ok(Self(Decimal::divide_uint128_by_decimal(amount, index)?))
+    }
+
+    /// Returns the underlying amount multiplying the scaled amount by the given index and
+    /// removing the SCALING_FACTOR.
+    /// Result is truncated, so `from_underlying(amount, index)?.to_underlying(index)?` can
+    /// be lower than `amount` by at most `index / SCALING_FACTOR + 2`, and is never higher
+    pub fn to_underlying(&self, index: Decimal) -> StdResult<Uint128> {
+        let amount = Decimal::multiply_uint128_by_decimal(self.0, index)?;
+        Ok(amount.checked_div(SCALING_FACTOR)?)
+    }
+
+    pub fn amount(&self) -> Uint128 {
+        self.0
+    }
+}
+
+impl From<Uint128> for ScaledAmount {
+    fn from(amount_scaled: Uint128) -> Self {
+        Self(amount_scaled)
+    }
+}
+
+impl From<ScaledAmount> for Uint128 {
+    fn from(scaled_amount: ScaledAmount) -> Self {
+        scaled_amount.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaled_amount_from_underlying() {
+        let scaled_amount = ScaledAmount::from_underlying(
+            Uint128::new(6_123_456),
+            Decimal::from_ratio(10u128, 1u128),
+        )
+        .unwrap();
+        assert_eq!(scaled_amount.amount(), Uint128::new(612_345_600_000));
+
+        // result is truncated
+        let scaled_amount =
+            ScaledAmount::from_underlying(Uint128::new(1), Decimal::from_ratio(3u128, 1u128))
+                .unwrap();
+        assert_eq!(scaled_amount.amount(), Uint128::new(333_333));
+
+        // zero index
+        ScaledAmount::from_underlying(Uint128::new(1), Decimal::zero()).unwrap_err();
+
+        // overflow when scaling
+        ScaledAmount::from_underlying(Uint128::MAX, Decimal::one()).unwrap_err();
+    }
+
+    #[test]
+    fn test_scaled_amount_to_underlying() {
+        let scaled_amount = ScaledAmount::new(Uint128::new(612_345_600_000));
+        assert_eq!(
+            scaled_amount
+                .to_underlying(Decimal::from_ratio(10u128, 1u128))
+                .unwrap(),
+            Uint128::new(6_123_456)
+        );
+
+        // result is truncated
+        let scaled_amount = ScaledAmount::new(Uint128::new(333_333));
+        assert_eq!(
+            scaled_amount
+                .to_underlying(Decimal::from_ratio(3u128, 1u128))
+                .unwrap(),
+            Uint128::zero()
+        );
+
+        assert_eq!(
+            Uint128::from(ScaledAmount::from(Uint128::new(42))),
+            Uint128::new(42)
+        );
+
+        // overflow when multiplying by the index
+        ScaledAmount::new(Uint128::MAX)
+            .to_underlying(Decimal::from_ratio(2u128, 1u128))
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_scaled_amount_round_trip() {
+        let amounts: Vec<u128> = vec![
+            0,
+            1,
+            2,
+            7,
+            999,
+            1_000_000,
+            6_123_456,
+            123_456_789_012,
+            987_654_321_987_654_321,
+            u128::MAX / 1_000_000_000_000,
+        ];
+        let indexes = vec![
+            Decimal::from_ratio(1u128, 3u128),
+            Decimal::from_ratio(999_999u128, 1_000_000u128),
+            Decimal::one(),
+            Decimal::from_ratio(1_000_001u128, 1_000_000u128),
+            Decimal::from_ratio(3u128, 2u128),
+            Decimal::from_ratio(22u128, 7u128),
+            Decimal::from_ratio(10u128, 1u128),
+            Decimal::from_ratio(123_456_789u128, 1_000u128),
+            Decimal::from_ratio(1_000_000u128, 1u128),
+            Decimal::from_ratio(7_654_321u128, 1u128),
+        ];
+
+        for amount in amounts {
+            let amount = Uint128::new(amount);
+            for index in &indexes {
+                let round_trip = ScaledAmount::from_underlying(amount, *index)
+                    .unwrap()
+                    .to_underlying(*index)
+                    .unwrap();
+
+                let tolerance = (Uint128::new(1) * *index)
+                    .checked_div(SCALING_FACTOR)
+                    .unwrap()
+                    + Uint128::new(2);
+
+                assert!(
+                    round_trip <= amount,
+                    "round trip of {} with index {} returned more: {}",
+                    amount,
+                    index,
+                    round_trip
+                );
+                assert!(
+                    amount - round_trip <= tolerance,
+                    "round trip of {} with index {} lost more than {}: {}",
+                    amount,
+                    index,
+                    tolerance,
+                    round_trip
+                );
+            }
+        }
+    }
+}
@@ -55,6 +55,24 @@ pub struct ClaimResponse {
     pub claim: Option<Claim>,
 }
 
+/// Unstaked Mars waiting for the cooldown to end
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingClaim {
+    /// Timestamp (in seconds) after which the claim is unlocked
+    pub cooldown_end_timestamp: u64,
+    /// Seconds left until the claim is unlocked (0 if it can already be claimed)
+    pub seconds_until_unlocked: u64,
+    /// Amount of Mars to be received, after applying slash events
+    pub amount: Uint128,
+}
+
+/// Response to PendingClaims query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingClaimsResponse {
+    /// Open claims of the user sorted by cooldown end
+    pub claims: Vec<PendingClaim>,
+}
+
 pub mod msg {
     use cosmwasm_std::{Decimal as StdDecimal, Uint128};
 
@@ -130,5 +148,8 @@ pub mod msg {
         /// Get open claim for given user. If claim exists, slash events are applied to the amount
         /// so actual amount of Mars received is given.
         Claim { user_address: String },
+        /// Get the unstaked Mars of a user waiting to be claimed, sorted by cooldown end, with
+        /// slash events applied to the amounts. Returns PendingClaimsResponse
+        PendingClaims { user_address: String },
     }
 }
@@ -0,0 +1,103 @@
+use cosmwasm_std::{Order, Pair, StdResult, Storage};
+use cw_storage_plus::{Bound, Prefix};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Limit used in paginated queries when none is given
+pub const PAGINATION_DEFAULT_LIMIT: u32 = 10;
+/// Maximum limit that can be used in paginated queries
+pub const PAGINATION_MAX_LIMIT: u32 = 30;
+
+/// Returns the pagination limit to use given an optional limit sent in a query
+pub fn get_pagination_limit(option_limit: Option<u32>) -> usize {
+    option_limit
+        .unwrap_or(PAGINATION_DEFAULT_LIMIT)
+        .min(PAGINATION_MAX_LIMIT) as usize
+}
+
+/// Returns a page of (raw key, value) pairs of a map prefix in ascending order, starting at
+/// the given bound. For maps with simple keys, use `map.prefix(())` to iterate all entries.
+pub fn paginate_prefix<T>(
+    prefix: &Prefix<T>,
+    storage: &dyn Storage,
+    start: Option<Bound>,
+    option_limit: Option<u32>,
+) -> StdResult<Vec<Pair<T>>>
+where
+    T: Serialize + DeserializeOwned,
+{
+    prefix
+        .range(storage, start, None, Order::Ascending)
+        .take(get_pagination_limit(option_limit))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+    use cw_storage_plus::{Map, U64Key};
+
+    const ITEMS: Map<U64Key, u64> = Map::new("items");
+
+    fn th_save_items(storage: &mut dyn Storage, count: u64) {
+        for i in 0..count {
+            ITEMS.save(storage, U64Key::new(i), &(i * 10)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_get_pagination_limit() {
+        assert_eq!(get_pagination_limit(None), 10);
+        assert_eq!(get_pagination_limit(Some(5)), 5);
+        assert_eq!(get_pagination_limit(Some(100)), 30);
+    }
+
+    #[test]
+    fn test_paginate_prefix_empty() {
+        let storage = MockStorage::new();
+        let page = paginate_prefix(&ITEMS.prefix(()), &storage, None, None).unwrap();
+        assert!(page.is_empty());
+    }
+
+    #[test]
+    fn test_paginate_prefix_partial_page() {
+        let mut storage = MockStorage::new();
+        th_save_items(&mut storage, 5);
+
+        let page = paginate_prefix(&ITEMS.prefix(()), &storage, None, None).unwrap();
+        let values: Vec<u64> = page.into_iter().map(|(_, v)| v).collect();
+        assert_eq!(values, vec![0, 10, 20, 30, 40]);
+
+        // start after last item
+        let start = Some(Bound::exclusive(U64Key::new(4)));
+        let page = paginate_prefix(&ITEMS.prefix(()), &storage, start, None).unwrap();
+        assert!(page.is_empty());
+
+        // start at last item
+        let start = Some(Bound::inclusive(U64Key::new(4)));
+        let page = paginate_prefix(&ITEMS.prefix(()), &storage, start, None).unwrap();
+        assert_eq!(page, vec![(4_u64.to_be_bytes().to_vec(), 40)]);
+    }
+
+    #[test]
+    fn test_paginate_prefix_full_pages() {
+        let mut storage = MockStorage::new();
+        th_save_items(&mut storage, 50);
+
+        // default limit
+        let page = paginate_prefix(&ITEMS.prefix(()), &storage, None, None).unwrap();
+        assert_eq!(page.len(), 10);
+        assert_eq!(page.last().unwrap().1, 90);
+
+        // next page starts after the last key of the previous one
+        let start = Some(Bound::exclusive(U64Key::new(9)));
+        let page = paginate_prefix(&ITEMS.prefix(()), &storage, start, Some(3)).unwrap();
+        let values: Vec<u64> = page.into_iter().map(|(_, v)| v).collect();
+        assert_eq!(values, vec![100, 110, 120]);
+
+        // limit higher than max is capped
+        let page = paginate_prefix(&ITEMS.prefix(()), &storage, None, Some(100)).unwrap();
+        assert_eq!(page.len(), 30);
+    }
+}
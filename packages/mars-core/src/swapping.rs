@@ -1,7 +1,9 @@
 use crate::helpers::cw20_get_balance;
 use astroport::{
     asset::{Asset as AstroportAsset, AssetInfo, PairInfo},
-    pair::ExecuteMsg as AstroportPairExecuteMsg,
+    pair::{
+        ExecuteMsg as AstroportPairExecuteMsg, PoolResponse, QueryMsg as AstroportPairQueryMsg,
+    },
     querier::query_pair_info,
 };
 use cosmwasm_std::{
@@ -9,6 +11,19 @@ use cosmwasm_std::{
     StdError, StdResult, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How to handle a swap that can't be fully filled within the max spread
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapFillMode {
+    /// Swap the whole amount, failing if the spread exceeds the max spread
+    Full,
+    /// Swap as much of the amount as the pool can take within the max spread, leaving the rest
+    /// on the contract
+    Partial,
+}
 
 /// Swap assets via Astroport
 pub fn execute_swap(
@@ -20,6 +35,33 @@ pub fn execute_swap(
     astroport_factory_addr: Addr,
     astroport_max_spread: Option<StdDecimal>,
 ) -> StdResult<Response> {
+    let (response, _) = execute_swap_with_fill_mode(
+        deps,
+        env,
+        offer_asset_info,
+        ask_asset_info,
+        amount,
+        astroport_factory_addr,
+        astroport_max_spread,
+        SwapFillMode::Full,
+    )?;
+    Ok(response)
+}
+
+/// Swap assets via Astroport. On partial fill mode the amount offered is capped so the spread
+/// of the swap does not exceed the max spread (assuming a constant product pool). Returns the
+/// response and the amount that is left unswapped
+#[allow(clippy::too_many_arguments)]
+pub fn execute_swap_with_fill_mode(
+    deps: DepsMut,
+    env: Env,
+    offer_asset_info: AssetInfo,
+    ask_asset_info: AssetInfo,
+    amount: Option<Uint128>,
+    astroport_factory_addr: Addr,
+    astroport_max_spread: Option<StdDecimal>,
+    fill_mode: SwapFillMode,
+) -> StdResult<(Response, Uint128)> {
     // Having the same asset as offer and ask asset doesn't make any sense
     if offer_asset_info == ask_asset_info {
         return Err(StdError::generic_err(format!(
@@ -76,26 +118,72 @@ pub fn execute_swap(
         astroport_factory_addr,
         &[offer_asset_info.clone(), ask_asset_info],
     )?;
+    let pair_address = deps
+        .api
+        .addr_validate(&pair_info.contract_addr.to_string())?;
+
+    let (amount_to_swap, leftover_amount) = match fill_mode {
+        SwapFillMode::Full => (amount_to_swap, Uint128::zero()),
+        SwapFillMode::Partial => {
+            let max_spread = astroport_max_spread
+                .ok_or_else(|| StdError::generic_err("Partial fill swaps require a max spread"))?;
+            let pool: PoolResponse = deps
+                .querier
+                .query_wasm_smart(pair_address.clone(), &AstroportPairQueryMsg::Pool {})?;
+            let offer_pool_amount = pool
+                .assets
+                .iter()
+                .find(|asset| asset.info == offer_asset_info)
+                .map(|asset| asset.amount)
+                .unwrap_or_else(Uint128::zero);
+
+            let filled_amount = amount_to_swap.min(get_max_offer_amount_within_spread(
+                offer_pool_amount,
+                max_spread,
+            ));
+            if filled_amount.is_zero() {
+                return Err(StdError::generic_err(format!(
+                    "Pool liquidity is too low to swap any {} within the max spread",
+                    offer_asset_label
+                )));
+            }
+            (filled_amount, amount_to_swap - filled_amount)
+        }
+    };
 
     let offer_asset = AstroportAsset {
         info: offer_asset_info,
         amount: amount_to_swap,
     };
-    let send_msg = asset_into_swap_msg(
-        deps.api
-            .addr_validate(&pair_info.contract_addr.to_string())?,
-        offer_asset,
-        astroport_max_spread,
-    )?;
+    let send_msg = asset_into_swap_msg(pair_address, offer_asset, astroport_max_spread)?;
 
-    let response = Response::new().add_message(send_msg).add_attributes(vec![
+    let mut response = Response::new().add_message(send_msg).add_attributes(vec![
         attr("action", "swap"),
         attr("offer_asset", offer_asset_label),
         attr("ask_asset", ask_asset_label),
         attr("offer_asset_amount", amount_to_swap),
     ]);
+    if fill_mode == SwapFillMode::Partial {
+        response = response.add_attribute("offer_asset_leftover", leftover_amount);
+    }
 
-    Ok(response)
+    Ok((response, leftover_amount))
+}
+
+/// Max amount that can be offered to a constant product pool for the spread to stay within
+/// `max_spread`. The spread of offering `x` to a pool with `X` of the offer asset is
+/// `x / (X + x)`, so the max offer is `X * max_spread / (1 - max_spread)`
+fn get_max_offer_amount_within_spread(
+    offer_pool_amount: Uint128,
+    max_spread: StdDecimal,
+) -> Uint128 {
+    if max_spread >= StdDecimal::one() {
+        return Uint128::MAX;
+    }
+    offer_pool_amount.multiply_ratio(
+        max_spread * Uint128::new(1_000_000_000_000_000_000),
+        (StdDecimal::one() - max_spread) * Uint128::new(1_000_000_000_000_000_000),
+    )
 }
 
 /// Construct Astroport message in order to swap assets
@@ -382,4 +470,175 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_swap_with_partial_fill_mode() {
+        let contract_asset_balance = Uint128::new(5_000);
+        let mut deps = mock_dependencies(&[Coin {
+            denom: "uluna".to_string(),
+            amount: contract_asset_balance,
+        }]);
+        let env = mock_env(MockEnvParams::default());
+
+        let offer_asset_info = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ask_asset_info = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        let max_spread = StdDecimal::from_ratio(1u128, 100u128);
+
+        deps.querier.set_astroport_pair(PairInfo {
+            asset_infos: [offer_asset_info.clone(), ask_asset_info.clone()],
+            contract_addr: Addr::unchecked("pair_uluna_uusd"),
+            liquidity_token: Addr::unchecked("lp_uluna_uusd"),
+            pair_type: PairType::Xyk {},
+        });
+
+        // partial fill mode requires a max spread
+        let response = execute_swap_with_fill_mode(
+            deps.as_mut(),
+            env.clone(),
+            offer_asset_info.clone(),
+            ask_asset_info.clone(),
+            None,
+            Addr::unchecked("astroport_factory"),
+            None,
+            SwapFillMode::Partial,
+        );
+        assert_generic_error_message(response, "Partial fill swaps require a max spread");
+
+        // a pool without liquidity can't fill anything
+        let response = execute_swap_with_fill_mode(
+            deps.as_mut(),
+            env.clone(),
+            offer_asset_info.clone(),
+            ask_asset_info.clone(),
+            None,
+            Addr::unchecked("astroport_factory"),
+            Some(max_spread),
+            SwapFillMode::Partial,
+        );
+        assert_generic_error_message(
+            response,
+            "Pool liquidity is too low to swap any uluna within the max spread",
+        );
+
+        // with 99_000 uluna on the pool, offering 1_000 uluna gives a 1% spread
+        deps.querier.set_astroport_pair_pool(
+            "pair_uluna_uusd".to_string(),
+            PoolResponse {
+                assets: [
+                    AstroportAsset {
+                        info: offer_asset_info.clone(),
+                        amount: Uint128::new(99_000),
+                    },
+                    AstroportAsset {
+                        info: ask_asset_info.clone(),
+                        amount: Uint128::new(990_000),
+                    },
+                ],
+                total_share: Uint128::new(100_000),
+            },
+        );
+
+        let swap_msg = |amount: Uint128| {
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from("pair_uluna_uusd"),
+                msg: to_binary(&AstroportPairExecuteMsg::Swap {
+                    offer_asset: AstroportAsset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        amount,
+                    },
+                    belief_price: None,
+                    max_spread: Some(max_spread),
+                    to: None,
+                })
+                .unwrap(),
+                funds: vec![Coin {
+                    denom: "uluna".to_string(),
+                    amount,
+                }],
+            }))
+        };
+
+        // amount within the max spread is fully filled
+        let (res, leftover) = execute_swap_with_fill_mode(
+            deps.as_mut(),
+            env.clone(),
+            offer_asset_info.clone(),
+            ask_asset_info.clone(),
+            Some(Uint128::new(800)),
+            Addr::unchecked("astroport_factory"),
+            Some(max_spread),
+            SwapFillMode::Partial,
+        )
+        .unwrap();
+
+        assert_eq!(leftover, Uint128::zero());
+        assert_eq!(res.messages, vec![swap_msg(Uint128::new(800))]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "swap"),
+                attr("offer_asset", "uluna"),
+                attr("ask_asset", "uusd"),
+                attr("offer_asset_amount", "800"),
+                attr("offer_asset_leftover", "0"),
+            ]
+        );
+
+        // amount above the max spread is partially filled
+        let (res, leftover) = execute_swap_with_fill_mode(
+            deps.as_mut(),
+            env.clone(),
+            offer_asset_info.clone(),
+            ask_asset_info.clone(),
+            None,
+            Addr::unchecked("astroport_factory"),
+            Some(max_spread),
+            SwapFillMode::Partial,
+        )
+        .unwrap();
+
+        assert_eq!(leftover, Uint128::new(4_000));
+        assert_eq!(res.messages, vec![swap_msg(Uint128::new(1_000))]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "swap"),
+                attr("offer_asset", "uluna"),
+                attr("ask_asset", "uusd"),
+                attr("offer_asset_amount", "1000"),
+                attr("offer_asset_leftover", "4000"),
+            ]
+        );
+
+        // full fill mode offers the whole amount
+        let (res, leftover) = execute_swap_with_fill_mode(
+            deps.as_mut(),
+            env,
+            offer_asset_info,
+            ask_asset_info,
+            None,
+            Addr::unchecked("astroport_factory"),
+            Some(max_spread),
+            SwapFillMode::Full,
+        )
+        .unwrap();
+
+        assert_eq!(leftover, Uint128::zero());
+        assert_eq!(res.messages, vec![swap_msg(contract_asset_balance)]);
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "swap"),
+                attr("offer_asset", "uluna"),
+                attr("ask_asset", "uusd"),
+                attr("offer_asset_amount", "5000"),
+            ]
+        );
+    }
 }
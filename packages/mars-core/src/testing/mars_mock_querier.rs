@@ -40,6 +40,7 @@ pub struct MarsMockQuerier {
     staking_querier: StakingQuerier,
     vesting_querier: VestingQuerier,
     incentives_querier: IncentivesQuerier,
+    unset_address_provider_contracts: Vec<address_provider::MarsContract>,
 }
 
 impl Querier for MarsMockQuerier {
@@ -72,6 +73,7 @@ impl MarsMockQuerier {
             staking_querier: StakingQuerier::default(),
             vesting_querier: VestingQuerier::default(),
             incentives_querier: IncentivesQuerier::default(),
+            unset_address_provider_contracts: vec![],
         }
     }
 
@@ -207,6 +209,12 @@ impl MarsMockQuerier {
         self.astroport_pair_querier.pairs.insert(key, pool_response);
     }
 
+    pub fn set_astroport_pair_pool(&mut self, contract_addr: String, pool: PoolResponse) {
+        self.astroport_pair_querier
+            .pairs
+            .insert(contract_addr, pool);
+    }
+
     pub fn set_astroport_pair_cumulative_prices(
         &mut self,
         contract_addr: String,
@@ -227,6 +235,11 @@ impl MarsMockQuerier {
             .insert(contract_addr, simulation);
     }
 
+    /// Make the address provider return an empty address for the given contract
+    pub fn unset_address_provider_address(&mut self, contract: address_provider::MarsContract) {
+        self.unset_address_provider_contracts.push(contract);
+    }
+
     pub fn set_incentives_address(&mut self, address: Addr) {
         self.incentives_querier.incentives_address = address;
     }
@@ -275,6 +288,7 @@ impl MarsMockQuerier {
                     return mock_address_provider::handle_query(
                         &contract_addr,
                         address_provider_query,
+                        &self.unset_address_provider_contracts,
                     );
                 }
 
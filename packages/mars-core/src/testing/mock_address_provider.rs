@@ -4,9 +4,14 @@ use crate::address_provider::msg::QueryMsg;
 use crate::address_provider::MarsContract;
 
 // NOTE: Addresses here are all hardcoded as we always use those to target a specific contract
-// in tests. This module implicitly supposes those are used.
-
-pub fn handle_query(contract_addr: &Addr, query: QueryMsg) -> QuerierResult {
+// in tests. This module implicitly supposes those are used. Contracts in `unset_contracts` get
+// an empty address as returned by an address provider that has not been configured for them.
+
+pub fn handle_query(
+    contract_addr: &Addr,
+    query: QueryMsg,
+    unset_contracts: &[MarsContract],
+) -> QuerierResult {
     let address_provider = Addr::unchecked("address_provider");
     if *contract_addr != address_provider {
         panic!(
@@ -16,12 +21,14 @@ pub fn handle_query(contract_addr: &Addr, query: QueryMsg) -> QuerierResult {
     }
 
     let ret: ContractResult<Binary> = match query {
-        QueryMsg::Address { contract } => to_binary(&get_contract_address(contract)).into(),
+        QueryMsg::Address { contract } => {
+            to_binary(&get_contract_address(contract, unset_contracts)).into()
+        }
 
         QueryMsg::Addresses { contracts } => {
             let addresses = contracts
                 .into_iter()
-                .map(get_contract_address)
+                .map(|contract| get_contract_address(contract, unset_contracts))
                 .collect::<Vec<_>>();
             to_binary(&addresses).into()
         }
@@ -32,7 +39,11 @@ pub fn handle_query(contract_addr: &Addr, query: QueryMsg) -> QuerierResult {
     Ok(ret).into()
 }
 
-fn get_contract_address(contract: MarsContract) -> Addr {
+fn get_contract_address(contract: MarsContract, unset_contracts: &[MarsContract]) -> Addr {
+    if unset_contracts.contains(&contract) {
+        return Addr::unchecked("");
+    }
+
     match contract {
         MarsContract::Council => Addr::unchecked("council"),
         MarsContract::Incentives => Addr::unchecked("incentives"),
@@ -1,38 +1,165 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Uint128};
 
 /// Treasury global configuration
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: Addr,
+    /// Number of blocks after which spending limits are reset
+    pub spending_limit_epoch_blocks: u64,
+    /// Number of blocks spending limit loosenings and spending limit epoch changes have to wait
+    /// before they can be applied
+    pub spending_limit_update_delay_blocks: u64,
+}
+
+/// Max amount of an asset the treasury can send on each epoch
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendingLimit {
+    /// Max amount that can be spent on an epoch
+    pub limit: Uint128,
+    /// Amount spent on the epoch
+    pub spent: Uint128,
+    /// Epoch where `spent` was last updated
+    pub epoch: u64,
+}
+
+impl SpendingLimit {
+    /// Amount spent on the given epoch (resets to zero when a new epoch is reached)
+    pub fn spent_at(&self, epoch: u64) -> Uint128 {
+        if epoch == self.epoch {
+            self.spent
+        } else {
+            Uint128::zero()
+        }
+    }
+}
+
+/// Spending limit loosening waiting for the update delay to pass
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingSpendingLimit {
+    /// New max amount that can be spent on an epoch (None removes the limit)
+    pub limit: Option<Uint128>,
+    /// Block from which the update can be applied
+    pub applicable_at_block: u64,
+}
+
+/// Spending limit config update waiting for the update delay to pass
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingConfigUpdate {
+    pub spending_limit_epoch_blocks: u64,
+    pub spending_limit_update_delay_blocks: u64,
+    /// Block from which the update can be applied
+    pub applicable_at_block: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendingLimitResponse {
+    /// Max amount that can be spent on an epoch
+    pub limit: Uint128,
+    /// Amount spent on the current epoch
+    pub spent: Uint128,
+    /// Amount that can still be spent on the current epoch
+    pub remaining: Uint128,
+    /// Current epoch
+    pub epoch: u64,
+    /// Loosening of the limit waiting for the update delay to pass, if any
+    pub pending_update: Option<PendingSpendingLimit>,
 }
 
 pub mod msg {
     use schemars::JsonSchema;
     use serde::{Deserialize, Serialize};
 
-    use cosmwasm_std::CosmosMsg;
+    use cosmwasm_std::{CosmosMsg, Uint128};
+
+    use crate::asset::Asset;
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     pub struct InstantiateMsg {
         pub owner: String,
+        /// Number of blocks after which spending limits are reset
+        pub spending_limit_epoch_blocks: u64,
+        /// Number of blocks spending limit loosenings and spending limit epoch changes have to
+        /// wait before they can be applied
+        pub spending_limit_update_delay_blocks: u64,
     }
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     #[serde(rename_all = "snake_case")]
     pub enum ExecuteMsg {
-        /// Execute Cosmos msg
+        /// Execute Cosmos msg. Only bank sends and burns, cw20 transfers, sends, burns and
+        /// allowance changes, and contract instantiations are allowed. Sent coins and cw20
+        /// tokens count towards the spending limit of the asset
         ExecuteCosmosMsg(CosmosMsg),
 
-        /// Update contract config (only callable by owner)
-        UpdateConfig { owner: Option<String> },
+        /// Update contract config (only callable by owner). The owner is updated right away.
+        /// Spending limit epoch changes and update delay decreases are scheduled to be applied
+        /// with `ApplyConfigUpdate` once the current update delay has passed
+        UpdateConfig {
+            owner: Option<String>,
+            spending_limit_epoch_blocks: Option<u64>,
+            spending_limit_update_delay_blocks: Option<u64>,
+        },
+
+        /// Apply the scheduled config update once its delay has passed
+        ApplyConfigUpdate {},
+
+        /// Set the max amount of an asset that can be spent on each epoch. A `None` limit
+        /// removes it (only callable by owner). Tighter limits apply right away and cancel any
+        /// pending loosening, while raising or removing a limit is scheduled to be applied with
+        /// `ApplySpendingLimit` once the update delay has passed
+        SetSpendingLimit {
+            asset: Asset,
+            limit: Option<Uint128>,
+        },
+
+        /// Apply the scheduled loosening of an asset spending limit once its delay has passed
+        ApplySpendingLimit { asset: Asset },
     }
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     #[serde(rename_all = "snake_case")]
     pub enum QueryMsg {
         Config {},
+        /// Get spending limit of an asset on the current epoch. Returns SpendingLimitResponse
+        SpendingLimit {
+            asset: Asset,
+        },
+        /// Get the scheduled config update. Returns Option<PendingConfigUpdate>
+        PendingConfigUpdate {},
+    }
+}
+
+pub mod error {
+    use cosmwasm_std::{StdError, Uint128};
+    use thiserror::Error;
+
+    use crate::error::MarsError;
+
+    #[derive(Error, Debug, PartialEq)]
+    pub enum ContractError {
+        #[error("{0}")]
+        Std(#[from] StdError),
+
+        #[error("{0}")]
+        Mars(#[from] MarsError),
+
+        #[error("Spending limit exceeded for {asset}: remaining {remaining} on this epoch, tried to spend {amount}")]
+        SpendingLimitExceeded {
+            asset: String,
+            remaining: Uint128,
+            amount: Uint128,
+        },
+
+        #[error("Cosmos msg not supported as it could move funds without counting towards the spending limits")]
+        UnsupportedCosmosMsg {},
+
+        #[error("No pending update")]
+        NoPendingUpdate {},
+
+        #[error("Update can only be applied from block {applicable_at_block}")]
+        UpdateDelayNotPassed { applicable_at_block: u64 },
     }
 }
@@ -202,6 +202,8 @@ async function main() {
   console.log("Deploying Protocol Rewards Collector...")
   deployConfig.protocolRewardsCollectorInitMsg.config.owner = wallet.key.accAddress
   deployConfig.protocolRewardsCollectorInitMsg.config.address_provider_address = addressProviderContractAddress
+  deployConfig.protocolRewardsCollectorInitMsg.config.fee_recipients[0].address = safetyFundContractAddress
+  deployConfig.protocolRewardsCollectorInitMsg.config.fee_recipients[1].address = treasuryContractAddress
   const protocolRewardsCollectorContractAddress = await deployContract(
     terra,
     wallet,
@@ -3,6 +3,8 @@ export const testnet: Config = {
     "config": {
       "address_provider_address": undefined,
       "proposal_voting_period": 80, // 20 blocks = ~2.5 minutes (for internal testing) // 57600 blocks = ~5 days
+      "proposal_min_voting_period": 20,
+      "proposal_max_voting_period": 100000,
       "proposal_effective_delay": 0, // 0 blocks = able to execute proposal immediately (for internal testing) // 11520 blocks = ~24 hours
       "proposal_expiration_period": 115200, // 115200 blocks = ~10 days
       "proposal_required_deposit": "100000000",
@@ -35,13 +37,18 @@ export const testnet: Config = {
     "owner": undefined,
     "astroport_factory_address": "terra1q5fku2rf8mcdjz4ud9rsjf2srcd9mhz2d7mwxw",
     "astroport_max_spread": "0.05",
+    "spending_limit_epoch_blocks": 100800,
+    "spending_limit_update_delay_blocks": 14400,
   },
   protocolRewardsCollectorInitMsg: {
     "config": {
       "owner": undefined,
       "address_provider_address": undefined,
-      "safety_fund_fee_share": "0.1",
-      "treasury_fee_share": "0.2",
+      // Addresses are set on deployment: [safety fund, treasury]
+      "fee_recipients": [
+        { "address": undefined, "fee_share": "0.1" },
+        { "address": undefined, "fee_share": "0.2" },
+      ],
       "astroport_factory_address": "terra1q5fku2rf8mcdjz4ud9rsjf2srcd9mhz2d7mwxw",
       "astroport_max_spread": "0.05",
     }
@@ -51,7 +58,15 @@ export const testnet: Config = {
       "owner": undefined,
       "address_provider_address": undefined,
       "ma_token_code_id": undefined,
-      "close_factor": "0.5"
+      "close_factor": "0.5",
+      "dust_threshold_in_uusd": "1000000",
+      "min_collateral_value_in_uusd": "10000000",
+      "borrow_health_factor_buffer": "0.05",
+      "max_markets_per_user": 20,
+      "max_price_deviation": "0.2",
+      "price_deviation_window": 600,
+      "astroport_factory_address": "terra1q5fku2rf8mcdjz4ud9rsjf2srcd9mhz2d7mwxw",
+      "update_cooldown_seconds": 0, // 0 = no cooldown between updates (for internal testing) // 86400 = 24 hours
     }
   },
   initialAssets: [
@@ -227,6 +242,8 @@ export const local: Config = {
       "address_provider_address": undefined,
 
       "proposal_voting_period": 1000,
+      "proposal_min_voting_period": 100,
+      "proposal_max_voting_period": 10000,
       "proposal_effective_delay": 150,
       "proposal_expiration_period": 3000,
       "proposal_required_deposit": "100000000",
@@ -261,13 +278,18 @@ export const local: Config = {
     "owner": undefined,
     "astroport_factory_address": undefined,
     "astroport_max_spread": "0.05",
+    "spending_limit_epoch_blocks": 100800,
+    "spending_limit_update_delay_blocks": 14400,
   },
   protocolRewardsCollectorInitMsg: {
     "config": {
       "owner": undefined,
       "address_provider_address": undefined,
-      "safety_fund_fee_share": "0.1",
-      "treasury_fee_share": "0.2",
+      // Addresses are set on deployment: [safety fund, treasury]
+      "fee_recipients": [
+        { "address": undefined, "fee_share": "0.1" },
+        { "address": undefined, "fee_share": "0.2" },
+      ],
       "astroport_factory_address": undefined,
       "astroport_max_spread": "0.05",
     }
@@ -277,7 +299,15 @@ export const local: Config = {
       "owner": undefined,
       "address_provider_address": undefined,
       "ma_token_code_id": undefined,
-      "close_factor": "0.5"
+      "close_factor": "0.5",
+      "dust_threshold_in_uusd": "1000000",
+      "min_collateral_value_in_uusd": "10000000",
+      "borrow_health_factor_buffer": "0.05",
+      "max_markets_per_user": 20,
+      "max_price_deviation": "0.2",
+      "price_deviation_window": 600,
+      "astroport_factory_address": undefined,
+      "update_cooldown_seconds": 0,
     }
   },
   initialAssets: [],
@@ -93,9 +93,10 @@ export class Logger {
   }
 }
 
-export async function createTransaction(wallet: Wallet, msg: Msg) {
+// Several msgs can be passed to execute them atomically in a single transaction
+export async function createTransaction(wallet: Wallet, msg: Msg | Msg[]) {
   return await wallet.createTx({
-    msgs: [msg],
+    msgs: Array.isArray(msg) ? msg : [msg],
     gasAdjustment: GAS_ADJUSTMENT,
   })
 }
@@ -106,7 +107,7 @@ export async function broadcastTransaction(terra: LCDClient, signedTx: Tx) {
   return result
 }
 
-export async function performTransaction(terra: LCDClient, wallet: Wallet, msg: Msg) {
+export async function performTransaction(terra: LCDClient, wallet: Wallet, msg: Msg | Msg[]) {
   const tx = await createTransaction(wallet, msg)
   const { account_number, sequence } = await wallet.accountNumberAndSequence()
   const signedTx = await wallet.key.signTx(tx,
@@ -0,0 +1,246 @@
+import { LocalTerra, MnemonicKey, MsgExecuteContract } from "@terra-money/terra.js"
+import { strictEqual, strict as assert } from "assert"
+import { join } from "path"
+import 'dotenv/config.js'
+import {
+  deployContract,
+  executeContract,
+  Logger,
+  performTransaction,
+  queryContract,
+  setTimeoutDuration,
+  toEncodedBinary,
+  uploadContract
+} from "../helpers.js"
+import {
+  depositNative,
+  queryBalanceCw20,
+  setAssetOraclePriceSource
+} from "./test_helpers.js"
+
+// CONSTS
+
+// required environment variables:
+const CW_PLUS_ARTIFACTS_PATH = process.env.CW_PLUS_ARTIFACTS_PATH!
+
+const UUSD_COLLATERAL = 1_000_000_000000
+const MARS_COLLATERAL = 100_000_000000
+const MARS_PRICE = 2
+const MARS_MAX_LTV = 0.55
+
+// MAIN
+
+(async () => {
+  setTimeoutDuration(0)
+
+  const logger = new Logger()
+
+  const terra = new LocalTerra()
+  const deployer = terra.wallets.test1
+  const provider = terra.wallets.test2
+  const borrower = terra.wallets.test3
+  // mock contract addresses
+  const protocolRewardsCollector = new MnemonicKey().accAddress
+
+  console.log("upload contracts")
+
+  const addressProvider = await deployContract(terra, deployer, "../artifacts/mars_address_provider.wasm",
+    { owner: deployer.key.accAddress }
+  )
+
+  const incentives = await deployContract(terra, deployer, "../artifacts/mars_incentives.wasm",
+    {
+      owner: deployer.key.accAddress,
+      address_provider_address: addressProvider
+    }
+  )
+
+  const oracle = await deployContract(terra, deployer, "../artifacts/mars_oracle.wasm",
+    { owner: deployer.key.accAddress }
+  )
+
+  const maTokenCodeId = await uploadContract(terra, deployer, "../artifacts/mars_ma_token.wasm")
+
+  const redBank = await deployContract(terra, deployer, "../artifacts/mars_red_bank.wasm",
+    {
+      config: {
+        owner: deployer.key.accAddress,
+        address_provider_address: addressProvider,
+        safety_fund_fee_share: "0.1",
+        treasury_fee_share: "0.2",
+        ma_token_code_id: maTokenCodeId,
+        close_factor: "0.5",
+        dust_threshold_in_uusd: "1000000",
+        min_collateral_value_in_uusd: "0",
+        borrow_health_factor_buffer: "0",
+        max_markets_per_user: 0,
+        max_price_deviation: "0",
+        price_deviation_window: 0,
+        astroport_factory_address: new MnemonicKey().accAddress,
+      }
+    }
+  )
+
+  const mars = await deployContract(terra, deployer, join(CW_PLUS_ARTIFACTS_PATH, "cw20_base.wasm"),
+    {
+      name: "Mars",
+      symbol: "MARS",
+      decimals: 6,
+      initial_balances: [{ address: borrower.key.accAddress, amount: String(MARS_COLLATERAL) }],
+    }
+  )
+
+  await executeContract(terra, deployer, addressProvider,
+    {
+      update_config: {
+        config: {
+          owner: deployer.key.accAddress,
+          incentives_address: incentives,
+          mars_token_address: mars,
+          oracle_address: oracle,
+          protocol_rewards_collector_address: protocolRewardsCollector,
+          red_bank_address: redBank,
+          protocol_admin_address: deployer.key.accAddress,
+        }
+      }
+    },
+    { logger: logger }
+  )
+
+  console.log("init assets")
+
+  // mars
+  await executeContract(terra, deployer, redBank,
+    {
+      init_asset: {
+        asset: { cw20: { contract_addr: mars } },
+        asset_params: {
+          initial_borrow_rate: "0.1",
+          max_loan_to_value: String(MARS_MAX_LTV),
+          reserve_factor: "0.2",
+          liquidation_threshold: "0.65",
+          liquidation_bonus: "0.1",
+          interest_rate_model_params: {
+            linear: {
+              optimal_utilization_rate: "1",
+              base: "0",
+              slope_1: "1",
+              slope_2: "0",
+            }
+          },
+          active: true,
+          deposit_enabled: true,
+          borrow_enabled: true
+        }
+      }
+    },
+    { logger: logger }
+  )
+
+  await setAssetOraclePriceSource(terra, deployer, oracle,
+    { cw20: { contract_addr: mars } },
+    MARS_PRICE,
+    logger
+  )
+
+  // uusd
+  await executeContract(terra, deployer, redBank,
+    {
+      init_asset: {
+        asset: { native: { denom: "uusd" } },
+        asset_params: {
+          initial_borrow_rate: "0.2",
+          max_loan_to_value: "0.75",
+          reserve_factor: "0.2",
+          liquidation_threshold: "0.85",
+          liquidation_bonus: "0.1",
+          interest_rate_model_params: {
+            linear: {
+              optimal_utilization_rate: "1",
+              base: "0",
+              slope_1: "1",
+              slope_2: "0",
+            }
+          },
+          active: true,
+          deposit_enabled: true,
+          borrow_enabled: true
+        }
+      }
+    },
+    { logger: logger }
+  )
+
+  await setAssetOraclePriceSource(terra, deployer, oracle,
+    { native: { denom: "uusd" } },
+    1,
+    logger
+  )
+
+  const marsMarket = await queryContract(terra, redBank,
+    { market: { asset: { cw20: { contract_addr: mars } } } }
+  )
+  const maMars = marsMarket.market.ma_token_address
+
+  // TESTS
+
+  console.log("provide uusd")
+
+  await depositNative(terra, provider, redBank, "uusd", UUSD_COLLATERAL, logger)
+
+  // Messages in a transaction are executed in order and each one (including the submessages it
+  // dispatches, e.g. the maToken mint of a deposit) is committed before the next one runs, so a
+  // borrow following a deposit in the same transaction counts the freshly deposited collateral
+  const depositMsg = new MsgExecuteContract(borrower.key.accAddress, mars,
+    {
+      send: {
+        contract: redBank,
+        amount: String(MARS_COLLATERAL),
+        msg: toEncodedBinary({ deposit_cw20: {} })
+      }
+    }
+  )
+  const borrowMsg = (amount: number) => new MsgExecuteContract(borrower.key.accAddress, redBank,
+    {
+      borrow: {
+        asset: { native: { denom: "uusd" } },
+        amount: String(amount)
+      }
+    }
+  )
+
+  const maxBorrowAmount = MARS_COLLATERAL * MARS_PRICE * MARS_MAX_LTV
+
+  console.log("deposit mars and borrow more uusd than allowed in the same tx")
+
+  await assert.rejects(
+    performTransaction(terra, borrower, [depositMsg, borrowMsg(maxBorrowAmount + 1)]),
+    (error: any) => {
+      return error.response.data.message.includes(
+        "Borrow amount exceeds maximum allowed given current collateral value"
+      )
+    }
+  )
+
+  // the whole tx is reverted, including the deposit
+  strictEqual(await queryBalanceCw20(terra, borrower.key.accAddress, maMars), 0)
+  strictEqual(await queryBalanceCw20(terra, borrower.key.accAddress, mars), MARS_COLLATERAL)
+
+  console.log("deposit mars and borrow uusd in the same tx")
+
+  const borrowAmount = maxBorrowAmount - 1_000000
+  await performTransaction(terra, borrower, [depositMsg, borrowMsg(borrowAmount)])
+
+  strictEqual(await queryBalanceCw20(terra, borrower.key.accAddress, mars), 0)
+  assert(await queryBalanceCw20(terra, borrower.key.accAddress, maMars) > 0)
+
+  const userPosition = await queryContract(terra, redBank,
+    { user_position: { user_address: borrower.key.accAddress } }
+  )
+  assert(parseInt(userPosition.total_collateral_in_uusd) >= MARS_COLLATERAL * MARS_PRICE)
+  assert(parseInt(userPosition.total_debt_in_uusd) >= borrowAmount)
+
+  console.log("OK")
+
+  logger.showGasConsumption()
+})()
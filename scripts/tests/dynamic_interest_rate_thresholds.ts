@@ -35,7 +35,7 @@ async function queryBorrowRate(
   asset: Asset,
 ) {
   const market = await queryContract(terra, redBank, { market: { asset } })
-  return parseFloat(market.borrow_rate)
+  return parseFloat(market.market.borrow_rate)
 }
 
 // MAIN
@@ -81,6 +81,13 @@ async function queryBorrowRate(
         address_provider_address: addressProvider,
         ma_token_code_id: maTokenCodeId,
         close_factor: "0.5",
+        dust_threshold_in_uusd: "1000000",
+        min_collateral_value_in_uusd: "0",
+        borrow_health_factor_buffer: "0",
+        max_markets_per_user: 0,
+        max_price_deviation: "0",
+        price_deviation_window: 0,
+        astroport_factory_address: new MnemonicKey().accAddress,
       }
     }
   )
@@ -86,6 +86,8 @@ async function assertXmarsBalanceAt(
       config: {
         address_provider_address: addressProvider,
         proposal_voting_period: PROPOSAL_VOTING_PERIOD,
+        proposal_min_voting_period: 1,
+        proposal_max_voting_period: PROPOSAL_VOTING_PERIOD * 10,
         proposal_effective_delay: PROPOSAL_EFFECTIVE_DELAY,
         proposal_expiration_period: 3000,
         proposal_required_deposit: String(PROPOSAL_REQUIRED_DEPOSIT),
@@ -110,6 +112,13 @@ async function assertXmarsBalanceAt(
         treasury_fee_share: "0.2",
         ma_token_code_id: maTokenCodeId,
         close_factor: "0.5",
+        dust_threshold_in_uusd: "1000000",
+        min_collateral_value_in_uusd: "0",
+        borrow_health_factor_buffer: "0",
+        max_markets_per_user: 0,
+        max_price_deviation: "0",
+        price_deviation_window: 0,
+        astroport_factory_address: new MnemonicKey().accAddress,
       }
     }
   )
@@ -186,6 +195,7 @@ async function assertXmarsBalanceAt(
             title: "Init Luna",
             description: "Initialise Luna",
             link: "http://www.terra.money",
+            kind: "parameter_change",
             messages: [
               // init luna as an asset in the red bank contract
               {
@@ -267,6 +277,7 @@ async function assertXmarsBalanceAt(
           submit_proposal: {
             title: "Null",
             description: "An empty proposal",
+            kind: "text_only",
             execute_calls: []
           }
         })
@@ -317,7 +328,7 @@ async function assertXmarsBalanceAt(
   const aliceMarsBalanceAfter = await queryBalanceCw20(terra, alice.key.accAddress, mars)
   strictEqual(aliceMarsBalanceAfter, aliceMarsBalanceBefore + ALICE_PROPOSAL_DEPOSIT)
 
-  console.log("- bob's proposal was rejected, so his Mars deposit is sent to the staking contract")
+  console.log("- bob's proposal was rejected without reaching quorum, so his Mars deposit is sent to the staking contract")
 
   const bobMarsBalanceBefore = await queryBalanceCw20(terra, bob.key.accAddress, mars)
   const stakingContractMarsBalanceBefore = await queryBalanceCw20(terra, staking, mars)
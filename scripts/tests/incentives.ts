@@ -131,6 +131,13 @@ function assertBalance(
         treasury_fee_share: "0.2",
         ma_token_code_id: maTokenCodeId,
         close_factor: "0.5",
+        dust_threshold_in_uusd: "1000000",
+        min_collateral_value_in_uusd: "0",
+        borrow_health_factor_buffer: "0",
+        max_markets_per_user: 0,
+        max_price_deviation: "0",
+        price_deviation_window: 0,
+        astroport_factory_address: astroportFactory,
       }
     }
   )
@@ -593,6 +593,13 @@ async function testUncollateralizedNativeLoan(
         treasury_fee_share: "0.2",
         ma_token_code_id: maTokenCodeId,
         close_factor: String(CLOSE_FACTOR),
+        dust_threshold_in_uusd: "1000000",
+        min_collateral_value_in_uusd: "0",
+        borrow_health_factor_buffer: "0",
+        max_markets_per_user: 0,
+        max_price_deviation: "0",
+        price_deviation_window: 0,
+        astroport_factory_address: new MnemonicKey().accAddress,
       }
     }
   )
@@ -87,6 +87,8 @@ async function castVote(
       config: {
         address_provider_address: addressProvider,
         proposal_voting_period: PROPOSAL_VOTING_PERIOD,
+        proposal_min_voting_period: 1,
+        proposal_max_voting_period: PROPOSAL_VOTING_PERIOD * 10,
         proposal_effective_delay: PROPOSAL_EFFECTIVE_DELAY,
         proposal_expiration_period: 3000,
         proposal_required_deposit: String(PROPOSAL_REQUIRED_DEPOSIT),
@@ -184,6 +186,7 @@ async function castVote(
               title: "Migrate counter contract",
               description: "Migrate counter_version_one -> counter_version_two",
               link: "http://www.terra.money",
+              kind: "contract_upgrade",
               messages: [
                 {
                   execution_order: 1,
@@ -266,6 +269,7 @@ async function castVote(
               title: "Migrate council contract",
               description: "Migrate council -> counter_version_two",
               link: "http://www.terra.money",
+              kind: "contract_upgrade",
               messages: [
                 {
                   execution_order: 1,
@@ -6,7 +6,7 @@ sed -E -i .bak '/timeout_(propose|prevote|precommit|commit)/s/[0-9]+m?s/1500ms/'
 ```
 */
 
-import { LCDClient, LocalTerra } from "@terra-money/terra.js"
+import { LCDClient, LocalTerra, MnemonicKey } from "@terra-money/terra.js"
 import { strictEqual } from "assert"
 import { join } from "path"
 import 'dotenv/config.js'
@@ -101,6 +101,13 @@ async function waitUntilTerraOracleAvailable(terra: LCDClient) {
         treasury_fee_share: "0.2",
         ma_token_code_id: maTokenCodeId,
         close_factor: "0.5",
+        dust_threshold_in_uusd: "1000000",
+        min_collateral_value_in_uusd: "0",
+        borrow_health_factor_buffer: "0",
+        max_markets_per_user: 0,
+        max_price_deviation: "0",
+        price_deviation_window: 0,
+        astroport_factory_address: new MnemonicKey().accAddress,
       }
     }
   )
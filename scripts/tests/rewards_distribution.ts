@@ -705,6 +705,13 @@ async function testLiquidateCw20(env: Env, logger?: Logger) {
         address_provider_address: addressProvider,
         ma_token_code_id: maTokenCodeId,
         close_factor: "0.5",
+        dust_threshold_in_uusd: "1000000",
+        min_collateral_value_in_uusd: "0",
+        borrow_health_factor_buffer: "0",
+        max_markets_per_user: 0,
+        max_price_deviation: "0",
+        price_deviation_window: 0,
+        astroport_factory_address: new MnemonicKey().accAddress,
       }
     }
   )
@@ -732,8 +739,10 @@ async function testLiquidateCw20(env: Env, logger?: Logger) {
       config: {
         owner: deployer.key.accAddress,
         address_provider_address: addressProvider,
-        safety_fund_fee_share: String(SAFETY_FUND_FEE_SHARE),
-        treasury_fee_share: String(TREASURY_FEE_SHARE),
+        fee_recipients: [
+          { address: safetyFund, fee_share: String(SAFETY_FUND_FEE_SHARE) },
+          { address: treasury, fee_share: String(TREASURY_FEE_SHARE) },
+        ],
         astroport_factory_address: astroportFactory,
         astroport_max_spread: "0.05",
       }
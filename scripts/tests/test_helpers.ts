@@ -159,7 +159,7 @@ export async function queryMaAssetAddress(
   asset: Asset,
 ): Promise<string> {
   const market = await queryContract(terra, redBank, { market: { asset } })
-  return market.ma_token_address
+  return market.market.ma_token_address
 }
 
 export async function depositNative(
@@ -64,7 +64,7 @@ async function queryBorrowRate(
   asset: any,
 ) {
   const market = await queryContract(terra, redBank, { market: { asset } })
-  return parseFloat(market.borrow_rate)
+  return parseFloat(market.market.borrow_rate)
 }
 
 // TESTS
@@ -309,6 +309,13 @@ async function testDynamicInterestRate(env: Env, logger?: Logger) {
         treasury_fee_share: "0.2",
         ma_token_code_id: maTokenCodeId,
         close_factor: "0.5",
+        dust_threshold_in_uusd: "1000000",
+        min_collateral_value_in_uusd: "0",
+        borrow_health_factor_buffer: "0",
+        max_markets_per_user: 0,
+        max_price_deviation: "0",
+        price_deviation_window: 0,
+        astroport_factory_address: new MnemonicKey().accAddress,
       }
     }
   )
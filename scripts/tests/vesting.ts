@@ -123,6 +123,8 @@ const JOHN_WALLET_MARS_BALANCE = 600_000000; // Mars tokens in john's wallet
       config: {
         address_provider_address: addressProvider,
         proposal_voting_period: PROPOSAL_VOTING_PERIOD,
+        proposal_min_voting_period: 1,
+        proposal_max_voting_period: PROPOSAL_VOTING_PERIOD * 10,
         proposal_effective_delay: PROPOSAL_EFFECTIVE_DELAY,
         proposal_expiration_period: 3000,
         proposal_required_deposit: String(PROPOSAL_REQUIRED_DEPOSIT),
@@ -307,6 +309,7 @@ const JOHN_WALLET_MARS_BALANCE = 600_000000; // Mars tokens in john's wallet
             title: "Test 1",
             description: "This is a test",
             link: "https://twitter.com/",
+            kind: "text_only",
             messages: [],
           },
         }),
@@ -373,6 +376,7 @@ const JOHN_WALLET_MARS_BALANCE = 600_000000; // Mars tokens in john's wallet
             title: "Test 2",
             description: "This is a test",
             link: "https://twitter.com/",
+            kind: "text_only",
             messages: [],
           },
         }),
@@ -416,6 +420,7 @@ const JOHN_WALLET_MARS_BALANCE = 600_000000; // Mars tokens in john's wallet
             title: "Test 3",
             description: "This is a test",
             link: "https://twitter.com/",
+            kind: "text_only",
             messages: [],
           },
         }),
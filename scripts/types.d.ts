@@ -2,6 +2,8 @@ interface CouncilInitMsg {
   config: {
     address_provider_address?: string
     proposal_voting_period: number
+    proposal_min_voting_period: number
+    proposal_max_voting_period: number
     proposal_effective_delay: number
     proposal_expiration_period: number
     proposal_required_deposit: string
@@ -42,14 +44,18 @@ interface TreasuryInitMsg {
   owner?: string
   astroport_factory_address?: string
   astroport_max_spread: string
+  spending_limit_epoch_blocks: number
+  spending_limit_update_delay_blocks: number
 }
 
 interface ProtocolRewardsCollectorInitMsg {
   config: {
     owner?: string,
     address_provider_address?: string,
-    safety_fund_fee_share: string,
-    treasury_fee_share: string,
+    fee_recipients: {
+      address?: string,
+      fee_share: string,
+    }[],
     astroport_factory_address?: string,
     astroport_max_spread: string,
   }
@@ -61,6 +67,14 @@ interface RedBankInitMsg {
     address_provider_address?: string
     ma_token_code_id?: number
     close_factor: string
+    dust_threshold_in_uusd: string
+    min_collateral_value_in_uusd: string
+    borrow_health_factor_buffer: string
+    max_markets_per_user: number,
+    max_price_deviation: string,
+    price_deviation_window: number,
+    astroport_factory_address?: string
+    update_cooldown_seconds?: number
   }
 }
 